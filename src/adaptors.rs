@@ -0,0 +1,340 @@
+//! General iterator adaptors, which prepare the input iterators for the joins or post-process
+//! their results.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::Peekable;
+#[cfg(feature = "std")]
+use std::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::{self, Vec};
+
+/// See [`with_key()`](trait.Joinkit.html#method.with_key) for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct WithKey<I, F> {
+    iter: I,
+    key: F,
+}
+
+impl<I, F> WithKey<I, F> {
+    /// Create a `WithKey` iterator.
+    pub fn new(iter: I, key: F) -> Self {
+        WithKey {
+            iter,
+            key,
+        }
+    }
+}
+
+impl<I, K, F> Iterator for WithKey<I, F>
+    where I: Iterator,
+          F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|v| ((self.key)(&v), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// The error yielded by [`check_sorted_by()`](trait.Joinkit.html#method.check_sorted_by) for an
+/// item ordered before its predecessor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfOrder<T> {
+    /// the index (in base0) of the item in the input iterator
+    pub index: usize,
+    /// the out-of-order item
+    pub item: T,
+}
+
+impl<T> fmt::Display for OutOfOrder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the item at index {} is ordered before its predecessor", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> ::std::error::Error for OutOfOrder<T> {}
+
+/// See [`check_sorted_by()`](trait.Joinkit.html#method.check_sorted_by) for the description and
+/// examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct CheckSortedBy<I, F> 
+    where I: Iterator,
+{
+    iter: I,
+    /// the item following the last yielded one, along with whether it is out of order; `None` if
+    /// not fetched yet
+    next: Option<Option<(I::Item, bool)>>,
+    cmp: F,
+    index: usize,
+}
+
+impl<I, F> CheckSortedBy<I, F> 
+    where I: Iterator,
+{
+    /// Create a `CheckSortedBy` iterator.
+    pub fn new(iter: I, cmp: F) -> Self {
+        CheckSortedBy {
+            iter,
+            next: None,
+            cmp,
+            index: 0,
+        }
+    }
+}
+
+impl<I, F> Iterator for CheckSortedBy<I, F>
+    where I: Iterator,
+          F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = Result<I::Item, OutOfOrder<I::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cur, out_of_order) = match self.next.take() {
+            Some(next) => next?,
+            None => (self.iter.next()?, false),
+        };
+        // the item is compared with its successor while it is still owned
+        let next = match self.iter.next() {
+            Some(n) => {
+                let out_of_order = (self.cmp)(&cur, &n) == Ordering::Greater;
+                Some((n, out_of_order))
+            },
+            None => None,
+        };
+        self.next = Some(next);
+        let index = self.index;
+        self.index += 1;
+        if out_of_order {
+            Some(Err(OutOfOrder { index, item: cur }))
+        } else {
+            Some(Ok(cur))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.iter.size_hint();
+        match self.next {
+            Some(Some(_)) => (lo.saturating_add(1), hi.and_then(|h| h.checked_add(1))),
+            Some(None) => (0, Some(0)),
+            None => (lo, hi),
+        }
+    }
+}
+
+/// See [`assert_sorted_by()`](trait.Joinkit.html#method.assert_sorted_by) for the description
+/// and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct AssertSortedBy<I, F> 
+    where I: Iterator,
+{
+    iter: CheckSortedBy<I, F>,
+}
+
+impl<I, F> AssertSortedBy<I, F> 
+    where I: Iterator,
+{
+    /// Create an `AssertSortedBy` iterator.
+    pub fn new(iter: I, cmp: F) -> Self {
+        AssertSortedBy {
+            iter: CheckSortedBy::new(iter, cmp),
+        }
+    }
+}
+
+impl<I, F> Iterator for AssertSortedBy<I, F>
+    where I: Iterator,
+          F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next()? {
+            Ok(v) => Some(v),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [`chain_sorted_by()`](trait.Joinkit.html#method.chain_sorted_by) for the description and
+/// examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct ChainSortedBy<L, R, F> 
+    where L: Iterator,
+          R: Iterator<Item=L::Item>,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+}
+
+impl<L, R, F> ChainSortedBy<L, R, F> 
+    where L: Iterator,
+          R: Iterator<Item=L::Item>,
+{
+    /// Create a `ChainSortedBy` iterator.
+    pub fn new(left: L, right: R, cmp: F) -> Self {
+        ChainSortedBy {
+            left: left.peekable(),
+            right: right.peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<L, R, F> Iterator for ChainSortedBy<L, R, F>
+    where L: Iterator,
+          R: Iterator<Item=L::Item>,
+          F: FnMut(&L::Item, &L::Item) -> Ordering,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let take_left = match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) => (self.cmp)(l, r) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if take_left {
+            self.left.next()
+        } else {
+            self.right.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (l_lo, l_hi) = self.left.size_hint();
+        let (r_lo, r_hi) = self.right.size_hint();
+        let hi = match (l_hi, r_hi) {
+            (Some(l), Some(r)) => l.checked_add(r),
+            _ => None,
+        };
+        (l_lo.saturating_add(r_lo), hi)
+    }
+}
+
+/// See [`ungroup()`](trait.Joinkit.html#method.ungroup) for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct Ungroup<I, K, V> {
+    iter: I,
+    /// the key and the remaining values of the current group
+    group: Option<(K, vec::IntoIter<V>)>,
+}
+
+impl<I, K, V> Ungroup<I, K, V> {
+    /// Create an `Ungroup` iterator.
+    pub fn new(iter: I) -> Self {
+        Ungroup {
+            iter,
+            group: None,
+        }
+    }
+}
+
+impl<I, K, V> Iterator for Ungroup<I, K, V>
+    where I: Iterator<Item=(K, Vec<V>)>,
+          K: Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let last = match self.group {
+                Some((_, ref vs)) if vs.len() > 1 => false,
+                Some((_, ref vs)) if vs.len() == 1 => true,
+                _ => match self.iter.next() {
+                    Some((k, vv)) => {
+                        self.group = Some((k, vv.into_iter()));
+                        continue;
+                    }
+                    None => return None,
+                },
+            };
+            // the key is moved out with the last value of its group, instead of cloned
+            if last {
+                return self.group.take().and_then(|(k, mut vs)| vs.next().map(|v| (k, v)));
+            }
+            if let Some((ref k, ref mut vs)) = self.group {
+                return vs.next().map(|v| (k.clone(), v));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // the groups of the input iterator may be empty or arbitrarily large
+        let buffered = self.group.as_ref().map_or(0, |g| g.1.len());
+        match self.iter.size_hint() {
+            (_, Some(0)) => (buffered, Some(buffered)),
+            _ => (buffered, None),
+        }
+    }
+}
+
+/// See [`group_joined_by_key()`](trait.Joinkit.html#method.group_joined_by_key) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct GroupJoinedByKey<I, K, F> 
+    where I: Iterator,
+{
+    iter: I,
+    key: F,
+    /// the first item of the next group along with its key
+    head: Option<(K, I::Item)>,
+}
+
+impl<I, K, F> GroupJoinedByKey<I, K, F> 
+    where I: Iterator,
+{
+    /// Create a `GroupJoinedByKey` iterator.
+    pub fn new(iter: I, key: F) -> Self {
+        GroupJoinedByKey {
+            iter,
+            key,
+            head: None,
+        }
+    }
+}
+
+impl<I, K, F> Iterator for GroupJoinedByKey<I, K, F>
+    where I: Iterator,
+          K: PartialEq,
+          F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (k, first) = match self.head.take() {
+            Some(head) => head,
+            None => {
+                let v = self.iter.next()?;
+                ((self.key)(&v), v)
+            },
+        };
+        let mut group = vec![first];
+        for v in self.iter.by_ref() {
+            let vk = (self.key)(&v);
+            if vk != k {
+                self.head = Some((vk, v));
+                break;
+            }
+            group.push(v);
+        }
+        Some((k, group))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.head.is_some() as usize;
+        let (lower, upper) = self.iter.size_hint();
+        // at least one group, if there is any item, and at most one group per item
+        ((buffered + lower).min(1), upper.and_then(|u| u.checked_add(buffered)))
+    }
+}
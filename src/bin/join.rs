@@ -0,0 +1,7 @@
+extern crate joinkit;
+
+use joinkit::cli;
+
+fn main() {
+    cli::main("join", "Join records of two files using the Hash Join or the Merge Join strategy.", None);
+}
@@ -0,0 +1,2408 @@
+//! The command line interface shared by the `join`, `hjoin` and `mjoin` binaries.
+//!
+//! The binaries differ only in the join strategy: `hjoin` always uses the Hash Join, `mjoin`
+//! always uses the Merge Join and `join` lets the user choose it with `--strategy`. Everything
+//! else - the arguments, the reading of the input files and the key extraction - is defined here
+//! once.
+
+use std::io::{self, Write, BufWriter, stderr,};
+use std::borrow::Cow;
+use std::cmp::{self, Ordering};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::iter;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::thread;
+use std::fs::File;
+use std::process;
+use std::time::Instant;
+use clap::{Arg, App, ArgMatches,};
+#[cfg(feature = "regex")]
+use regex::Regex;
+use super::{Joinkit, JoinMode, Strategy, OverflowAction, group_into_map, HashJoinInner, HashJoinLeftExcl,
+            HashJoinLeftOuter, HashJoinRightExcl, HashJoinRightOuter, HashJoinRightOuterFlat,
+            HashJoinFullOuter};
+use super::EitherOrBoth::{Left, Both, Right};
+use util::{self, Separator};
+
+const STRATEGIES: [&str; 2] = ["hash", "merge"];
+
+/// The order of the keys in both input files of the Merge Join, see `--descending`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    /// Compares the keys, so that the key coming first in the files is the lesser one.
+    fn cmp<K: Ord>(self, l: &K, r: &K) -> Ordering {
+        match self {
+            SortOrder::Ascending => Ord::cmp(l, r),
+            SortOrder::Descending => Ord::cmp(r, l),
+        }
+    }
+}
+
+const FIELDS1_HELP: &str = concat!("Join on these comma-separated FIELDS of FILE1. ",
+                                   "The index starts with 1 and must not contain duplicates. ",
+                                   "It can optionally contain a flag to convert the given key to a ",
+                                   "number, e.g. '1-i,2,3-u'. ",
+                                   "A contiguous block of fields can be given as a range, e.g. ",
+                                   "'3:6-u'. ",
+                                   "Since strings are compared lexicographically, they are not ",
+                                   "suitable for numbers, e.g. `2` would be greater than `12`. ",
+                                   "The recognized flags are: -u: convert to unsigned int 64 ",
+                                   "-i: convert to signed int 64. ",
+                                   "The keys of both files must have the same number of fields ",
+                                   "with the same flags at the same positions, otherwise they ",
+                                   "would never compare equal.");
+
+const FIELDS2_HELP: &str = concat!("Join on these comma-separated FIELDS of FILE2. ",
+                                   "The index starts with 1 and must not contain duplicates. ",
+                                   "It can optionally contain a flag to convert the given key to a ",
+                                   "number, e.g. '1-i,2,3-u'. ",
+                                   "A contiguous block of fields can be given as a range, e.g. ",
+                                   "'3:6-u'. ",
+                                   "Since strings are compared lexicographically, they are not ",
+                                   "suitable for numbers, e.g. `2` would be greater than `12`. ",
+                                   "The recognized flags are: -u: convert to unsigned int 64 ",
+                                   "-i: convert to signed int 64. ",
+                                   "The keys of both files must have the same number of fields ",
+                                   "with the same flags at the same positions, otherwise they ",
+                                   "would never compare equal.");
+
+/// Builds the command line application.
+///
+/// If the `strategy` is `None`, the application takes the `--strategy` argument. The
+/// `--validate-unique`, `--check-sorted` and `--descending` arguments are available only if the
+/// Merge Join can be selected, the `--max-keys` and `--parallel` arguments only if the Hash Join
+/// can be selected.
+pub fn app<'a, 'b>(name: &str, about: &'b str, strategy: Option<Strategy>) -> App<'a, 'b> {
+    let app = App::new(name)
+        .version(crate_version!())
+        .author("Milan Opath <milan.opath@gmail.com>")
+        .about(about)
+        .arg(Arg::with_name("FIELDS1")
+            .help(FIELDS1_HELP)
+            .short("1")
+            .default_value("1")
+            .use_delimiter(true))
+        .arg(Arg::with_name("FIELDS2")
+            .help(FIELDS2_HELP)
+            .short("2")
+            .default_value("1")
+            .use_delimiter(true))
+        .arg(Arg::with_name("in-rec-sep")
+            .help("Input record separator - must be encodable as a single byte in utf8.")
+            .short("R")
+            .long("in-rec-sep")
+            .takes_value(true))
+        .arg(Arg::with_name("in-field-sep")
+            .help("Input field separator - can be any string.")
+            .short("F")
+            .long("in-field-sep")
+            .takes_value(true)
+            .empty_values(false))
+        .arg(Arg::with_name("auto-sep")
+            .help("Detect the input field separator of each file from its first record: a tab if \
+                  the record contains any, otherwise a comma.")
+            .long("auto-sep")
+            .conflicts_with_all(&["in-field-sep", "in-field-sep-left", "in-field-sep-right"]))
+        .arg(Arg::with_name("in-rec-sep-left")
+            .help("Left input file record separator - must be encodable as a single byte in utf8.")
+            .long("in-rec-sep-left")
+            .conflicts_with("in-rec-sep")
+            .requires("in-rec-sep-right")
+            .takes_value(true))
+        .arg(Arg::with_name("in-field-sep-left")
+            .help("Left input file field separator - can be any string.")
+            .long("in-field-sep-left")
+            .conflicts_with("in-field-sep")
+            .requires("in-field-sep-right")
+            .takes_value(true)
+            .empty_values(false))
+        .arg(Arg::with_name("in-rec-sep-right")
+            .help("Right input file record separator - must be encodable as a single byte in utf8.")
+            .long("in-rec-sep-right")
+            .conflicts_with("in-rec-sep")
+            .requires("in-rec-sep-left")
+            .takes_value(true))
+        .arg(Arg::with_name("in-field-sep-right")
+            .help("Right input file field separator - can be any string.")
+            .long("in-field-sep-right")
+            .conflicts_with("in-field-sep")
+            .requires("in-field-sep-left")
+            .takes_value(true)
+            .empty_values(false))
+        .arg(Arg::with_name("out-rec-sep")
+            .help("Output record separator - if not specified, it is equal to in-rec-sep. \
+                  The escapes '\\t', '\\n', '\\r', '\\0' and '\\\\' are interpreted.")
+            .long("out-rec-sep")
+            .takes_value(true))
+        .arg(Arg::with_name("normalize-eol")
+            .help("Write the records terminated by EOL, 'lf' or 'crlf', and strip a trailing '\\r' \
+                  from each input record, e.g. of a CRLF file split on the default '\\n' \
+                  in-rec-sep, so it does not end up in the last field. Replaces out-rec-sep.")
+            .long("normalize-eol")
+            .value_name("EOL")
+            .possible_values(&["lf", "crlf"])
+            .takes_value(true)
+            .conflicts_with("out-rec-sep"))
+        .arg(Arg::with_name("out-field-sep")
+            .help("Output field separator - if not specified, it is equal to in-field-sep. \
+                  The escapes '\\t', '\\n', '\\r', '\\0' and '\\\\' are interpreted.")
+            .long("out-field-sep")
+            .takes_value(true))
+        .arg(Arg::with_name("mode")
+            .help("Join mode.")
+            .short("m")
+            .long("mode")
+            .possible_values(&JoinMode::NAMES)
+            .takes_value(true))
+        .arg(Arg::with_name("keep-empty")
+            .help("Keep the empty records in between the non-empty ones. The empty records at the \
+                  end of the input are always skipped.")
+            .long("keep-empty"))
+        .arg(Arg::with_name("quoted")
+            .help("Do not split the records and the fields on the separators enclosed in double \
+                  quotes, e.g. the newlines inside the quoted fields of a CSV file. The key fields \
+                  are compared without their quotes.")
+            .long("quoted"))
+        .arg(Arg::with_name("header")
+            .help("Treat the first record of each file as a header, which is not joined, but written \
+                  to the output instead.")
+            .long("header"))
+        .arg(Arg::with_name("by")
+            .help("Join on these comma-separated column NAMES of both files, as found in their \
+                  headers. Overrides FIELDS1 and FIELDS2.")
+            .long("by")
+            .requires("header")
+            .takes_value(true)
+            .use_delimiter(true))
+        .arg(Arg::with_name("left-by")
+            .help("Join on these comma-separated column NAMES of FILE1, as found in its header. \
+                  Overrides FIELDS1.")
+            .long("left-by")
+            .conflicts_with("by")
+            .requires("header")
+            .takes_value(true)
+            .use_delimiter(true))
+        .arg(Arg::with_name("right-by")
+            .help("Join on these comma-separated column NAMES of FILE2, as found in its header. \
+                  Overrides FIELDS2.")
+            .long("right-by")
+            .conflicts_with("by")
+            .requires("header")
+            .takes_value(true)
+            .use_delimiter(true))
+        .arg(Arg::with_name("skip-empty-keys")
+            .help("Drop the records with an empty key field before joining, so they are not \
+                  written even by the outer joins.")
+            .long("skip-empty-keys"))
+        .arg(Arg::with_name("empty-as-null")
+            .help("Treat a key with an empty field as null, which matches no key, not even another \
+                  null. The records with a null key are written only by the modes keeping the \
+                  unmatched records of their file, after the joined records.")
+            .long("empty-as-null")
+            .conflicts_with("skip-empty-keys"))
+        .arg(Arg::with_name("expect-fields")
+            .help("Check each record of both files has exactly N fields, or at least N fields if \
+                  given as 'N+', before its key is extracted. The first record failing the check is \
+                  reported and the join is aborted.")
+            .long("expect-fields")
+            .value_name("N")
+            .takes_value(true))
+        .arg(Arg::with_name("expect-fields-left")
+            .help("The same as --expect-fields for FILE1 only, overriding it.")
+            .long("expect-fields-left")
+            .value_name("N")
+            .takes_value(true))
+        .arg(Arg::with_name("expect-fields-right")
+            .help("The same as --expect-fields for FILE2 only, overriding it.")
+            .long("expect-fields-right")
+            .value_name("N")
+            .takes_value(true))
+        .arg(Arg::with_name("bad-number")
+            .help("What to do with a record, whose numeric key field (-i or -u) is not a number: \
+                  'skip' drops the record, 'error' aborts with the record's key field, 'zero' \
+                  joins the key field as zero, so all such records match each other and the zero \
+                  key.")
+            .long("bad-number")
+            .possible_values(&["skip", "error", "zero"])
+            .takes_value(true))
+        .arg(Arg::with_name("strict")
+            .help("Abort on the first record, whose numeric key field (-i or -u) is not a number \
+                  written in its canonical form, e.g. '+1', '007' or '-0', which would be joined \
+                  as the number silently otherwise.")
+            .long("strict")
+            .conflicts_with("bad-number"))
+        .arg(Arg::with_name("coalesce")
+            .help("Write a single record for each matched pair, coalesced from the fields of both \
+                  records by their position: the left field is preferred, unless it is empty. The \
+                  unmatched records are written without padding.")
+            .long("coalesce"))
+        .arg(Arg::with_name("left-excl-out")
+            .help("Write the unmatched records of FILE1 into FILE in the same pass, as in the \
+                  'left-excl' mode, while the matched ones are written to the standard output. \
+                  Requires the 'inner' mode.")
+            .long("left-excl-out")
+            .value_name("FILE")
+            .takes_value(true)
+            .conflicts_with_all(&["summary", "agg"]))
+        .arg(Arg::with_name("report-unmatched")
+            .help("Write the unmatched records of both files to the standard error, prefixed by \
+                  'FILE1: ' or 'FILE2: ', while the matched ones are written to the standard output. \
+                  Requires the 'inner' mode.")
+            .long("report-unmatched")
+            .conflicts_with_all(&["left-excl-out", "summary", "agg"]))
+        .arg(Arg::with_name("emit-key")
+            .help("Write the join key as the leading field(s) of each output record, one field per \
+                  key component. The numeric keys are written as parsed, e.g. '007' as '7'.")
+            .long("emit-key"))
+        .arg(Arg::with_name("with-fanout")
+            .help("Write the fanout of each output record as its leading field, before the key \
+                  fields of --emit-key: the number of the records of FILE2 matched by its record \
+                  of FILE1, or 0 for an unmatched record.")
+            .long("with-fanout")
+            .conflicts_with_all(&["summary", "agg"]))
+        .arg(Arg::with_name("project")
+            .help("Write only the comma-separated output fields of LIST, in the given order. A \
+                  field is referenced as 'FILE.FIELD', e.g. '1.3' for the 3rd field of FILE1, or \
+                  by the column name with --header, e.g. '2.amount'. A constant field is given as \
+                  '=VALUE'. With --header, each field can be renamed in the output header as \
+                  'FIELD:NAME', e.g. '2.3:amount' or '=EUR:currency'. The fields of the missing \
+                  record of an outer join are empty.")
+            .long("project")
+            .value_name("LIST")
+            .takes_value(true)
+            .conflicts_with_all(&["coalesce", "emit-key"]))
+        .arg(Arg::with_name("max-group")
+            .help("Fail if a key has more than N records in either file, guarding against an \
+                  accidental cartesian product of the matched records. With the 'hash' strategy, \
+                  only the records of FILE2 are grouped by the key, so only those are counted. \
+                  With the 'merge' strategy, at most N + 1 records of a key are buffered. \
+                  The exclusive modes, which do not pair the records, are not checked.")
+            .long("max-group")
+            .value_name("N")
+            .takes_value(true))
+        .arg(Arg::with_name("on-max-group")
+            .help("What to do with a key exceeding --max-group: 'abort' (the default) fails with \
+                  the key, 'skip' drops the records of the key in that file with a warning, as if \
+                  they were not in the input.")
+            .long("on-max-group")
+            .possible_values(&["abort", "skip"])
+            .takes_value(true)
+            .requires("max-group"))
+        .arg(Arg::with_name("limit")
+            .help("Stop after writing N records (not counting the header).")
+            .long("limit")
+            .value_name("N")
+            .takes_value(true))
+        .arg(Arg::with_name("unbuffered")
+            .help("Flush the output after each record, e.g. for interactive pipelines.")
+            .long("unbuffered"))
+        .arg(Arg::with_name("flush-every")
+            .help("Flush the output after every N records, trading the latency for fewer writes. \
+                  By default, the output is flushed only when the buffer is full and at the end.")
+            .long("flush-every")
+            .value_name("N")
+            .takes_value(true)
+            .conflicts_with("unbuffered"))
+        .arg(Arg::with_name("progress")
+            .help("Report the number of the left records read and of the records written, along \
+                  with the elapsed time, to stderr every 100000 left records and at the end.")
+            .long("progress"))
+        .arg(Arg::with_name("explain")
+            .help("Print the resolved key fields, separators, mode and strategy to stderr and exit \
+                  without joining. Only the headers are read, if required.")
+            .long("explain"))
+        .arg(Arg::with_name("capabilities")
+            .help("Print the capabilities of this build to stdout, one 'KIND:NAME' per line, and exit: \
+                  the join strategies, modes and key data types, the options depending on them, \
+                  and the optional cargo features compiled in. The input files are not required.")
+            .long("capabilities"))
+        .arg(Arg::with_name("summary")
+            .help("Print the statistics of the join instead of the joined records: the number of \
+                  the records and the distinct keys of each file, the number of the matched keys, \
+                  the largest key group of each file and the number of the records the join would \
+                  write in the given mode. The output options are ignored.")
+            .long("summary")
+            .conflicts_with_all(&["explain", "empty-as-null"]))
+        .arg(Arg::with_name("agg")
+            .help("Write a single record per matched key instead of the joined records: the key \
+                  fields followed by the aggregate of the joined pairs. Only 'count', the number \
+                  of the pairs, is supported, and only in the inner mode.")
+            .long("agg")
+            .value_name("AGG")
+            .possible_values(&["count"])
+            .takes_value(true)
+            .conflicts_with_all(&["summary", "emit-key", "project", "coalesce"]))
+        .arg(Arg::with_name("FILE1")
+            .help("The left input file.")
+            .required_unless("capabilities")
+            .index(1))
+        .arg(Arg::with_name("FILE2")
+            .help("The right input file.")
+            .required_unless("capabilities")
+            .index(2));
+    #[cfg(feature = "regex")]
+    let app = app.arg(Arg::with_name("field-sep-regex")
+        .help("Input field separator of both files as a regular expression, e.g. '\\s+' for the \
+              fields separated by irregular whitespace. The matches at the start and at the end of \
+              a record are ignored. If not specified, the output field separator is a tab. It is \
+              considerably slower than a literal separator.")
+        .long("field-sep-regex")
+        .value_name("PATTERN")
+        .conflicts_with_all(&["in-field-sep", "in-field-sep-left", "in-field-sep-right", "auto-sep"])
+        .takes_value(true));
+    let app = match strategy {
+        None => app.arg(Arg::with_name("strategy")
+            .help("Join strategy: 'hash' does not require sorted input, but loads FILE2 into \
+                  memory; 'merge' requires both files sorted on the key.")
+            .short("s")
+            .long("strategy")
+            .possible_values(&STRATEGIES)
+            .default_value("hash")),
+        Some(_) => app,
+    };
+    let app = match strategy {
+        Some(Strategy::Merge) => app,
+        _ => app.arg(Arg::with_name("max-keys")
+            .help("Fail as soon as more than N distinct keys of FILE2 are loaded into memory. \
+                  Requires the 'hash' strategy.")
+            .long("max-keys")
+            .value_name("N")
+            .takes_value(true))
+            .arg(Arg::with_name("parallel")
+            .help("Build and probe the in-memory map by N worker threads, the number of CPUs if \
+                  not given. The output is the same as without it. Requires the 'hash' strategy.")
+            .long("parallel")
+            .value_name("N")
+            .takes_value(true)
+            .min_values(0)
+            .require_equals(true)
+            .conflicts_with_all(&["max-keys", "summary", "agg"]))
+            .arg(Arg::with_name("auto-build-side")
+            .help("Build the in-memory map from the smaller of the input files, FILE1 or FILE2, \
+                  by their size. The fields of FILE1 are still written first, but the order of \
+                  the records follows FILE2, if the map is built from FILE1. Both files must be \
+                  regular files. It cannot be combined with --max-group and --max-keys, which \
+                  guard FILE2. Requires the 'hash' strategy.")
+            .long("auto-build-side")
+            .conflicts_with_all(&["summary", "agg", "with-fanout", "max-group", "max-keys"])),
+    };
+    #[cfg(feature = "collation")]
+    let app = match strategy {
+        Some(Strategy::Hash) => app,
+        _ => app.arg(Arg::with_name("collation")
+            .help("Compare the string keys under the comma-separated COLLATIONS, as the input \
+                  files are sorted: 'nocase' compares the letters converted to the upper case, as \
+                  'LC_ALL=C sort -f', 'noaccent' compares the accented Latin letters as their base \
+                  letters. The keys equal under the collation are matched. Requires the 'merge' \
+                  strategy.")
+            .long("collation")
+            .value_name("COLLATIONS")
+            .possible_values(&["nocase", "noaccent"])
+            .use_delimiter(true)
+            .takes_value(true)
+            .conflicts_with_all(&["summary", "agg"])),
+    };
+    match strategy {
+        Some(Strategy::Hash) => app,
+        _ => app.arg(Arg::with_name("validate-unique")
+            .help("Fail if any of the input files contains consecutive records with equal keys. \
+                  Requires the 'merge' strategy.")
+            .long("validate-unique"))
+            .arg(Arg::with_name("check-sorted")
+            .help("Fail if any of the input files is not sorted on the key, in the order given by \
+                  --descending. Requires the 'merge' strategy.")
+            .long("check-sorted"))
+            .arg(Arg::with_name("descending")
+            .help("Both input files are sorted on the key in descending order instead of the \
+                  ascending one. Requires the 'merge' strategy.")
+            .long("descending")),
+    }
+}
+
+/// One side of the join.
+struct Input<'a, I> {
+    /// the file name used in the error messages
+    label: &'static str,
+    records: I,
+    field_sep: &'a InFieldSep<'a>,
+    key_idx: Vec<(usize, isize, util::DataType)>,
+    /// whether the unparsable numeric key fields are joined as zero
+    zero_bad_numbers: bool,
+}
+
+/// The input field separator.
+#[derive(Debug, Clone)]
+enum InFieldSep<'a> {
+    Literal(&'a str),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+    /// the separator does not split the fields enclosed in the quote
+    Quoted(Box<InFieldSep<'a>>, char),
+}
+
+impl<'a> util::Separator for InFieldSep<'a> {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        match *self {
+            InFieldSep::Literal(sep) => sep.find_in(s),
+            #[cfg(feature = "regex")]
+            InFieldSep::Regex(ref re) => re.find_in(s),
+            InFieldSep::Quoted(ref sep, _) => sep.find_in(s),
+        }
+    }
+
+    fn trim_record<'r>(&self, record: &'r str) -> &'r str {
+        match *self {
+            InFieldSep::Literal(sep) => sep.trim_record(record),
+            #[cfg(feature = "regex")]
+            InFieldSep::Regex(ref re) => re.trim_record(record),
+            InFieldSep::Quoted(ref sep, _) => sep.trim_record(record),
+        }
+    }
+
+    fn quote(&self) -> Option<char> {
+        match *self {
+            InFieldSep::Quoted(_, q) => Some(q),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for InFieldSep<'a> {
+    /// Displays the escaped separator, e.g. `"\t"` or `regex "\\s+"`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InFieldSep::Literal(sep) => write!(f, "{:?}", sep),
+            #[cfg(feature = "regex")]
+            InFieldSep::Regex(ref re) => write!(f, "regex {:?}", re.as_str()),
+            InFieldSep::Quoted(ref sep, _) => sep.fmt(f),
+        }
+    }
+}
+
+/// Returns the input field separators of the left and the right file, along with the default
+/// output field separator, if it differs from the input one.
+#[cfg(feature = "regex")]
+fn in_field_seps<'a>(matches: &ArgMatches, left: &'a str, right: &'a str) 
+                     -> (InFieldSep<'a>, InFieldSep<'a>, Option<&'static str>) {
+    match matches.value_of("field-sep-regex") {
+        Some(pattern) => match Regex::new(pattern) {
+            // there is no literal separator to write, so the fields are separated by a tab
+            Ok(re) => (InFieldSep::Regex(re.clone()), InFieldSep::Regex(re), Some("\t")),
+            Err(e) => {
+                writeln!(&mut stderr(), "Error: invalid field separator regex: {}", e).unwrap();
+                process::exit(1);
+            },
+        },
+        None => (InFieldSep::Literal(left), InFieldSep::Literal(right), None),
+    }
+}
+
+/// Returns the input field separators of the left and the right file, along with the default
+/// output field separator, if it differs from the input one.
+#[cfg(not(feature = "regex"))]
+fn in_field_seps<'a>(_: &ArgMatches, left: &'a str, right: &'a str) 
+                     -> (InFieldSep<'a>, InFieldSep<'a>, Option<&'static str>) {
+    (InFieldSep::Literal(left), InFieldSep::Literal(right), None)
+}
+
+/// Returns the collation of the string keys of the merge join, if any.
+#[cfg(feature = "collation")]
+fn collation(matches: &ArgMatches) -> Option<util::Collation> {
+    matches.values_of("collation").map(|names| {
+        let mut c = util::Collation::default();
+        for name in names {
+            match name {
+                "nocase" => c.fold_case = true,
+                _ => c.fold_accents = true,
+            }
+        }
+        c
+    })
+}
+
+/// Returns the collation of the string keys of the merge join, if any.
+#[cfg(not(feature = "collation"))]
+fn collation(_: &ArgMatches) -> Option<util::Collation> {
+    None
+}
+
+/// Parses the command line arguments and joins the input files accordingly.
+///
+/// If the `strategy` is `None`, it is taken from the `--strategy` argument.
+pub fn main(name: &str, about: &str, strategy: Option<Strategy>) {
+    let matches = app(name, about, strategy).get_matches();
+    if matches.is_present("capabilities") {
+        let mut out = io::stdout();
+        for c in capabilities(strategy) {
+            writeln!(&mut out, "{}", c).expect("Error: could not write into output stream!");
+        }
+        return;
+    }
+    let strategy = strategy.unwrap_or_else(|| match matches.value_of("strategy") {
+        Some("merge") => Strategy::Merge,
+        _ => Strategy::Hash,
+    });
+    if strategy == Strategy::Merge {
+        for arg in &["max-keys", "parallel", "auto-build-side"] {
+            if matches.is_present(arg) {
+                writeln!(&mut stderr(), "Error: --{} requires the 'hash' strategy", arg).unwrap();
+                process::exit(1);
+            }
+        }
+    }
+    if strategy == Strategy::Hash {
+        for arg in &["validate-unique", "check-sorted", "descending", "collation"] {
+            if matches.is_present(arg) {
+                writeln!(&mut stderr(), "Error: --{} requires the 'merge' strategy", arg).unwrap();
+                process::exit(1);
+            }
+        }
+    }
+    run(&matches, strategy);
+}
+
+/// Returns the capabilities of the binary with the given `strategy`, as printed by
+/// `--capabilities`.
+///
+/// Each capability is given as `KIND:NAME`, where `KIND` is one of `strategy`, `mode`, `datatype`,
+/// `option` (the long name of an input format option, or of an option, which depends on the
+/// strategy or a feature) and
+/// `feature` (the optional cargo feature compiled in). The names are stable, so they can be
+/// matched by the scripts wrapping the binaries.
+pub fn capabilities(strategy: Option<Strategy>) -> Vec<String> {
+    let hash = strategy != Some(Strategy::Merge);
+    let merge = strategy != Some(Strategy::Hash);
+    let mut caps: Vec<String> = Vec::new();
+    for (&name, &on) in STRATEGIES.iter().zip(&[hash, merge]) {
+        if on {
+            caps.push(format!("strategy:{}", name));
+        }
+    }
+    caps.extend(JoinMode::NAMES.iter().map(|m| format!("mode:{}", m)));
+    caps.extend(["string", "i64", "u64"].iter().map(|d| format!("datatype:{}", d)));
+    let options = [("auto-sep", true),
+                   ("quoted", true),
+                   ("header", true),
+                   ("keep-empty", true),
+                   ("normalize-eol", true),
+                   ("max-keys", hash),
+                   ("parallel", hash),
+                   ("auto-build-side", hash),
+                   ("validate-unique", merge),
+                   ("check-sorted", merge),
+                   ("descending", merge),
+                   ("collation", merge && cfg!(feature = "collation")),
+                   ("field-sep-regex", cfg!(feature = "regex"))];
+    caps.extend(options.iter().filter(|o| o.1).map(|o| format!("option:{}", o.0)));
+    let features = [("regex", cfg!(feature = "regex")),
+                    ("collation", cfg!(feature = "collation")),
+                    ("serde", cfg!(feature = "serde"))];
+    caps.extend(features.iter().filter(|f| f.1).map(|f| format!("feature:{}", f.0)));
+    caps
+}
+
+fn run(matches: &ArgMatches, strategy: Strategy) {
+    let file_left: &str = matches.value_of("FILE1").unwrap();
+    let file_right: &str = matches.value_of("FILE2").unwrap();
+    
+    let in_rec_sep: &str = matches.value_of("in-rec-sep").unwrap_or("\n");
+    let in_rec_sep_left: &str = matches.value_of("in-rec-sep-left").unwrap_or(in_rec_sep);
+    let in_rec_sep_left_u8: u8 = match util::rec_sep_as_byte(in_rec_sep_left) {
+        Ok(b) => b,
+        Err(e) => e.exit(),
+    };
+    let in_rec_sep_right: &str = matches.value_of("in-rec-sep-right").unwrap_or(in_rec_sep);
+    let in_rec_sep_right_u8: u8 = match util::rec_sep_as_byte(in_rec_sep_right) {
+        Ok(b) => b,
+        Err(e) => e.exit(),
+    };
+
+    let (in_field_sep, in_field_sep_left, in_field_sep_right) = if matches.is_present("auto-sep") {
+        // the output fields are separated like the left input ones
+        let left = detect_field_sep(file_left, "FILE1", in_rec_sep_left_u8);
+        (left, left, detect_field_sep(file_right, "FILE2", in_rec_sep_right_u8))
+    } else {
+        let in_field_sep: &str = matches.value_of("in-field-sep").unwrap_or(",");
+        (in_field_sep,
+         matches.value_of("in-field-sep-left").unwrap_or(in_field_sep),
+         matches.value_of("in-field-sep-right").unwrap_or(in_field_sep))
+    };
+    let (in_field_sep_left, in_field_sep_right, out_field_sep_default) = in_field_seps(matches, in_field_sep_left, in_field_sep_right);
+    // the separators inside the quoted fields do not split the keys either
+    let (in_field_sep_left, in_field_sep_right) = if matches.is_present("quoted") {
+        (InFieldSep::Quoted(Box::new(in_field_sep_left), '"'), InFieldSep::Quoted(Box::new(in_field_sep_right), '"'))
+    } else {
+        (in_field_sep_left, in_field_sep_right)
+    };
+    let (in_field_sep_left, in_field_sep_right) = (&in_field_sep_left, &in_field_sep_right);
+
+    let normalize_eol = matches.value_of("normalize-eol");
+    let out_rec_sep: Cow<str> = match normalize_eol {
+        Some("crlf") => Cow::Borrowed("\r\n"),
+        Some(_) => Cow::Borrowed("\n"),
+        None => matches.value_of("out-rec-sep").map_or(Cow::Borrowed(in_rec_sep), util::unescape),
+    };
+    let out_rec_sep_u8: &[u8] = out_rec_sep.as_bytes();
+
+    let out_field_sep: Cow<str> = matches.value_of("out-field-sep")
+        .map_or(Cow::Borrowed(out_field_sep_default.unwrap_or(in_field_sep)), util::unescape);
+    let out_field_sep_u8: &[u8] = out_field_sep.as_bytes();
+
+    let keep_empty = matches.is_present("keep-empty");
+    let quote = if matches.is_present("quoted") {Some(b'"')} else {None};
+    let header = matches.is_present("header");
+    let coalesce = matches.is_present("coalesce");
+    let emit_key = matches.is_present("emit-key");
+    let with_fanout = matches.is_present("with-fanout");
+    let validate_unique = matches.is_present("validate-unique");
+    let order = if matches.is_present("descending") {SortOrder::Descending} else {SortOrder::Ascending};
+    let check_sorted = if matches.is_present("check-sorted") {Some(order)} else {None};
+    let collation = collation(matches);
+    let empty_keys = if matches.is_present("skip-empty-keys") {
+        EmptyKeys::Skip
+    } else if matches.is_present("empty-as-null") {
+        EmptyKeys::Null
+    } else {
+        EmptyKeys::Keep
+    };
+    let bad_number = match matches.value_of("bad-number") {
+        Some("skip") => Some(BadNumber::Skip),
+        Some("error") => Some(BadNumber::Error),
+        Some("zero") => Some(BadNumber::Zero),
+        _ => None,
+    };
+    let expect_fields = |name: &str| {
+        matches.value_of(name).or_else(|| matches.value_of("expect-fields")).map(|n| match ExpectFields::parse(n) {
+            Some(e) => e,
+            None => {
+                writeln!(&mut stderr(), "Error: the expected number of fields must be a non-negative \
+                                         integer, optionally followed by '+'").unwrap();
+                process::exit(1);
+            },
+        })
+    };
+    let (expect_fields_left, expect_fields_right) = (expect_fields("expect-fields-left"), 
+                                                     expect_fields("expect-fields-right"));
+    let max_group = matches.value_of("max-group").map(|n| match n.parse() {
+        Ok(max) => MaxGroup {
+            max,
+            action: match matches.value_of("on-max-group") {
+                Some("skip") => OverflowAction::Skip,
+                _ => OverflowAction::Error,
+            },
+        },
+        Err(_) => {
+            writeln!(&mut stderr(), "Error: the maximum group size must be a non-negative integer").unwrap();
+            process::exit(1);
+        },
+    });
+    let max_keys: Option<usize> = matches.value_of("max-keys").map(|n| match n.parse() {
+        Ok(n) => n,
+        Err(_) => {
+            writeln!(&mut stderr(), "Error: the maximum number of keys must be a non-negative integer").unwrap();
+            process::exit(1);
+        },
+    });
+    let parallel: Option<usize> = if matches.is_present("parallel") {
+        match matches.value_of("parallel").map(str::parse) {
+            None => Some(thread::available_parallelism().map_or(1, |n| n.get())),
+            Some(Ok(n)) if n > 0 => Some(n),
+            _ => {
+                writeln!(&mut stderr(), "Error: the number of threads must be a positive integer").unwrap();
+                process::exit(1);
+            },
+        }
+    } else {
+        None
+    };
+    // the map is built from the right input, so the inputs are swapped if the left one is smaller
+    let swap = matches.is_present("auto-build-side") && file_size(file_left, "FILE1") < file_size(file_right, "FILE2");
+    let flush_every: Option<usize> = if matches.is_present("unbuffered") {
+        Some(1)
+    } else {
+        matches.value_of("flush-every").map(|n| match n.parse() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                writeln!(&mut stderr(), "Error: the number of records to flush after must be a positive integer").unwrap();
+                process::exit(1);
+            },
+        })
+    };
+    let limit: Option<usize> = matches.value_of("limit").map(|n| match n.parse() {
+        Ok(n) => n,
+        Err(_) => {
+            writeln!(&mut stderr(), "Error: the limit must be a non-negative integer").unwrap();
+            process::exit(1);
+        },
+    });
+
+    let mut lines_left = read_records(file_left, "FILE1", in_rec_sep_left_u8, quote, keep_empty, normalize_eol.is_some()).peekable();
+    let header_left = if header {lines_left.next()} else {None};
+    let key_idx_left = key_idx(matches.values_of("left-by").or_else(|| matches.values_of("by")),
+                               matches.values_of("FIELDS1").unwrap(),
+                               header_left.as_ref().map_or("", |h| h.as_str()),
+                               in_field_sep_left);
+
+    let mut lines_right = read_records(file_right, "FILE2", in_rec_sep_right_u8, quote, keep_empty, normalize_eol.is_some()).peekable();
+    let header_right = if header {lines_right.next()} else {None};
+    let key_idx_right = key_idx(matches.values_of("right-by").or_else(|| matches.values_of("by")),
+                                matches.values_of("FIELDS2").unwrap(),
+                                header_right.as_ref().map_or("", |h| h.as_str()),
+                                in_field_sep_right);
+
+    if let Err(e) = util::check_key_types(&key_idx_left, &key_idx_right) {
+        e.exit();
+    }
+
+    let mut out_stream = BufWriter::new(io::stdout());
+
+    let mode: JoinMode = match matches.value_of("mode").map_or(Ok(JoinMode::Inner), str::parse) {
+        Ok(m) => m,
+        Err(e) => {
+            writeln!(&mut stderr(), "Error: {}", e).unwrap();
+            process::exit(1);
+        },
+    };
+
+    let left_excl_out = matches.value_of("left-excl-out");
+    let report_unmatched = matches.is_present("report-unmatched");
+    for &(arg, present) in &[("left-excl-out", left_excl_out.is_some()), ("report-unmatched", report_unmatched)] {
+        if present && mode != JoinMode::Inner {
+            writeln!(&mut stderr(), "Error: --{} requires the 'inner' mode", arg).unwrap();
+            process::exit(1);
+        }
+    }
+
+    if matches.is_present("explain") {
+        let plan = Plan {
+            strategy,
+            mode,
+            key_idx_left: &key_idx_left,
+            key_idx_right: &key_idx_right,
+            in_rec_seps: (in_rec_sep_left, in_rec_sep_right),
+            in_field_seps: (in_field_sep_left, in_field_sep_right),
+            out_rec_sep: &out_rec_sep,
+            out_field_sep: &out_field_sep,
+        };
+        write!(&mut stderr(), "{}", plan).unwrap();
+        return;
+    }
+
+    let projection = matches.value_of("project").map(|list| {
+        projection(list, 
+                   if header {Some((header_left.as_ref().map_or("", |h| h.as_str()), 
+                                    header_right.as_ref().map_or("", |h| h.as_str())))} else {None},
+                   (in_field_sep_left, in_field_sep_right))
+    });
+
+    let summary = matches.is_present("summary");
+    let agg = matches.is_present("agg");
+    if agg && mode != JoinMode::Inner {
+        writeln!(&mut stderr(), "Error: --agg requires the 'inner' mode").unwrap();
+        process::exit(1);
+    }
+    if header && !summary {
+        let header_left = header_left.as_ref().map_or("", |h| h.as_str());
+        let header_right = header_right.as_ref().map_or("", |h| h.as_str());
+        if with_fanout {
+            out_stream.write_all(b"fanout").unwrap();
+            out_stream.write_all(out_field_sep_u8).unwrap();
+        }
+        if emit_key || agg {
+            // the names of the key fields are taken from the file the key is taken from
+            let (names, field_sep, key_idx) = match mode {
+                JoinMode::RightExcl => (header_right, in_field_sep_right, &key_idx_right),
+                _ => (header_left, in_field_sep_left, &key_idx_left),
+            };
+            let mut key_idx: Vec<&(usize, isize, util::DataType)> = key_idx.iter().collect();
+            key_idx.sort_by_key(|&&(_, pos, _)| pos);
+            for &&(idx, _, _) in &key_idx {
+                let mut fields = util::Fields::new(names, field_sep);
+                let name = iter::from_fn(|| fields.next_raw()).nth(idx).unwrap_or("");
+                out_stream.write_all(name.as_bytes()).unwrap();
+                out_stream.write_all(out_field_sep_u8).unwrap();
+            }
+        }
+        if agg {
+            out_stream.write_all(b"count").unwrap();
+            out_stream.write_all(out_rec_sep_u8).unwrap();
+        } else if let Some(ref p) = projection {
+            p.write_header(&mut out_stream, out_field_sep_u8, out_rec_sep_u8);
+        } else {
+            match mode {
+                _ if coalesce => util::write_coalesced(&mut out_stream, header_left, header_right, 
+                                                       in_field_sep_left, in_field_sep_right, 
+                                                       out_field_sep_u8, out_rec_sep_u8),
+                JoinMode::LeftExcl => util::write_left(&mut out_stream, header_left, 0, out_field_sep_u8, out_rec_sep_u8),
+                JoinMode::RightExcl => util::write_right(&mut out_stream, header_right, 0, out_field_sep_u8, out_rec_sep_u8),
+                _ => util::write_both(&mut out_stream, header_left, header_right, out_field_sep_u8, out_rec_sep_u8),
+            }
+        }
+    }
+
+    let mut left_excl_stream = left_excl_out.map(|path| match File::create(path) {
+        Ok(f) => BufWriter::new(f),
+        Err(_) => {
+            writeln!(&mut stderr(), "Error: could not create {}", path).unwrap();
+            process::exit(1);
+        },
+    });
+    if let (Some(s), Some(h)) = (left_excl_stream.as_mut(), header_left.as_ref()) {
+        util::write_left(s, h, 0, out_field_sep_u8, out_rec_sep_u8);
+    }
+    // the unmatched left records are routed by the left outer join into the other stream
+    let mode = if left_excl_stream.is_some() {JoinMode::LeftOuter} else {mode};
+    // and those of both files by the full outer join into the standard error
+    let mode = if report_unmatched {JoinMode::FullOuter} else {mode};
+    let unmatched_stream = if report_unmatched {Some(BufWriter::new(io::stderr()))} else {None};
+
+    // the missing records are padded by the number of fields of the first record of their file,
+    // or of its header, if the file has no records
+    let num_fields = |first: Option<&String>, header: &Option<String>, field_sep| {
+        first.or(header.as_ref()).map_or(0, |r| util::num_fields(r, field_sep))
+    };
+    let arity = util::output_arity(num_fields(lines_left.peek(), &header_left, in_field_sep_left),
+                                   num_fields(lines_right.peek(), &header_right, in_field_sep_right),
+                                   mode);
+    let progress = if matches.is_present("progress") {Some(Progress::new())} else {None};
+    let lines_left = lines_left.inspect(|_| if let Some(ref p) = progress {p.left_read()});
+    let lines_left = check_fields(lines_left, "FILE1", in_field_sep_left, expect_fields_left);
+    let lines_right = check_fields(lines_right, "FILE2", in_field_sep_right, expect_fields_right);
+    let nulls_left: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let nulls_right: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let emit_key_idx = if emit_key {Some((key_idx_left.clone(), key_idx_right.clone()))} else {None};
+    let lines_left = filter_empty_keys(lines_left, in_field_sep_left, key_idx_left.clone(), empty_keys,
+                                       if mode.keeps_unmatched_left() {Some(&nulls_left)} else {None});
+    let lines_right = filter_empty_keys(lines_right, in_field_sep_right, key_idx_right.clone(), empty_keys,
+                                        if mode.keeps_unmatched_right() {Some(&nulls_right)} else {None});
+    let lines_left = filter_bad_numbers(lines_left, "FILE1", in_field_sep_left, key_idx_left.clone(), bad_number);
+    let lines_right = filter_bad_numbers(lines_right, "FILE2", in_field_sep_right, key_idx_right.clone(), bad_number);
+    let strict = matches.is_present("strict");
+    let lines_left = check_strict(lines_left, "FILE1", in_field_sep_left, key_idx_left.clone(), strict);
+    let lines_right = check_strict(lines_right, "FILE2", in_field_sep_right, key_idx_right.clone(), strict);
+    let zero_bad_numbers = bad_number == Some(BadNumber::Zero);
+
+    let left = Input {
+        label: "FILE1",
+        records: lines_left,
+        field_sep: in_field_sep_left,
+        key_idx: key_idx_left,
+        zero_bad_numbers,
+    };
+    let right = Input {
+        label: "FILE2",
+        records: lines_right,
+        field_sep: in_field_sep_right,
+        key_idx: key_idx_right,
+        zero_bad_numbers,
+    };
+    if summary {
+        let s = Summary::new(left, right, mode);
+        write!(&mut out_stream, "{}", s).expect("Error: could not write into output stream!");
+        out_stream.flush().expect("Error: could not write into output stream!");
+        return;
+    }
+    if agg {
+        let limit = limit.unwrap_or(usize::MAX);
+        let quote = left.field_sep.quote();
+        match strategy {
+            Strategy::Hash => write_counts(&mut out_stream, count_pairs_hash(left, right, max_keys).into_iter().take(limit), 
+                                           out_field_sep_u8, out_rec_sep_u8, quote),
+            Strategy::Merge => write_counts(&mut out_stream, count_pairs_merge(left, right, order).take(limit), 
+                                            out_field_sep_u8, out_rec_sep_u8, quote),
+        }
+        out_stream.flush().expect("Error: could not write into output stream!");
+        return;
+    }
+    let mut out = Output {
+        stream: out_stream,
+        field_sep: out_field_sep_u8,
+        rec_sep: out_rec_sep_u8,
+        remaining: limit,
+        flush_every,
+        unflushed: 0,
+        swapped: false,
+        coalesce: if coalesce {Some((in_field_sep_left, in_field_sep_right))} else {None},
+        progress: progress.as_ref(),
+        projection: projection.as_ref(),
+        emit_key: emit_key_idx.as_ref().map(|(left, right)| {
+            (EmitKey {label: "FILE1", field_sep: in_field_sep_left, key_idx: left, zero_bad_numbers},
+             EmitKey {label: "FILE2", field_sep: in_field_sep_right, key_idx: right, zero_bad_numbers})
+        }),
+        left_excl: left_excl_stream,
+        unmatched: unmatched_stream,
+        with_fanout,
+        arity,
+    };
+    if limit != Some(0) {
+        // reaching the limit just stops the join early
+        let _ = match strategy {
+            Strategy::Hash if swap => {
+                out.swapped = true;
+                let res = match parallel {
+                    Some(threads) => parallel_hash_join(right, left, mode.swapped(), max_group, threads, &mut out),
+                    None => hash_join(right, left, mode.swapped(), max_group, max_keys, &mut out),
+                };
+                // the records with a null key below are not swapped
+                out.swapped = false;
+                res
+            },
+            Strategy::Hash => match parallel {
+                Some(threads) => parallel_hash_join(left, right, mode, max_group, threads, &mut out),
+                None => hash_join(left, right, mode, max_group, max_keys, &mut out),
+            },
+            Strategy::Merge => merge_join(left, right, mode, validate_unique, check_sorted, order,
+                                                collation, max_group, &mut out),
+        }.and_then(|()| {
+            for lv in nulls_left.into_inner() {
+                out.left(&lv)?;
+            }
+            for rv in nulls_right.into_inner() {
+                out.right(&rv)?;
+            }
+            Ok(())
+        });
+    }
+    out.stream.flush().expect("Error: could not write into output stream!");
+    if let Some(ref mut s) = out.left_excl {
+        s.flush().expect("Error: could not write into output stream!");
+    }
+    if let Some(ref mut s) = out.unmatched {
+        s.flush().expect("Error: could not write into output stream!");
+    }
+    if let Some(ref p) = progress {
+        p.report(true);
+    }
+}
+
+/// The number of the left records read between two progress reports.
+const PROGRESS_EVERY: usize = 100_000;
+
+/// The progress of the join, reported to stderr.
+///
+/// The counters are updated from within the input and the output loops, so no thread is needed.
+struct Progress {
+    left_read: Cell<usize>,
+    written: Cell<usize>,
+    start: Instant,
+}
+
+impl Progress {
+    fn new() -> Self {
+        Progress {
+            left_read: Cell::new(0),
+            written: Cell::new(0),
+            start: Instant::now(),
+        }
+    }
+
+    /// Counts a left record read and reports the progress every `PROGRESS_EVERY` records.
+    fn left_read(&self) {
+        let n = self.left_read.get() + 1;
+        self.left_read.set(n);
+        if n.is_multiple_of(PROGRESS_EVERY) {
+            self.report(false);
+        }
+    }
+
+    /// Writes the status line to stderr, overwriting the previous one, unless it is the `last`.
+    fn report(&self, last: bool) {
+        write!(&mut stderr(), "\rleft records read: {}, records written: {}, elapsed: {:.1}s{}",
+               self.left_read.get(), self.written.get(), self.start.elapsed().as_secs_f64(), 
+               if last {"\n"} else {""}).unwrap();
+    }
+}
+
+/// The resolved configuration of the join, as printed by `--explain`.
+struct Plan<'a> {
+    strategy: Strategy,
+    mode: JoinMode,
+    key_idx_left: &'a [(usize, isize, util::DataType)],
+    key_idx_right: &'a [(usize, isize, util::DataType)],
+    in_rec_seps: (&'a str, &'a str),
+    in_field_seps: (&'a InFieldSep<'a>, &'a InFieldSep<'a>),
+    out_rec_sep: &'a str,
+    out_field_sep: &'a str,
+}
+
+impl<'a> fmt::Display for Plan<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "strategy: {}", match self.strategy {
+            Strategy::Hash => STRATEGIES[0],
+            Strategy::Merge => STRATEGIES[1],
+        })?;
+        writeln!(f, "mode: {}", self.mode)?;
+        writeln!(f, "left key fields: {}", KeyFields(self.key_idx_left))?;
+        writeln!(f, "right key fields: {}", KeyFields(self.key_idx_right))?;
+        writeln!(f, "input record separators: {:?} (left), {:?} (right)", self.in_rec_seps.0, self.in_rec_seps.1)?;
+        writeln!(f, "input field separators: {} (left), {} (right)", self.in_field_seps.0, self.in_field_seps.1)?;
+        writeln!(f, "output record separator: {:?}", self.out_rec_sep)?;
+        writeln!(f, "output field separator: {:?}", self.out_field_sep)
+    }
+}
+
+/// Counts the joined pairs of each matched key, as written by `--agg count`, by probing the sizes
+/// of the right groups in the hash map with the left keys.
+///
+/// The keys are returned in the order of their first left record.
+fn count_pairs_hash<L, R>(left: Input<L>, 
+                          right: Input<R>, 
+                          max_keys: Option<usize>) -> Vec<(Vec<util::VarData>, usize)>
+    where L: Iterator<Item=String>,
+          R: Iterator<Item=String>,
+{
+    let mut right_counts: HashMap<Vec<util::VarData>, usize> = HashMap::new();
+    for r in right.records {
+        let (k, _) = key_value(r, right.label, right.field_sep, &right.key_idx, right.zero_bad_numbers);
+        let len = right_counts.len();
+        match right_counts.entry(k) {
+            Entry::Occupied(mut e) => *e.get_mut() += 1,
+            Entry::Vacant(e) => {
+                check_max_keys(right.label, len, max_keys);
+                e.insert(1);
+            },
+        }
+    }
+    // the index of each matched key in `counts`
+    let mut idx: HashMap<Vec<util::VarData>, usize> = HashMap::new();
+    let mut counts: Vec<(Vec<util::VarData>, usize)> = Vec::new();
+    for r in left.records {
+        let (k, _) = key_value(r, left.label, left.field_sep, &left.key_idx, left.zero_bad_numbers);
+        if let Some(&n) = right_counts.get(&k) {
+            match idx.get(&k) {
+                Some(&i) => counts[i].1 += n,
+                None => {
+                    idx.insert(k.clone(), counts.len());
+                    counts.push((k, n));
+                },
+            }
+        }
+    }
+    counts
+}
+
+/// Counts the joined pairs of each matched key, as written by `--agg count`, by merge joining the
+/// sizes of the key runs of both files.
+///
+/// The keys are yielded in the order of the input files, as they are joined.
+fn count_pairs_merge<'a, L, R>(left: Input<'a, L>, right: Input<'a, R>, order: SortOrder) 
+                               -> impl Iterator<Item=(Vec<util::VarData>, usize)> + 'a
+    where L: Iterator<Item=String> + 'a,
+          R: Iterator<Item=String> + 'a,
+{
+    let (label_left, field_sep_left, zero_left) = (left.label, left.field_sep, left.zero_bad_numbers);
+    let (label_right, field_sep_right, zero_right) = (right.label, right.field_sep, right.zero_bad_numbers);
+    let (key_idx_left, key_idx_right) = (left.key_idx, right.key_idx);
+    let runs_left = key_runs(left.records, 
+                             move |r: &String| record_key(r, label_left, field_sep_left, &key_idx_left, zero_left),
+                             label_left, 
+                             None)
+        .map(|(k, run)| (k, run.len()));
+    let runs_right = key_runs(right.records, 
+                              move |r: &String| record_key(r, label_right, field_sep_right, &key_idx_right, zero_right),
+                              label_right, 
+                              None)
+        .map(|(k, run)| (k, run.len()));
+    runs_left.merge_join_inner_by(runs_right, move |l, r| order.cmp(&l.0, &r.0))
+        .map(|((k, l), (_, r))| (k, l * r))
+}
+
+/// Writes each component of the `key`, followed by the `field_sep`.
+///
+/// The string components are enclosed in the `quote`, if any, when they would not be read back as
+/// a single field otherwise.
+fn write_key<W: Write>(stream: &mut BufWriter<W>, key: &[util::VarData], field_sep: &[u8], quote: Option<char>) {
+    for k in key {
+        match (k, quote) {
+            (util::VarData::S(s), Some(q)) if s.contains(q) || s.contains(['\n', '\r']) ||
+                                              (!field_sep.is_empty() &&
+                                               s.as_bytes().windows(field_sep.len()).any(|w| w == field_sep)) => {
+                let mut qq = String::with_capacity(2 * q.len_utf8());
+                qq.push(q);
+                qq.push(q);
+                write!(stream, "{}{}{}", q, s.replace(q, &qq), q)
+            },
+            _ => write!(stream, "{}", k),
+        }.expect("Error: could not write into output stream!");
+        stream.write_all(field_sep).expect("Error: could not write into output stream!");
+    }
+}
+
+/// Writes each key along with its count of the joined pairs, as a record.
+fn write_counts<W, I>(stream: &mut BufWriter<W>, counts: I, field_sep: &[u8], rec_sep: &[u8], quote: Option<char>)
+    where W: Write,
+          I: Iterator<Item=(Vec<util::VarData>, usize)>,
+{
+    for (key, n) in counts {
+        write_key(stream, &key, field_sep, quote);
+        write!(stream, "{}", n).expect("Error: could not write into output stream!");
+        stream.write_all(rec_sep).expect("Error: could not write into output stream!");
+    }
+}
+
+/// The statistics of the join, as printed by `--summary`.
+#[derive(Debug, Default)]
+struct Summary {
+    left_records: usize,
+    right_records: usize,
+    left_keys: usize,
+    right_keys: usize,
+    matched_keys: usize,
+    left_max_group: usize,
+    right_max_group: usize,
+    output_records: usize,
+}
+
+impl Summary {
+    /// Counts the records of both inputs by their key in a single pass over each.
+    ///
+    /// Only the keys and their counts are kept in memory, so the inputs need not be sorted.
+    fn new<L, R>(left: Input<L>, right: Input<R>, mode: JoinMode) -> Self
+        where L: Iterator<Item=String>,
+              R: Iterator<Item=String>,
+    {
+        // the number of the left and the right records of each key
+        let mut counts: HashMap<Vec<util::VarData>, (usize, usize)> = HashMap::new();
+        for r in left.records {
+            let (k, _) = key_value(r, left.label, left.field_sep, &left.key_idx, left.zero_bad_numbers);
+            counts.entry(k).or_insert((0, 0)).0 += 1;
+        }
+        for r in right.records {
+            let (k, _) = key_value(r, right.label, right.field_sep, &right.key_idx, right.zero_bad_numbers);
+            counts.entry(k).or_insert((0, 0)).1 += 1;
+        }
+
+        let mut s = Summary::default();
+        for &(l, r) in counts.values() {
+            s.left_records += l;
+            s.right_records += r;
+            s.left_keys += (l > 0) as usize;
+            s.right_keys += (r > 0) as usize;
+            s.left_max_group = s.left_max_group.max(l);
+            s.right_max_group = s.right_max_group.max(r);
+            s.output_records += match (l, r) {
+                (l, 0) if mode.keeps_unmatched_left() => l,
+                (0, r) if mode.keeps_unmatched_right() => r,
+                (0, _) | (_, 0) => 0,
+                (l, r) => {
+                    s.matched_keys += 1;
+                    match mode {
+                        JoinMode::LeftExcl | JoinMode::RightExcl => 0,
+                        _ => l * r,
+                    }
+                },
+            };
+        }
+        s
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "records: {} (left), {} (right)", self.left_records, self.right_records)?;
+        writeln!(f, "distinct keys: {} (left), {} (right)", self.left_keys, self.right_keys)?;
+        writeln!(f, "matched keys: {}", self.matched_keys)?;
+        writeln!(f, "max group size: {} (left), {} (right)", self.left_max_group, self.right_max_group)?;
+        writeln!(f, "output records: {}", self.output_records)
+    }
+}
+
+/// Displays the key fields in the order of the key, as given on the command line, e.g. `3-u,1`.
+struct KeyFields<'a>(&'a [(usize, isize, util::DataType)]);
+
+impl<'a> fmt::Display for KeyFields<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut fields: Vec<&(usize, isize, util::DataType)> = self.0.iter().collect();
+        fields.sort_by_key(|&&(_, pos, _)| pos);
+        for (i, &&(idx, _, ref dt)) in fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", idx + 1)?;
+            match *dt {
+                util::DataType::I => f.write_str("-i")?,
+                util::DataType::U => f.write_str("-u")?,
+                util::DataType::S => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The output of the join, limited to at most `remaining` records.
+struct Output<'a, W: Write> {
+    stream: BufWriter<W>,
+    field_sep: &'a [u8],
+    rec_sep: &'a [u8],
+    /// the number of records still to be written, if limited
+    remaining: Option<usize>,
+    /// flush after every so many records
+    flush_every: Option<usize>,
+    /// the number of records written since the last flush, if flushed every so many records
+    unflushed: usize,
+    /// the input field separators of the left and the right records, if the matched records are
+    /// coalesced
+    coalesce: Option<(&'a InFieldSep<'a>, &'a InFieldSep<'a>)>,
+    /// counts the records written, if the progress is reported
+    progress: Option<&'a Progress>,
+    /// the keys of the left and the right records, if written as the leading fields
+    emit_key: Option<(EmitKey<'a>, EmitKey<'a>)>,
+    /// the output fields, if projected
+    projection: Option<&'a Projection<'a>>,
+    /// the stream of the unmatched left records, if written apart from the matched ones
+    left_excl: Option<BufWriter<File>>,
+    /// the stream of the unmatched records of both files, if reported apart from the matched ones
+    unmatched: Option<BufWriter<io::Stderr>>,
+    /// whether the number of the right records matched by the left one is written as the leading
+    /// field
+    with_fanout: bool,
+    /// the number of the fields padded in place of the missing left and right record
+    arity: (usize, usize),
+    /// the join yields the records of FILE2 as the left ones and vice versa, see
+    /// `--auto-build-side`
+    swapped: bool,
+}
+
+/// The output fields given by `--project`.
+struct Projection<'a> {
+    fields: Vec<ProjectedField>,
+    /// the names of the fields in the output header
+    names: Vec<String>,
+    /// the input field separators of the left and the right records
+    field_seps: (&'a InFieldSep<'a>, &'a InFieldSep<'a>),
+}
+
+/// An output field given by `--project`.
+enum ProjectedField {
+    /// the field (base-0) of the left record
+    Left(usize),
+    /// the field (base-0) of the right record
+    Right(usize),
+    /// the constant value
+    Const(String),
+}
+
+impl<'a> Projection<'a> {
+    /// Writes the projected fields of the left and the right record, either of which can be
+    /// missing in the outer joins.
+    fn write<W: Write>(&self, 
+                       stream: &mut BufWriter<W>, 
+                       lv: Option<&str>, 
+                       rv: Option<&str>, 
+                       field_sep: &[u8], 
+                       rec_sep: &[u8]) {
+        // the fields are written as they are, including their quotes
+        let raw = |v, field_sep| {
+            let mut fields = util::Fields::new(v, field_sep);
+            iter::from_fn(move || fields.next_raw()).collect()
+        };
+        let left: Vec<&str> = lv.map_or_else(Vec::new, |lv| raw(lv, self.field_seps.0));
+        let right: Vec<&str> = rv.map_or_else(Vec::new, |rv| raw(rv, self.field_seps.1));
+        let values: Vec<&[u8]> = self.fields.iter().map(|field| match *field {
+            ProjectedField::Left(idx) => left.get(idx).cloned().unwrap_or(""),
+            ProjectedField::Right(idx) => right.get(idx).cloned().unwrap_or(""),
+            ProjectedField::Const(ref value) => value,
+        }.as_bytes()).collect();
+        util::write_record(stream, &values, field_sep, rec_sep);
+    }
+
+    /// Writes the names of the projected fields.
+    fn write_header<W: Write>(&self, stream: &mut BufWriter<W>, field_sep: &[u8], rec_sep: &[u8]) {
+        for (i, name) in self.names.iter().enumerate() {
+            if i > 0 {
+                stream.write_all(field_sep).expect("Error: could not write into output stream!");
+            }
+            stream.write_all(name.as_bytes()).expect("Error: could not write into output stream!");
+        }
+        stream.write_all(rec_sep).expect("Error: could not write into output stream!");
+    }
+}
+
+/// Extracts the key of a record again, in order to write it into the output.
+struct EmitKey<'a> {
+    /// the file name used in the error messages
+    label: &'static str,
+    field_sep: &'a InFieldSep<'a>,
+    key_idx: &'a [(usize, isize, util::DataType)],
+    /// whether the unparsable numeric key fields are written as zero
+    zero_bad_numbers: bool,
+}
+
+impl<'a> EmitKey<'a> {
+    /// Writes each key component of the `record`, followed by the `field_sep`.
+    fn write<W: Write>(&self, stream: &mut BufWriter<W>, record: &str, field_sep: &[u8]) {
+        let key = if self.zero_bad_numbers {
+            util::try_extract_key_or_zero(record, self.field_sep, self.key_idx)
+        } else {
+            util::try_extract_key(record, self.field_sep, self.key_idx)
+        }.unwrap_or_else(|e| key_error_exit(self.label, &e));
+        write_key(stream, &key, field_sep, self.field_sep.quote());
+    }
+}
+
+/// Signals the output limit has been reached, so the join should stop.
+struct LimitReached;
+
+impl<'a, W: Write> Output<'a, W> {
+    /// Writes the matched pair of the records, as yielded by the join, where `fanout` is the
+    /// number of the right records matched by the left one.
+    fn both(&mut self, lv: &str, rv: &str, fanout: usize) -> Result<(), LimitReached> {
+        if self.swapped {self.write_both(rv, lv, fanout)} else {self.write_both(lv, rv, fanout)}
+    }
+
+    /// Writes the unmatched left record, as yielded by the join.
+    fn left(&mut self, lv: &str) -> Result<(), LimitReached> {
+        if self.swapped {self.write_right(lv)} else {self.write_left(lv)}
+    }
+
+    /// Writes the unmatched right record, as yielded by the join.
+    fn right(&mut self, rv: &str) -> Result<(), LimitReached> {
+        if self.swapped {self.write_left(rv)} else {self.write_right(rv)}
+    }
+
+    fn write_both(&mut self, lv: &str, rv: &str, fanout: usize) -> Result<(), LimitReached> {
+        self.write_fanout(fanout);
+        if let Some(p) = self.projection {
+            p.write(&mut self.stream, Some(lv), Some(rv), self.field_sep, self.rec_sep);
+            return self.end_record();
+        }
+        if let Some((ref key, _)) = self.emit_key {
+            key.write(&mut self.stream, lv, self.field_sep);
+        }
+        match self.coalesce {
+            Some((lfs, rfs)) => util::write_coalesced(&mut self.stream, lv, rv, lfs, rfs, self.field_sep, self.rec_sep),
+            None => util::write_both(&mut self.stream, lv, rv, self.field_sep, self.rec_sep),
+        }
+        self.end_record()
+    }
+
+    fn write_left(&mut self, lv: &str) -> Result<(), LimitReached> {
+        // the unmatched left records are written as they are, not counted against the limit
+        if let Some(ref mut s) = self.left_excl {
+            util::write_left(s, lv, 0, self.field_sep, self.rec_sep);
+            return Ok(());
+        }
+        if let Some(ref mut s) = self.unmatched {
+            s.write_all(b"FILE1: ").expect("Error: could not write into output stream!");
+            util::write_left(s, lv, 0, self.field_sep, self.rec_sep);
+            return Ok(());
+        }
+        self.write_fanout(0);
+        // the coalesced records are not padded
+        if let Some(p) = self.projection {
+            p.write(&mut self.stream, Some(lv), None, self.field_sep, self.rec_sep);
+            return self.end_record();
+        }
+        let r_len = if self.coalesce.is_some() {0} else {self.arity.1};
+        if let Some((ref key, _)) = self.emit_key {
+            key.write(&mut self.stream, lv, self.field_sep);
+        }
+        util::write_left(&mut self.stream, lv, r_len, self.field_sep, self.rec_sep);
+        self.end_record()
+    }
+
+    fn write_right(&mut self, rv: &str) -> Result<(), LimitReached> {
+        if let Some(ref mut s) = self.unmatched {
+            s.write_all(b"FILE2: ").expect("Error: could not write into output stream!");
+            util::write_right(s, rv, 0, self.field_sep, self.rec_sep);
+            return Ok(());
+        }
+        self.write_fanout(0);
+        if let Some(p) = self.projection {
+            p.write(&mut self.stream, None, Some(rv), self.field_sep, self.rec_sep);
+            return self.end_record();
+        }
+        let l_len = if self.coalesce.is_some() {0} else {self.arity.0};
+        if let Some((_, ref key)) = self.emit_key {
+            key.write(&mut self.stream, rv, self.field_sep);
+        }
+        util::write_right(&mut self.stream, rv, l_len, self.field_sep, self.rec_sep);
+        self.end_record()
+    }
+
+    /// Writes the `fanout` followed by the `field_sep`, if requested.
+    fn write_fanout(&mut self, fanout: usize) {
+        if self.with_fanout {
+            write!(self.stream, "{}", fanout).expect("Error: could not write into output stream!");
+            self.stream.write_all(self.field_sep).expect("Error: could not write into output stream!");
+        }
+    }
+
+    /// Flushes the records written so far, if it is their turn, and counts the record just written
+    /// against the limit.
+    fn end_record(&mut self) -> Result<(), LimitReached> {
+        if let Some(n) = self.flush_every {
+            self.unflushed += 1;
+            if self.unflushed == n {
+                self.stream.flush().expect("Error: could not write into output stream!");
+                self.unflushed = 0;
+            }
+        }
+        if let Some(p) = self.progress {
+            p.written.set(p.written.get() + 1);
+        }
+        match self.remaining {
+            Some(ref mut n) => {
+                *n -= 1;
+                if *n == 0 {Err(LimitReached)} else {Ok(())}
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+/// The handling of the records with an empty key field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmptyKeys {
+    /// join the empty key field as an empty string
+    Keep,
+    /// drop the record
+    Skip,
+    /// the key matches no other key, the record is set aside to `nulls`, if given
+    Null,
+}
+
+/// Filters out the records with an empty key field, unless `empty_keys` is `Keep`.
+///
+/// If `empty_keys` is `Null`, the filtered records are pushed to `nulls`, if given, or dropped
+/// otherwise.
+fn filter_empty_keys<'a, I>(records: I, 
+                            field_sep: &'a InFieldSep<'a>, 
+                            key_idx: Vec<(usize, isize, util::DataType)>,
+                            empty_keys: EmptyKeys,
+                            nulls: Option<&'a RefCell<Vec<String>>>) -> impl Iterator<Item=String> + 'a
+    where I: Iterator<Item=String> + 'a,
+{
+    records.filter_map(move |r| {
+        if empty_keys == EmptyKeys::Keep || !util::has_empty_key(&r, field_sep, &key_idx) {
+            return Some(r);
+        }
+        if let (EmptyKeys::Null, Some(nulls)) = (empty_keys, nulls) {
+            nulls.borrow_mut().push(r);
+        }
+        None
+    })
+}
+
+/// The number of fields expected in each record, as given by `--expect-fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExpectFields {
+    n: usize,
+    /// whether more than `n` fields are allowed
+    at_least: bool,
+}
+
+impl ExpectFields {
+    /// Parses `N` or `N+`.
+    fn parse(s: &str) -> Option<Self> {
+        let (n, at_least) = match s.strip_suffix('+') {
+            Some(n) => (n, true),
+            None => (s, false),
+        };
+        n.parse().ok().map(|n| ExpectFields {n, at_least})
+    }
+}
+
+impl fmt::Display for ExpectFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.at_least {
+            write!(f, "at least {}", self.n)
+        } else {
+            write!(f, "{}", self.n)
+        }
+    }
+}
+
+/// Checks each record has the `expected` number of fields, if any, reporting the first one, which
+/// has not, and exiting.
+///
+/// The records are numbered from 1, not counting the header and the skipped empty records.
+fn check_fields<'a, I>(records: I, 
+                       label: &'static str,
+                       field_sep: &'a InFieldSep<'a>, 
+                       expected: Option<ExpectFields>) -> impl Iterator<Item=String> + 'a
+    where I: Iterator<Item=String> + 'a,
+{
+    records.enumerate().map(move |(i, r)| {
+        if let Some(e) = expected {
+            let n = util::num_fields(&r, field_sep);
+            if n < e.n || (n > e.n && !e.at_least) {
+                writeln!(&mut stderr(), "Error: the record {} has {} fields instead of {} in {}", 
+                         i + 1, n, e, label).unwrap();
+                process::exit(1);
+            }
+        }
+        r
+    })
+}
+
+/// The handling of the records with a numeric key field, which is not a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BadNumber {
+    /// drop the record
+    Skip,
+    /// report the key field and exit
+    Error,
+    /// join the key field as zero
+    Zero,
+}
+
+/// Filters out the records with an unparsable numeric key field if `bad_number` is `Skip`, or
+/// reports the first such record and exits if it is `Error`.
+///
+/// The other records, including those with a missing key field, are passed through to the join.
+fn filter_bad_numbers<'a, I>(records: I, 
+                             label: &'static str,
+                             field_sep: &'a InFieldSep<'a>, 
+                             key_idx: Vec<(usize, isize, util::DataType)>,
+                             bad_number: Option<BadNumber>) -> impl Iterator<Item=String> + 'a
+    where I: Iterator<Item=String> + 'a,
+{
+    // the string keys are always valid, so there is nothing to check
+    let check = match bad_number {
+        Some(BadNumber::Skip) | Some(BadNumber::Error) => {
+            key_idx.iter().any(|(_, _, dt)| *dt != util::DataType::S)
+        },
+        _ => false,
+    };
+    records.filter(move |r| {
+        if !check {
+            return true;
+        }
+        match util::try_extract_key(r, field_sep, &key_idx) {
+            Err(ref e @ util::KeyError::ParseInt { .. }) | 
+            Err(ref e @ util::KeyError::ParseUint { .. }) => {
+                if bad_number == Some(BadNumber::Error) {
+                    key_error_exit(label, e);
+                }
+                false
+            },
+            _ => true,
+        }
+    })
+}
+
+/// Reports the first record with a numeric key field, which is not a canonical number, along with
+/// its number and exits, if `strict` is set.
+///
+/// The records are numbered as they reach the check, i.e. not counting the header and the
+/// records filtered out before.
+fn check_strict<'a, I>(records: I, 
+                       label: &'static str,
+                       field_sep: &'a InFieldSep<'a>, 
+                       key_idx: Vec<(usize, isize, util::DataType)>,
+                       strict: bool) -> impl Iterator<Item=String> + 'a
+    where I: Iterator<Item=String> + 'a,
+{
+    // the string keys are always valid, so there is nothing to check
+    let check = strict && key_idx.iter().any(|(_, _, dt)| *dt != util::DataType::S);
+    records.enumerate().map(move |(n, r)| {
+        if check {
+            match util::try_extract_key_strict(&r, field_sep, &key_idx) {
+                // a missing key field is reported by the join itself
+                Err(util::KeyError::IndexOutOfRange { .. }) | Ok(_) => (),
+                Err(e) => {
+                    writeln!(&mut stderr(), "Error: {} in {} at record {}", e, label, n + 1).unwrap();
+                    process::exit(1);
+                },
+            }
+        }
+        r
+    })
+}
+
+/// Returns the size of the file in bytes. Any error is reported and the process exits.
+fn file_size(path: &str, label: &str) -> u64 {
+    match std::fs::metadata(path) {
+        Ok(m) if m.is_file() => m.len(),
+        _ => {
+            writeln!(&mut stderr(), "Error: could not get the size of {}, which must be a regular file", label).unwrap();
+            process::exit(1);
+        },
+    }
+}
+
+/// Opens the file and returns an iterator over its records.
+///
+/// If `strip_cr` is set, a trailing `\r` is removed from each record before it is checked for
+/// being empty. Any error reading the file is reported and the process exits.
+fn read_records(path: &str, 
+                label: &'static str, 
+                rec_sep: u8, 
+                quote: Option<u8>, 
+                keep_empty: bool, 
+                strip_cr: bool) -> impl Iterator<Item=String> {
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => {
+            writeln!(&mut stderr(), "Error: could not open {}", label).unwrap();
+            process::exit(1);
+        },
+    };
+    let stream = io::BufReader::new(file);
+    let raw_records = util::split_records(stream, rec_sep, quote)
+        .map(move |r| match r {
+            Ok(mut v) => {
+                if strip_cr && v.last() == Some(&b'\r') {
+                    v.pop();
+                }
+                v
+            },
+            Err(_) => {
+                writeln!(&mut stderr(), "Error: could not read the record in {}", label).unwrap();
+                process::exit(1);
+            },
+        });
+    util::skip_empty(raw_records, keep_empty)
+        .map(String::from_utf8)
+        .map(|r| match r {
+            Ok(s) => s,
+            Err(_) => {
+                writeln!(&mut stderr(), "Error: could not convert the record bytes into string").unwrap();
+                process::exit(1);
+            },
+        })
+}
+
+/// Returns the field separator detected from the first record of the file at `path`, see
+/// [`util::detect_field_sep`](../util/fn.detect_field_sep.html), and reports it to stderr.
+fn detect_field_sep(path: &str, label: &'static str, rec_sep: u8) -> &'static str {
+    let first = read_records(path, label, rec_sep, None, false, false).next();
+    let sep = util::detect_field_sep(first.as_ref().map_or("", |r| r.as_str()));
+    writeln!(&mut stderr(), "Warning: using the field separator {:?} in {}", sep, label).unwrap();
+    sep
+}
+
+/// Returns the key index of the input file, given either the column `names` or the `fields`.
+///
+/// Any error is reported and the process exits.
+fn key_idx<'a, N, F>(names: Option<N>, fields: F, header: &str, field_sep: &InFieldSep) 
+                     -> Vec<(usize, isize, util::DataType)> 
+    where N: Iterator<Item=&'a str>,
+          F: Iterator<Item=&'a str>,
+{
+    let fields: Vec<String> = match names {
+        Some(names) => match util::header_fields(header, field_sep, names.collect()) {
+            Ok(v) => v,
+            Err(e) => e.exit(),
+        },
+        None => fields.map(str::to_owned).collect(),
+    };
+    match util::fields_to_idx(fields.iter().map(|f| f.as_str()).collect()) {
+        Ok(v) => v,
+        Err(e) => e.exit(),
+    }
+}
+
+/// Parses the `--project` LIST, looking up the column names in the `headers` of both files, if
+/// any.
+fn projection<'a>(list: &str, 
+                  headers: Option<(&str, &str)>, 
+                  field_seps: (&'a InFieldSep<'a>, &'a InFieldSep<'a>)) -> Projection<'a> {
+    let mut fields = Vec::new();
+    let mut names = Vec::new();
+    for item in list.split(',') {
+        let (spec, rename) = match item.find(':') {
+            Some(i) => (&item[..i], Some(&item[i + 1..])),
+            None => (item, None),
+        };
+        if rename.is_some() && headers.is_none() {
+            writeln!(&mut stderr(), "Error: renaming the --project field '{}' requires --header", item).unwrap();
+            process::exit(1);
+        }
+        if let Some(value) = spec.strip_prefix('=') {
+            fields.push(ProjectedField::Const(value.to_string()));
+            names.push(rename.unwrap_or("").to_string());
+            continue;
+        }
+        let (header, field_sep, left) = match spec.split_once('.') {
+            Some(("1", _)) => (headers.map(|h| h.0), field_seps.0, true),
+            Some(("2", _)) => (headers.map(|h| h.1), field_seps.1, false),
+            _ => {
+                writeln!(&mut stderr(), "Error: invalid --project field '{}', expected e.g. '1.3'", item).unwrap();
+                process::exit(1);
+            },
+        };
+        let field = &spec[2..];
+        let idx = match (field.parse::<usize>(), header) {
+            (Ok(n), _) if n > 0 => n - 1,
+            (Err(_), Some(header)) => {
+                let mut columns = util::Fields::new(header, field_sep);
+                match iter::from_fn(|| columns.next_unescaped()).position(|c| c == field) {
+                    Some(idx) => idx,
+                    None => {
+                        writeln!(&mut stderr(), "Error: the column '{}' was not found in the header!", field).unwrap();
+                        process::exit(1);
+                    },
+                }
+            },
+            _ => {
+                writeln!(&mut stderr(), "Error: invalid --project field '{}', expected e.g. '1.3'", item).unwrap();
+                process::exit(1);
+            },
+        };
+        let name = match rename {
+            Some(name) => name,
+            None => header.and_then(|h| {
+                let mut columns = util::Fields::new(h, field_sep);
+                iter::from_fn(|| columns.next_raw()).nth(idx)
+            }).unwrap_or(""),
+        };
+        names.push(name.to_string());
+        fields.push(if left {ProjectedField::Left(idx)} else {ProjectedField::Right(idx)});
+    }
+    Projection { fields, names, field_seps }
+}
+
+fn hash_join<L, R, W>(left: Input<L>, 
+                      right: Input<R>, 
+                      mode: JoinMode, 
+                      max_group: Option<MaxGroup>,
+                      max_keys: Option<usize>,
+                      out: &mut Output<W>) -> Result<(), LimitReached>
+    where L: Iterator<Item=String>,
+          R: Iterator<Item=String>,
+          W: Write,
+{
+    let (in_field_sep_left, key_fields_idx_left) = (left.field_sep, left.key_idx);
+    let (in_field_sep_right, key_fields_idx_right) = (right.field_sep, right.key_idx);
+    let (label_left, label_right) = (left.label, right.label);
+    let zero_bad_numbers = left.zero_bad_numbers;
+    let mut records_left = left.records
+        .map(|s| key_value(s, label_left, in_field_sep_left, &key_fields_idx_left, zero_bad_numbers))
+        .peekable();
+    let records_right = right.records
+        .map(|s| key_value(s, label_right, in_field_sep_right, &key_fields_idx_right, zero_bad_numbers));
+
+    match mode {
+        JoinMode::Inner => {
+            let join = HashJoinInner::from_map(records_left, group_right(records_right, label_right, max_group, max_keys));
+            for (lv, rvv) in join {
+                let n = rvv.len();
+                for rv in rvv {
+                    out.both(&lv, &rv, n)?;
+                }
+            }
+        },
+        JoinMode::LeftExcl => {
+            // an empty left file yields nothing, so the right one is not even read
+            let set = match records_left.peek() {
+                Some(_) => key_set(records_right, label_right, max_keys),
+                None => HashSet::new(),
+            };
+            let join = HashJoinLeftExcl::from_set(records_left, set);
+            for lv in join {
+                out.left(&lv)?;
+            }
+        },
+        JoinMode::LeftOuter => {
+            let join = HashJoinLeftOuter::from_map(records_left, group_right(records_right, label_right, max_group, max_keys));
+            for e in join {
+                match e {
+                    Left(lv) => {
+                        out.left(&lv)?;
+                    },
+                    Both(lv, rvv) => {
+                        let n = rvv.len();
+                        for rv in rvv {
+                            out.both(&lv, &rv, n)?;
+                        }
+                    },
+                    _ => unreachable!(),
+                }
+
+            }
+        },
+        JoinMode::RightExcl => {
+            let join = HashJoinRightExcl::from_map(records_left, group_right(records_right, label_right, None, max_keys));
+            for rvv in join {
+                for rv in rvv {
+                    out.right(&rv)?;
+                }
+            }
+        },
+        JoinMode::RightOuter if out.with_fanout => {
+            // the flat join does not tell the size of the matched group
+            let join = HashJoinRightOuter::from_map(records_left, group_right(records_right, label_right, max_group, max_keys));
+            for e in join {
+                match e {
+                    Right(rvv) => for rv in rvv {
+                        out.right(&rv)?;
+                    },
+                    Both(lv, rvv) => for rv in &rvv {
+                        out.both(&lv, rv, rvv.len())?;
+                    },
+                    _ => unreachable!(),
+                }
+            }
+        },
+        JoinMode::RightOuter => {
+            let join = HashJoinRightOuterFlat::from_map(records_left, group_right(records_right, label_right, max_group, max_keys));
+            for e in join {
+                match e {
+                    Right(rv) => out.right(&rv)?,
+                    // the fanout is written only by the grouped join above
+                    Both(lv, rv) => out.both(&lv, &rv, 0)?,
+                    _ => unreachable!(),
+                }
+            }
+        },
+        JoinMode::FullOuter => {
+            let join = HashJoinFullOuter::from_map(records_left, group_right(records_right, label_right, max_group, max_keys));
+            for e in join {
+                match e {
+                    Left(lv) => {
+                        out.left(&lv)?;
+                    },
+                    Right(rvv) => for rv in rvv {
+                        out.right(&rv)?;
+                    },
+                    Both(lv, rvv) => {
+                        let n = rvv.len();
+                        for rv in rvv {
+                            out.both(&lv, &rv, n)?;
+                        }
+                    },
+                }
+
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Extracts the key from the record, given as `(key, record)`.
+///
+/// If `zero_bad_numbers` is set, the unparsable numeric key fields are taken as zero and any other
+/// error is reported, before the process exits.
+fn key_value<'a>(record: String, 
+                 label: &str, 
+                 field_sep: &InFieldSep, 
+                 key_idx: &[(usize, isize, util::DataType)], 
+                 zero_bad_numbers: bool) -> (Vec<util::VarData>, Cow<'a, str>) {
+    let key = record_key(&record, label, field_sep, key_idx, zero_bad_numbers);
+    (key, Cow::Owned(record))
+}
+
+/// Extracts the key from the record, the same as `key_value`, but the record is only borrowed.
+fn record_key(record: &str, 
+              label: &str, 
+              field_sep: &InFieldSep, 
+              key_idx: &[(usize, isize, util::DataType)], 
+              zero_bad_numbers: bool) -> Vec<util::VarData> {
+    if zero_bad_numbers {
+        util::try_extract_key_or_zero(record, field_sep, key_idx).unwrap_or_else(|e| key_error_exit(label, &e))
+    } else {
+        unsafe {util::extract_key(record, field_sep, key_idx)}
+    }
+}
+
+/// The number of the left records probed by the worker threads at once, see `--parallel`.
+const PARALLEL_BATCH: usize = 65_536;
+
+/// A partition of the grouped right records of the parallel hash join, each flagged as matched.
+type Partition = HashMap<Vec<util::VarData>, (Vec<String>, AtomicBool)>;
+
+/// Returns the partition of the `key` among `n` partitions.
+fn partition_of(key: &[util::VarData], n: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % n as u64) as usize
+}
+
+/// Hash joins the records, the same as `hash_join`, but the keys are extracted and the map is
+/// built and probed by `threads` worker threads, see `--parallel`.
+///
+/// The right records are partitioned by the hash of their keys into a map per thread. The left
+/// records are read in batches, which are split among the threads to probe the maps, while the
+/// calling thread writes the joined records in the order of the left file, so the output is the
+/// same as that of `hash_join`.
+fn parallel_hash_join<L, R, W>(left: Input<L>, 
+                               right: Input<R>, 
+                               mode: JoinMode, 
+                               max_group: Option<MaxGroup>,
+                               threads: usize,
+                               out: &mut Output<W>) -> Result<(), LimitReached>
+    where L: Iterator<Item=String>,
+          R: Iterator<Item=String>,
+          W: Write,
+{
+    let zero_bad_numbers = left.zero_bad_numbers;
+    let (label_left, field_sep_left, key_idx_left) = (left.label, left.field_sep, &left.key_idx[..]);
+    let (label_right, field_sep_right, key_idx_right) = (right.label, right.field_sep, &right.key_idx[..]);
+    let chunk_len = |len: usize| cmp::max(1, len.div_ceil(threads));
+
+    // the right records are read by the calling thread, their keys are extracted by the workers
+    let records_right: Vec<String> = right.records.collect();
+    let keys: Vec<(usize, Vec<util::VarData>)> = thread::scope(|s| {
+        let workers: Vec<_> = records_right.chunks(chunk_len(records_right.len())).map(|chunk| {
+            s.spawn(move || chunk.iter().map(|r| {
+                let key = record_key(r, label_right, field_sep_right, key_idx_right, zero_bad_numbers);
+                (partition_of(&key, threads), key)
+            }).collect::<Vec<_>>())
+        }).collect();
+        workers.into_iter().flat_map(|w| w.join().expect("Error: a worker thread panicked!")).collect()
+    });
+    let mut parts: Vec<Vec<(Vec<util::VarData>, String)>> = (0..threads).map(|_| Vec::new()).collect();
+    for ((p, key), r) in keys.into_iter().zip(records_right) {
+        parts[p].push((key, r));
+    }
+    let mut maps: Vec<Partition> = thread::scope(|s| {
+        let workers: Vec<_> = parts.into_iter().map(|part| s.spawn(move || {
+            let mut map = Partition::new();
+            for (key, r) in part {
+                map.entry(key).or_insert_with(|| (Vec::with_capacity(1), AtomicBool::new(false))).0.push(r);
+            }
+            map
+        })).collect();
+        workers.into_iter().map(|w| w.join().expect("Error: a worker thread panicked!")).collect()
+    });
+    // the exclusive modes do not pair the records, so they are not checked, as in `hash_join()`
+    if let Some(m) = max_group.filter(|_| mode != JoinMode::LeftExcl && mode != JoinMode::RightExcl) {
+        for map in &mut maps {
+            map.retain(|k, rvv| m.check(label_right, k, rvv.0.len()));
+        }
+    }
+
+    let maps = &maps;
+    let mut records_left = left.records;
+    loop {
+        let batch: Vec<String> = records_left.by_ref().take(PARALLEL_BATCH).collect();
+        if batch.is_empty() {
+            break;
+        }
+        // the right records matched by each left record of the batch
+        let matches: Vec<Option<&[String]>> = thread::scope(|s| {
+            let workers: Vec<_> = batch.chunks(chunk_len(batch.len())).map(|chunk| {
+                s.spawn(move || chunk.iter().map(|r| {
+                    let key = record_key(r, label_left, field_sep_left, key_idx_left, zero_bad_numbers);
+                    maps[partition_of(&key, threads)].get(&key).map(|(rvv, matched)| {
+                        matched.store(true, AtomicOrdering::Relaxed);
+                        &rvv[..]
+                    })
+                }).collect::<Vec<_>>())
+            }).collect();
+            workers.into_iter().flat_map(|w| w.join().expect("Error: a worker thread panicked!")).collect()
+        });
+        for (lv, m) in batch.iter().zip(matches) {
+            match m {
+                Some(rvv) => if mode != JoinMode::LeftExcl && mode != JoinMode::RightExcl {
+                    for rv in rvv {
+                        out.both(lv, rv, rvv.len())?;
+                    }
+                },
+                None => if mode.keeps_unmatched_left() {
+                    out.left(lv)?;
+                },
+            }
+        }
+    }
+    if mode.keeps_unmatched_right() {
+        for (rvv, matched) in maps.iter().flat_map(|map| map.values()) {
+            if !matched.load(AtomicOrdering::Relaxed) {
+                for rv in rvv {
+                    out.right(rv)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn merge_join<L, R, W>(left: Input<L>, 
+                       right: Input<R>, 
+                       mode: JoinMode, 
+                       validate_unique: bool,
+                       check_sorted: Option<SortOrder>,
+                       order: SortOrder,
+                       collation: Option<util::Collation>,
+                       max_group: Option<MaxGroup>,
+                       out: &mut Output<W>) -> Result<(), LimitReached>
+    where L: Iterator<Item=String>,
+          R: Iterator<Item=String>,
+          W: Write,
+{
+    let (label_left, field_sep_left) = (left.label, left.field_sep);
+    let (label_right, field_sep_right) = (right.label, right.field_sep);
+    let zero_bad_numbers = left.zero_bad_numbers;
+    // a single numeric key is compared directly, without wrapping it into `Vec<VarData>`
+    match (&left.key_idx[..], &right.key_idx[..]) {
+        // the bad numbers taken as zero are handled by the general path only
+        _ if zero_bad_numbers => {
+            let key_idx_left = left.key_idx.clone();
+            let key_idx_right = right.key_idx.clone();
+            let left_key = checked_key(label_left, validate_unique, check_sorted, move |s| {
+                collate(util::try_extract_key_or_zero(s, field_sep_left, &key_idx_left)
+                    .unwrap_or_else(|e| key_error_exit(label_left, &e)), collation)
+            });
+            let right_key = checked_key(label_right, validate_unique, check_sorted, move |s| {
+                collate(util::try_extract_key_or_zero(s, field_sep_right, &key_idx_right)
+                    .unwrap_or_else(|e| key_error_exit(label_right, &e)), collation)
+            });
+            merge_join_by(left, right, mode, order, max_group, out, left_key, right_key)
+        },
+        (&[(idx_left, _, util::DataType::I)], &[(idx_right, _, util::DataType::I)]) => {
+            let left_key = checked_key(label_left, validate_unique, check_sorted, move |s| {
+                util::try_extract_i64(s, field_sep_left, idx_left).unwrap_or_else(|e| key_error_exit(label_left, &e))
+            });
+            let right_key = checked_key(label_right, validate_unique, check_sorted, move |s| {
+                util::try_extract_i64(s, field_sep_right, idx_right).unwrap_or_else(|e| key_error_exit(label_right, &e))
+            });
+            merge_join_by(left, right, mode, order, max_group, out, left_key, right_key)
+        },
+        (&[(idx_left, _, util::DataType::U)], &[(idx_right, _, util::DataType::U)]) => {
+            let left_key = checked_key(label_left, validate_unique, check_sorted, move |s| {
+                util::try_extract_u64(s, field_sep_left, idx_left).unwrap_or_else(|e| key_error_exit(label_left, &e))
+            });
+            let right_key = checked_key(label_right, validate_unique, check_sorted, move |s| {
+                util::try_extract_u64(s, field_sep_right, idx_right).unwrap_or_else(|e| key_error_exit(label_right, &e))
+            });
+            merge_join_by(left, right, mode, order, max_group, out, left_key, right_key)
+        },
+        _ => {
+            let key_idx_left = left.key_idx.clone();
+            let key_idx_right = right.key_idx.clone();
+            let left_key = checked_key(label_left, validate_unique, check_sorted, move |s| {
+                collate(unsafe {util::extract_key(s, field_sep_left, &key_idx_left)}, collation)
+            });
+            let right_key = checked_key(label_right, validate_unique, check_sorted, move |s| {
+                collate(unsafe {util::extract_key(s, field_sep_right, &key_idx_right)}, collation)
+            });
+            merge_join_by(left, right, mode, order, max_group, out, left_key, right_key)
+        },
+    }
+}
+
+/// Replaces the string components of the key by their collation keys, if collated.
+fn collate(mut key: Vec<util::VarData>, collation: Option<util::Collation>) -> Vec<util::VarData> {
+    if let Some(c) = collation {
+        for k in &mut key {
+            if let util::VarData::S(ref mut s) = *k {
+                *s = c.key(s);
+            }
+        }
+    }
+    key
+}
+
+/// Merge joins the records by the keys extracted by `left_key` and `right_key`.
+#[allow(clippy::too_many_arguments)]
+fn merge_join_by<L, R, W, K, LK, RK>(left: Input<L>, 
+                                     right: Input<R>, 
+                                     mode: JoinMode, 
+                                     order: SortOrder,
+                                     max_group: Option<MaxGroup>,
+                                     out: &mut Output<W>,
+                                     left_key: LK,
+                                     right_key: RK) -> Result<(), LimitReached>
+    where L: Iterator<Item=String>,
+          R: Iterator<Item=String>,
+          W: Write,
+          K: Ord + fmt::Debug,
+          LK: FnMut(&String) -> K,
+          RK: FnMut(&String) -> K,
+{
+    let (label_left, label_right) = (left.label, right.label);
+    match mode {
+        JoinMode::Inner if max_group.is_none() && !out.with_fanout && order == SortOrder::Ascending => {
+            // the records are paired directly, without grouping them first
+            let join = left.records.merge_join_records_by(right.records, left_key, right_key);
+            for (lv, rv) in join {
+                out.both(&lv, &rv, 0)?;
+            }
+        },
+        JoinMode::Inner => {
+            let records_left = key_runs(left.records, left_key, label_left, max_group);
+            let records_right = key_runs(right.records, right_key, label_right, max_group);
+            let join = records_left.merge_join_inner_by(records_right, |l, r| order.cmp(&l.0, &r.0));
+            for ((_, lvv), (_, rvv)) in join {
+                for lv in lvv {
+                    for rv in &rvv {
+                        out.both(&lv, rv, rvv.len())?;
+                    }
+                }
+            }
+        },
+        JoinMode::LeftExcl => {
+            // the exclusive modes do not pair the records, so the runs are not checked
+            let records_left = key_runs(left.records, left_key, label_left, None);
+            let records_right = key_runs(right.records, right_key, label_right, None);
+            let join = records_left.merge_join_left_excl_by(records_right, |l, r| order.cmp(&l.0, &r.0));
+            for (_, lvv) in join {
+                for lv in lvv {
+                    out.left(&lv)?;
+                }
+            }
+        },
+        JoinMode::LeftOuter => {
+            let records_left = key_runs(left.records, left_key, label_left, max_group);
+            let records_right = key_runs(right.records, right_key, label_right, max_group);
+            let join = records_left.merge_join_left_outer_by(records_right, |l, r| order.cmp(&l.0, &r.0));
+            for e in join {
+                match e {
+                    Left((_, lvv)) => for lv in lvv {
+                        out.left(&lv)?;
+                    },
+                    Both((_, lvv), (_, rvv)) => for lv in lvv {
+                        for rv in &rvv {
+                            out.both(&lv, rv, rvv.len())?;
+                        }
+                    },
+                    _ => unreachable!(),
+                }
+
+            }
+        },
+        JoinMode::RightExcl => {
+            // the exclusive modes do not pair the records, so the runs are not checked
+            let records_left = key_runs(left.records, left_key, label_left, None);
+            let records_right = key_runs(right.records, right_key, label_right, None);
+            // left-excl with inverted input
+            let join = records_right.merge_join_left_excl_by(records_left, |l, r| order.cmp(&l.0, &r.0));
+            for (_, lvv) in join {
+                for lv in lvv {
+                    out.right(&lv)?;
+                }
+            }
+        },
+        JoinMode::RightOuter => {
+            let records_left = key_runs(left.records, left_key, label_left, max_group);
+            let records_right = key_runs(right.records, right_key, label_right, max_group);
+            // left-outer with inverted input
+            let join = records_right.merge_join_left_outer_by(records_left, |l, r| order.cmp(&l.0, &r.0));
+            for e in join {
+                match e {
+                    Left((_, lvv)) => for lv in lvv {
+                        out.right(&lv)?;
+                    },
+                    Both((_, lvv), (_, rvv)) => {
+                        // the right records are the left ones of the inverted join
+                        let n = lvv.len();
+                        for lv in lvv {
+                            for rv in &rvv {
+                                out.both(rv, &lv, n)?;
+                            }
+                        }
+                    },
+                    _ => unreachable!(),
+                }
+
+            }
+        },
+        JoinMode::FullOuter => {
+            let records_left = key_runs(left.records, left_key, label_left, max_group);
+            let records_right = key_runs(right.records, right_key, label_right, max_group);
+            let join = records_left.merge_join_full_outer_by(records_right, |l, r| order.cmp(&l.0, &r.0));
+            for e in join {
+                match e {
+                    Left((_, lvv)) => for lv in lvv {
+                        out.left(&lv)?;
+                    },
+                    Right((_, rvv)) => for rv in rvv {
+                        out.right(&rv)?;
+                    },
+                    Both((_, lvv), (_, rvv)) => for lv in lvv {
+                        for rv in &rvv {
+                            out.both(&lv, rv, rvv.len())?;
+                        }
+                    },
+                }
+
+            }
+        },
+    }
+    Ok(())
+}
+
+/// The limit of the number of records of a key in either file, see `--max-group`.
+#[derive(Debug, Clone, Copy)]
+struct MaxGroup {
+    max: usize,
+    action: OverflowAction,
+}
+
+impl MaxGroup {
+    /// Checks the number of records `len` of the `key` in the `file`: returns whether to keep them,
+    /// or exits, if the limit is exceeded and the action is to abort.
+    fn check<K: fmt::Debug>(&self, file: &str, key: &K, len: usize) -> bool {
+        if len <= self.max {
+            return true;
+        }
+        match self.action {
+            OverflowAction::Error => {
+                writeln!(&mut stderr(), "Error: the key {:?} has more than {} records in {}", 
+                         key, self.max, file).unwrap();
+                process::exit(1);
+            },
+            OverflowAction::Skip => {
+                writeln!(&mut stderr(), "Warning: skipping the key {:?} with more than {} records in {}", 
+                         key, self.max, file).unwrap();
+                false
+            },
+        }
+    }
+}
+
+/// Groups the right records by the key into the map probed by the hash join, checking the
+/// `max_group` and the `max_keys` limits, if any.
+fn group_right<R, RV>(records: R, 
+                      file: &str, 
+                      max_group: Option<MaxGroup>,
+                      max_keys: Option<usize>) -> HashMap<Vec<util::VarData>, Vec<RV>>
+    where R: Iterator<Item=(Vec<util::VarData>, RV)>,
+{
+    let mut map = match max_keys {
+        None => group_into_map(records),
+        Some(_) => {
+            let mut map: HashMap<Vec<util::VarData>, Vec<RV>> = HashMap::new();
+            for (k, v) in records {
+                let len = map.len();
+                match map.entry(k) {
+                    Entry::Occupied(mut e) => e.get_mut().push(v),
+                    Entry::Vacant(e) => {
+                        check_max_keys(file, len, max_keys);
+                        e.insert(vec![v]);
+                    },
+                }
+            }
+            map
+        },
+    };
+    if let Some(m) = max_group {
+        map.retain(|k, rvv| m.check(file, k, rvv.len()));
+    }
+    map
+}
+
+/// Collects the keys of the right records into the set probed by the left exclusive hash join,
+/// checking the `max_keys` limit, if any.
+fn key_set<R, RV>(records: R, file: &str, max_keys: Option<usize>) -> HashSet<Vec<util::VarData>>
+    where R: Iterator<Item=(Vec<util::VarData>, RV)>,
+{
+    let mut set = HashSet::new();
+    for (k, _) in records {
+        if !set.contains(&k) {
+            check_max_keys(file, set.len(), max_keys);
+            set.insert(k);
+        }
+    }
+    set
+}
+
+/// Exits, if the `len` distinct keys of the `file` already loaded into memory reach the
+/// `max_keys` limit, so no other key can be inserted.
+fn check_max_keys(file: &str, len: usize, max_keys: Option<usize>) {
+    if let Some(max) = max_keys {
+        if len >= max {
+            writeln!(&mut stderr(), "Error: {} has more than {} distinct keys, consider the 'merge' \
+                                     strategy, which does not load it into memory", file, max).unwrap();
+            process::exit(1);
+        }
+    }
+}
+
+/// Groups the consecutive records with equal keys for the merge join, checking the `max_group`
+/// limit, if any.
+///
+/// The limit is checked as the run is collected, so at most `max + 1` records of a key are
+/// buffered: the rest of a skipped run is consumed without buffering it.
+fn key_runs<I, K, F>(mut records: I, 
+                     mut key: F, 
+                     file: &'static str, 
+                     max_group: Option<MaxGroup>) -> impl Iterator<Item=(K, Vec<String>)>
+    where I: Iterator<Item=String>,
+          K: PartialEq + fmt::Debug,
+          F: FnMut(&String) -> K,
+{
+    // whether to skip the run of the `key` with `len` records so far
+    let skip_run = move |key: &K, len| max_group.is_some_and(|m| len > m.max && !m.check(file, key, len));
+    // the first record of the next run along with its key
+    let mut head: Option<(K, String)> = None;
+    iter::from_fn(move || loop {
+        let (k, first) = match head.take() {
+            Some(h) => h,
+            None => {
+                let r = records.next()?;
+                (key(&r), r)
+            },
+        };
+        let mut skip = skip_run(&k, 1);
+        let mut run = if skip {Vec::new()} else {vec![first]};
+        for r in records.by_ref() {
+            let rk = key(&r);
+            if rk != k {
+                head = Some((rk, r));
+                break;
+            }
+            if skip {
+                continue;
+            }
+            run.push(r);
+            if skip_run(&k, run.len()) {
+                skip = true;
+                run = Vec::new();
+            }
+        }
+        if !skip {
+            return Some((k, run));
+        }
+    })
+}
+
+/// Wraps the key extractor of the records, optionally checking the consecutive keys are unique
+/// and sorted in the `check_sorted` order.
+fn checked_key<K, F>(label: &'static str, 
+                     validate_unique: bool, 
+                     check_sorted: Option<SortOrder>, 
+                     mut key: F) -> impl FnMut(&String) -> K 
+    where K: Ord + Clone + fmt::Debug,
+          F: FnMut(&str) -> K,
+{
+    let mut prev: Option<K> = None;
+    let mut n = 0usize;
+    move |s| {
+        let k = key(s);
+        if validate_unique || check_sorted.is_some() {
+            if let Some(ref p) = prev {
+                if validate_unique && *p == k {
+                    duplicate_key_exit(label, &k, n - 1);
+                }
+                if let Some(order) = check_sorted {
+                    if order.cmp(p, &k) == Ordering::Greater {
+                        writeln!(&mut stderr(), "Error: the key {:?} in {} at record {} is out of order", 
+                                 k, label, n + 1).unwrap();
+                        process::exit(1);
+                    }
+                }
+            }
+            prev = Some(k.clone());
+        }
+        n += 1;
+        k
+    }
+}
+
+/// Reports the key extraction error and exits.
+fn key_error_exit(file: &str, e: &util::KeyError) -> ! {
+    writeln!(&mut stderr(), "Error: {} in {}", e, file).unwrap();
+    process::exit(1);
+}
+
+/// Reports the first two records of a key group, which is expected to be unique, and exits.
+///
+/// `offset` is the number of records preceding the group in the file.
+fn duplicate_key_exit<K: fmt::Debug>(file: &str, key: &K, offset: usize) -> ! {
+    writeln!(&mut stderr(), "Error: duplicate key {:?} in {} at records {} and {}", 
+             key, file, offset + 1, offset + 2).unwrap();
+    process::exit(1);
+}
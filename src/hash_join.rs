@@ -12,170 +12,1114 @@
 //! The supported join types:
 //!
 //! * [`INNER JOIN`](trait.Joinkit.html#method.hash_join_inner) - an intersection between the
-//! left and the right iterator.
+//!   left and the right iterator.
 //! * [`LEFT EXCL JOIN`](trait.Joinkit.html#method.hash_join_left_excl) - a difference
-//! between the left and the right iterator (not directly in SQL).
+//!   between the left and the right iterator (not directly in SQL).
 //! * [`LEFT OUTER JOIN`](trait.Joinkit.html#method.hash_join_left_outer) - a union of `INNER
-//! JOIN` and `LEFT EXCL JOIN`.
+//!   JOIN` and `LEFT EXCL JOIN`.
 //! * [`RIGHT EXCL JOIN`](trait.Joinkit.html#method.hash_join_right_excl) - a difference
-//! between the right and the left iterator (not directly in SQL).
+//!   between the right and the left iterator (not directly in SQL).
 //! * [`RIGHT OUTER JOIN`](trait.Joinkit.html#method.hash_join_right_outer) - a union of `INNER
-//! JOIN` and `RIGHT EXCL JOIN`.
+//!   JOIN` and `RIGHT EXCL JOIN`.
 //! * [`FULL OUTER JOIN`](trait.Joinkit.html#method.hash_join_full_outer) - a union of `INNER
-//! JOIN`, `LEFT EXCL JOIN` and `RIGHT EXCL JOIN`.
+//!   JOIN`, `LEFT EXCL JOIN` and `RIGHT EXCL JOIN`.
 
 use std::collections::hash_map::{HashMap, IntoIter,};
 use std::collections::hash_set::{HashSet,};
+use std::error;
+use std::fmt;
 use std::mem;
+use std::slice;
+use std::vec;
 use std::hash::Hash;
+use std::iter::{FromIterator, Peekable};
 use super::EitherOrBoth::{self, Right, Left, Both};
 
+/// Collect the `(key, value)` pairs into a `HashMap`, grouping the values by the key.
+///
+/// This is the map the hash join adaptors build from the right input iterator. It can be built
+/// once and probed by the borrowing adaptors, e.g.
+/// [`hash_join_inner_pairs_ref()`](trait.Joinkit.html#method.hash_join_inner_pairs_ref).
+///
+/// ```
+/// use joinkit;
+///
+/// let map = joinkit::group_into_map(vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")]);
+/// assert_eq!(map["1"], vec!["1;X", "1;Y"]);
+/// assert_eq!(map["2"], vec!["2;Z"]);
+/// ```
+pub fn group_into_map<K, RV, RI>(right: RI) -> HashMap<K, Vec<RV>>
+    where K: Hash + Eq,
+          RI: IntoIterator<Item=(K, RV)>
+{
+    group_into_map_with_capacity(right, 0)
+}
+
+/// Same as `group_into_map`, but the map is pre-sized for `capacity` distinct keys.
+fn group_into_map_with_capacity<K, RV, RI>(right: RI, capacity: usize) -> HashMap<K, Vec<RV>>
+    where K: Hash + Eq,
+          RI: IntoIterator<Item=(K, RV)>
+{
+    let mut map: HashMap<K, Vec<RV>> = HashMap::with_capacity(capacity);
+    for (k, v) in right {
+        let values = map.entry(k).or_insert_with(|| Vec::with_capacity(1));
+        values.push(v);
+    }
+    map
+}
+
+/// Collect the `(key, value)` pairs into a `HashMap` pre-sized for `capacity` distinct keys,
+/// grouping the values by the key. Each group is flagged as not matched yet.
+fn flag_into_map<K, RV, RI>(right: RI, capacity: usize) -> HashMap<K, (Vec<RV>, bool)>
+    where K: Hash + Eq,
+          RI: IntoIterator<Item=(K, RV)>
+{
+    let mut map: HashMap<K, (Vec<RV>, bool)> = HashMap::with_capacity(capacity);
+    for (k, v) in right {
+        let values = map.entry(k).or_insert((Vec::with_capacity(1), false));
+        values.0.push(v);
+    }
+    map
+}
+
+/// Remove the values equal to any preceding one, keeping the order of the first occurrences.
+fn dedup_values<RV: Hash + Eq>(values: &mut Vec<RV>) {
+    if values.len() < 2 {
+        return;
+    }
+    let first: Vec<bool> = {
+        let mut seen: HashSet<&RV> = HashSet::with_capacity(values.len());
+        values.iter().map(|v| seen.insert(v)).collect()
+    };
+    let mut first = first.into_iter();
+    values.retain(|_| first.next().unwrap_or(false));
+}
+
+/// Flag each group of an already built map as not matched yet.
+fn flag_map<K, RV>(map: HashMap<K, Vec<RV>>) -> HashMap<K, (Vec<RV>, bool)>
+    where K: Hash + Eq,
+{
+    map.into_iter().map(|(k, rvv)| (k, (rvv, false))).collect()
+}
+
+/// A builder of the map probed by the borrowing hash join adaptors, which can grow
+/// incrementally.
+///
+/// Unlike [`group_into_map()`](fn.group_into_map.html), the right values need not be available
+/// at once: they can be added in batches via `Extend`, or one by one via `add_right()`, as long
+/// as the map is not borrowed by a join adaptor at the same time.
+///
+/// ```
+/// use joinkit::{Joinkit, HashJoinMap};
+///
+/// let mut map: HashJoinMap<_, _> = vec![("1", "1;X")].into_iter().collect();
+/// map.extend(vec![("2", "2;Z"), ("1", "1;Y")]);
+///
+/// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+/// let mut it = l.hash_join_inner_pairs_ref(map.as_map());
+/// assert_eq!(it.next(), Some(("1;B", &"1;X")));
+/// assert_eq!(it.next(), Some(("1;B", &"1;Y")));
+/// assert_eq!(it.next(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HashJoinMap<K, RV> 
+    where K: Hash + Eq,
+{
+    map: HashMap<K, Vec<RV>>,
+}
+
+impl<K, RV> HashJoinMap<K, RV> 
+    where K: Hash + Eq,
+{
+    /// Create an empty `HashJoinMap`.
+    pub fn new() -> Self {
+        HashJoinMap { map: HashMap::new() }
+    }
+
+    /// Create an empty `HashJoinMap` pre-sized for `capacity` distinct keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashJoinMap { map: HashMap::with_capacity(capacity) }
+    }
+
+    /// Add a single right value to the group of its key.
+    pub fn add_right(&mut self, k: K, v: RV) {
+        self.map.entry(k).or_insert_with(|| Vec::with_capacity(1)).push(v);
+    }
+
+    /// Return the underlying map of the grouped right values.
+    pub fn as_map(&self) -> &HashMap<K, Vec<RV>> {
+        &self.map
+    }
+
+    /// Consume the builder and return the underlying map of the grouped right values.
+    pub fn into_map(self) -> HashMap<K, Vec<RV>> {
+        self.map
+    }
+}
+
+impl<K, RV> Default for HashJoinMap<K, RV> 
+    where K: Hash + Eq,
+{
+    fn default() -> Self {
+        HashJoinMap::new()
+    }
+}
+
+impl<K, RV> Extend<(K, RV)> for HashJoinMap<K, RV> 
+    where K: Hash + Eq,
+{
+    fn extend<I: IntoIterator<Item=(K, RV)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.add_right(k, v);
+        }
+    }
+}
+
+impl<K, RV> FromIterator<(K, RV)> for HashJoinMap<K, RV> 
+    where K: Hash + Eq,
+{
+    fn from_iter<I: IntoIterator<Item=(K, RV)>>(iter: I) -> Self {
+        let mut map = HashJoinMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
 /// See [`hash_join_inner()`](trait.Joinkit.html#method.hash_join_inner) for the description and
 /// examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
-pub struct HashJoinInner<L, K, RV> {
+pub struct HashJoinInner<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, Vec<RV>>,
+}
+
+impl<L, K, RV> HashJoinInner<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinInner` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinInner` iterator with the map pre-sized for `capacity` distinct right keys.
+    pub fn with_capacity<LI, RI>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        let map = if left.peek().is_some() {
+            group_into_map_with_capacity(right, capacity)
+        } else {
+            HashMap::new()
+        };
+        HashJoinInner {
+            left,
+            map,
+        }
+    }
+
+    /// Create a `HashJoinInner` iterator from a fallible right iterator, returning its first
+    /// error instead.
+    pub fn try_new<LI, RI, E>(left: LI, right: RI) -> Result<Self, E>
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=Result<(K, RV), E>>
+    {
+        let mut left = left.into_iter().peekable();
+        let mut map: HashMap<K, Vec<RV>> = HashMap::new();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for r in right {
+                let (k, v) = r?;
+                map.entry(k).or_insert_with(|| Vec::with_capacity(1)).push(v);
+            }
+        }
+        Ok(HashJoinInner {
+            left,
+            map,
+        })
+    }
+
+    /// Create a `HashJoinInner` iterator, which probes an already built map of the grouped right
+    /// values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinInner {
+            left: left.into_iter().peekable(),
+            map,
+        }
+    }
+
+    /// Retain only the groups of the right values, for which `pred` returns `true`, e.g. to join
+    /// only against the frequently occurring keys.
+    ///
+    /// It must be called before the first `next()`, otherwise the already yielded left values
+    /// have been joined against the whole map.
+    ///
+    /// ```
+    /// use joinkit::HashJoinInner;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")];
+    /// let r = vec![("0", "0;X"), ("1", "1;Y"), ("1", "1;Z")];
+    /// let mut it = HashJoinInner::new(l, r);
+    /// it.retain(|_, rvv| rvv.len() > 1);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", vec!["1;Y", "1;Z"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    pub fn retain<F>(&mut self, mut pred: F) 
+        where F: FnMut(&K, &Vec<RV>) -> bool
+    {
+        self.map.retain(|k, rvv| pred(k, rvv));
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinInner<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          RV: Clone,
+{
+    type Item = (LV, Vec<RV>);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => match self.map.get(&lk) {
+                    Some(rvv) => return Some((lv, rvv.clone())),
+                    None => continue,
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_inner_take()`](trait.Joinkit.html#method.hash_join_inner_take) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerTake<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, Vec<RV>>,
+}
+
+impl<L, K, RV> HashJoinInnerTake<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerTake` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        let map = if left.peek().is_some() {group_into_map(right)} else {HashMap::new()};
+        HashJoinInnerTake {
+            left,
+            map,
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinInnerTake<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = (LV, Vec<RV>);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => match self.map.remove(&lk) {
+                    Some(rvv) => return Some((lv, rvv)),
+                    None => continue,
+                },
+                None => return None,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // each group is yielded at most once
+        let upper = match self.left.size_hint().1 {
+            Some(l) => l.min(self.map.len()),
+            None => self.map.len(),
+        };
+        (0, Some(upper))
+    }
+}
+
+/// See [`hash_join_inner_pairs()`](trait.Joinkit.html#method.hash_join_inner_pairs) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerPairs<L, K, LV, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    /// the index of the group of each right key in `groups`
+    index: HashMap<K, usize>,
+    groups: Vec<Vec<RV>>,
+    /// the current left value along with the index of its matched group and the cursor into it
+    group: Option<(LV, usize, usize)>,
+}
+
+impl<L, K, LV, RV> HashJoinInnerPairs<L, K, LV, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerPairs` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        let mut index: HashMap<K, usize> = HashMap::new();
+        let mut groups: Vec<Vec<RV>> = Vec::new();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for (k, rv) in right {
+                let i = *index.entry(k).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[i].push(rv);
+            }
+        }
+        HashJoinInnerPairs {
+            left,
+            index,
+            groups,
+            group: None,
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinInnerPairs<L, K, LV, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          LV: Clone,
+          RV: Clone,
+{
+    type Item = (LV, RV);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((lv, i, pos)) = self.group.take() {
+                let rvs = &self.groups[i];
+                // the last right value takes the left value, the others clone it
+                if pos + 1 == rvs.len() {
+                    return Some((lv, rvs[pos].clone()));
+                }
+                let item = (lv.clone(), rvs[pos].clone());
+                self.group = Some((lv, i, pos + 1));
+                return Some(item);
+            }
+            match self.left.next() {
+                Some((lk, lv)) => if let Some(&i) = self.index.get(&lk) {
+                    self.group = Some((lv, i, 0));
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_inner_pairs_ref()`](trait.Joinkit.html#method.hash_join_inner_pairs_ref) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerPairsRef<'a, L, K, LV, RV> 
+    where K: 'a,
+          RV: 'a,
+{
+    left: L,
+    map: &'a HashMap<K, Vec<RV>>,
+    /// the current left value along with the cursor into its matched right values
+    group: Option<(LV, slice::Iter<'a, RV>)>,
+}
+
+impl<'a, L, K, LV, RV> HashJoinInnerPairsRef<'a, L, K, LV, RV> 
+    where K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerPairsRef` iterator.
+    pub fn new<LI>(left: LI, map: &'a HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinInnerPairsRef {
+            left: left.into_iter(),
+            map,
+            group: None,
+        }
+    }
+}
+
+impl<'a, L, K, LV, RV> Iterator for HashJoinInnerPairsRef<'a, L, K, LV, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          LV: Clone,
+{
+    type Item = (LV, &'a RV);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((lv, mut rvs)) = self.group.take() {
+                match rvs.next() {
+                    // the last right value takes the left value, the others clone it
+                    Some(rv) if rvs.len() == 0 => return Some((lv, rv)),
+                    Some(rv) => {
+                        let item = (lv.clone(), rv);
+                        self.group = Some((lv, rvs));
+                        return Some(item);
+                    },
+                    None => (),
+                }
+            }
+            match self.left.next() {
+                Some((lk, lv)) => if let Some(rvv) = self.map.get(&lk) {
+                    self.group = Some((lv, rvv.iter()));
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_inner_with_map()`](trait.Joinkit.html#method.hash_join_inner_with_map) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerRef<'a, L, K, RV> 
+    where K: 'a,
+          RV: 'a,
+{
+    left: L,
+    map: &'a HashMap<K, Vec<RV>>,
+}
+
+impl<'a, L, K, RV> HashJoinInnerRef<'a, L, K, RV> 
+    where K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerRef` iterator.
+    pub fn new<LI>(left: LI, map: &'a HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinInnerRef {
+            left: left.into_iter(),
+            map,
+        }
+    }
+}
+
+impl<'a, L, K, LV, RV> Iterator for HashJoinInnerRef<'a, L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = (LV, &'a [RV]);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => match self.map.get(&lk) {
+                    Some(rvv) => return Some((lv, &rvv[..])),
+                    None => continue,
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_left_outer_with_map()`](trait.Joinkit.html#method.hash_join_left_outer_with_map)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinLeftOuterRef<'a, L, K, RV> 
+    where K: 'a,
+          RV: 'a,
+{
+    left: L,
+    map: &'a HashMap<K, Vec<RV>>,
+}
+
+impl<'a, L, K, RV> HashJoinLeftOuterRef<'a, L, K, RV> 
+    where K: Hash + Eq,
+{
+    /// Create a `HashJoinLeftOuterRef` iterator.
+    pub fn new<LI>(left: LI, map: &'a HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinLeftOuterRef {
+            left: left.into_iter(),
+            map,
+        }
+    }
+}
+
+impl<'a, L, K, LV, RV> Iterator for HashJoinLeftOuterRef<'a, L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = EitherOrBoth<LV, &'a [RV]>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.left.next() {
+            Some((lk, lv)) => match self.map.get(&lk) {
+                Some(rvv) => Some(Both(lv, &rvv[..])),
+                None => Some(Left(lv)),
+            },
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.left.size_hint()
+    }
+}
+
+/// See [`hash_join_inner_count()`](trait.Joinkit.html#method.hash_join_inner_count) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerCount<L, K> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, usize>,
+}
+
+impl<L, K> HashJoinInnerCount<L, K> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerCount` iterator.
+    pub fn new<LI, RI, RV>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        let mut map: HashMap<K, usize> = HashMap::new();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for (k, _) in right {
+                *map.entry(k).or_insert(0) += 1;
+            }
+        }
+        HashJoinInnerCount {
+            left,
+            map,
+        }
+    }
+}
+
+impl<L, K, LV> Iterator for HashJoinInnerCount<L, K> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = (LV, usize);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => match self.map.get(&lk) {
+                    Some(&n) => return Some((lv, n)),
+                    None => continue,
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_inner_reduce()`](trait.Joinkit.html#method.hash_join_inner_reduce) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinInnerReduce<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, RV>,
+}
+
+impl<L, K, RV> HashJoinInnerReduce<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinInnerReduce` iterator.
+    pub fn new<LI, RI, F>(left: LI, right: RI, mut reduce: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>,
+              F: FnMut(RV, RV) -> RV,
+    {
+        let mut left = left.into_iter().peekable();
+        let mut map: HashMap<K, RV> = HashMap::new();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for (k, v) in right {
+                // fold the value into the one already stored for the key, if any
+                let v = match map.remove(&k) {
+                    Some(acc) => reduce(acc, v),
+                    None => v,
+                };
+                map.insert(k, v);
+            }
+        }
+        HashJoinInnerReduce {
+            left,
+            map,
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinInnerReduce<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          RV: Clone,
+{
+    type Item = (LV, RV);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => match self.map.get(&lk) {
+                    Some(rv) => return Some((lv, rv.clone())),
+                    None => continue,
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_left_excl()`](trait.Joinkit.html#method.hash_join_left_excl) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinLeftExcl<L, K> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    set: HashSet<K>,
+}
+
+impl<L, K> HashJoinLeftExcl<L, K> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinLeftExcl` iterator.
+    pub fn new<LI, RI, RV>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinLeftExcl` iterator with the set pre-sized for `capacity` distinct right
+    /// keys.
+    pub fn with_capacity<LI, RI, RV>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        let mut set: HashSet<K> = HashSet::with_capacity(capacity);
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for (k, _) in right.into_iter() {
+                set.insert(k);
+            }
+        }
+        HashJoinLeftExcl {
+            left,
+            set,
+        }
+    }
+
+    /// Create a `HashJoinLeftExcl` iterator, which probes an already built set of the right keys
+    /// instead of consuming a right iterator.
+    pub fn from_set<LI>(left: LI, set: HashSet<K>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinLeftExcl {
+            left: left.into_iter().peekable(),
+            set,
+        }
+    }
+}
+
+impl<L, K, LV> Iterator for HashJoinLeftExcl<L, K> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = LV;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.left.next() {
+                Some((lk, lv)) => {
+                    if self.set.contains(&lk) {
+                        continue;
+                    } else {
+                        return Some(lv);
+                    }
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+/// See [`hash_join_left_outer()`](trait.Joinkit.html#method.hash_join_left_outer) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinLeftOuter<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, Vec<RV>>,
+}
+
+impl<L, K, RV> HashJoinLeftOuter<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinLeftOuter` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinLeftOuter` iterator with the map pre-sized for `capacity` distinct right keys.
+    pub fn with_capacity<LI, RI>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = left.into_iter().peekable();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        let map = if left.peek().is_some() {
+            group_into_map_with_capacity(right, capacity)
+        } else {
+            HashMap::new()
+        };
+        HashJoinLeftOuter {
+            left,
+            map,
+        }
+    }
+
+    /// Create a `HashJoinLeftOuter` iterator, which probes an already built map of the grouped right
+    /// values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinLeftOuter {
+            left: left.into_iter().peekable(),
+            map,
+        }
+    }
+
+    /// Create a `HashJoinLeftOuter` iterator, keeping only the first of the equal right values of
+    /// each key.
+    pub fn new_dedup<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>,
+              RV: Hash + Eq,
+    {
+        let mut left = left.into_iter().peekable();
+        let mut map = if left.peek().is_some() {group_into_map(right)} else {HashMap::new()};
+        for values in map.values_mut() {
+            dedup_values(values);
+        }
+        HashJoinLeftOuter {
+            left,
+            map,
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinLeftOuter<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          RV: Clone,
+{
+    type Item = EitherOrBoth<LV, Vec<RV>>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.left.next() {
+            Some((lk, lv)) => match self.map.get(&lk) {
+                Some(rvv) => Some(Both(lv, rvv.clone())),
+                None => Some(Left(lv)),
+            },
+            None => None,
+        }
+    }
+}
+
+/// See [`hash_join_left_option()`](trait.Joinkit.html#method.hash_join_left_option) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinLeftOption<L, K, RV> 
+    where L: Iterator,
+{
+    inner: HashJoinLeftOuter<L, K, RV>,
+}
+
+impl<L, K, RV> HashJoinLeftOption<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
+{
+    /// Create a `HashJoinLeftOption` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftOption {
+            inner: HashJoinLeftOuter::new(left, right),
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinLeftOption<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          RV: Clone,
+{
+    type Item = (LV, Option<Vec<RV>>);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|e| match e {
+            Left(lv) => (lv, None),
+            Both(lv, rvv) => (lv, Some(rvv)),
+            Right(_) => unreachable!("a left outer join yields no right-only values"),
+        })
+    }
+}
+
+/// See [`hash_join_left_outer_unmatched()`](trait.Joinkit.html#method.hash_join_left_outer_unmatched)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinLeftOuterUnmatched<L, K, RV> {
     left: L,
     map: HashMap<K, Vec<RV>>,
+    /// the keys of the left values yielded as `Left` so far
+    unmatched: Vec<K>,
 }
 
-impl<L, K, RV> HashJoinInner<L, K, RV> 
+impl<L, K, RV> HashJoinLeftOuterUnmatched<L, K, RV> 
     where K: Hash + Eq,
 {
-    /// Create a `HashJoinInner` iterator.
+    /// Create a `HashJoinLeftOuterUnmatched` iterator.
     pub fn new<LI, RI>(left: LI, right: RI) -> Self
         where L: Iterator<Item=LI::Item>,
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut map: HashMap<K, Vec<RV>> = HashMap::new();
-        for (k, v) in right.into_iter() {
-            let values = map.entry(k).or_insert(Vec::with_capacity(1));
-            values.push(v);
-        }
-        HashJoinInner {
+        HashJoinLeftOuterUnmatched {
             left: left.into_iter(),
-            map: map,
+            map: group_into_map(right),
+            unmatched: Vec::new(),
         }
     }
+
+    /// Return the keys of the unmatched left values yielded so far, in the order of the left
+    /// iterator.
+    ///
+    /// The keys are complete only once the iterator is exhausted.
+    pub fn unmatched_keys(&self) -> &[K] {
+        &self.unmatched
+    }
+
+    /// Consume the iterator and return the keys of the unmatched left values yielded so far.
+    pub fn into_unmatched_keys(self) -> Vec<K> {
+        self.unmatched
+    }
 }
 
-impl<L, K, LV, RV> Iterator for HashJoinInner<L, K, RV> 
+impl<L, K, LV, RV> Iterator for HashJoinLeftOuterUnmatched<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
           RV: Clone,
 {
-    type Item = (LV, Vec<RV>);
+    type Item = EitherOrBoth<LV, Vec<RV>>;
     
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.left.next() {
-                Some((lk, lv)) => match self.map.get(&lk) {
-                    Some(rvv) => return Some((lv, rvv.clone())),
-                    None => continue,
+        match self.left.next() {
+            Some((lk, lv)) => match self.map.get(&lk) {
+                Some(rvv) => Some(Both(lv, rvv.clone())),
+                None => {
+                    self.unmatched.push(lk);
+                    Some(Left(lv))
                 },
-                None => return None,
-            }
+            },
+            None => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.left.size_hint()
+    }
 }
 
-/// See [`hash_join_left_excl()`](trait.Joinkit.html#method.hash_join_left_excl) for the
-/// description and examples.
+/// See [`hash_join_first()`](trait.Joinkit.html#method.hash_join_first) for the description and
+/// examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
-pub struct HashJoinLeftExcl<L, K> {
-    left: L,
-    set: HashSet<K>,
+pub struct HashJoinFirst<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, RV>,
 }
 
-impl<L, K> HashJoinLeftExcl<L, K> 
-    where K: Hash + Eq,
+impl<L, K, RV> HashJoinFirst<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
 {
-    /// Create a `HashJoinLeftExcl` iterator.
-    pub fn new<LI, RI, RV>(left: LI, right: RI) -> Self
+    /// Create a `HashJoinFirst` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
         where L: Iterator<Item=LI::Item>,
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut set: HashSet<K> = HashSet::new();
-        for (k, _) in right.into_iter() {
-            set.insert(k);
+        let mut left = left.into_iter().peekable();
+        let mut map: HashMap<K, RV> = HashMap::new();
+        // an empty left iterator yields nothing, so the right one is not even consumed
+        if left.peek().is_some() {
+            for (k, v) in right {
+                // keep the first value, ignore the later duplicates
+                map.entry(k).or_insert(v);
+            }
         }
-        HashJoinLeftExcl {
-            left: left.into_iter(),
-            set: set,
+        HashJoinFirst {
+            left,
+            map,
         }
     }
 }
 
-impl<L, K, LV> Iterator for HashJoinLeftExcl<L, K> 
+impl<L, K, LV, RV> Iterator for HashJoinFirst<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
+          RV: Clone,
 {
-    type Item = LV;
+    type Item = (LV, Option<RV>);
     
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.left.next() {
-                Some((lk, lv)) => {
-                    if self.set.contains(&lk) {
-                        continue;
-                    } else {
-                        return Some(lv);
-                    }
-                },
-                None => return None,
-            }
+        match self.left.next() {
+            Some((lk, lv)) => Some((lv, self.map.get(&lk).cloned())),
+            None => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.left.size_hint()
+    }
 }
 
-/// See [`hash_join_left_outer()`](trait.Joinkit.html#method.hash_join_left_outer) for the
-/// description and examples.
+/// The error returned by
+/// [`hash_join_inner_unique_right()`](trait.Joinkit.html#method.hash_join_inner_unique_right) for
+/// a key appearing more than once in the right input iterator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey<K> {
+    /// the index (in base0) of the second occurrence of the key in the right input iterator
+    pub index: usize,
+    /// the duplicate key
+    pub key: K,
+}
+
+impl<K> fmt::Display for DuplicateKey<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the key at index {} of the right input is a duplicate", self.index)
+    }
+}
+
+impl<K: fmt::Debug> error::Error for DuplicateKey<K> {}
+
+/// See [`hash_join_inner_unique_right()`](trait.Joinkit.html#method.hash_join_inner_unique_right)
+/// for the description and examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
-pub struct HashJoinLeftOuter<L, K, RV> {
-    left: L,
-    map: HashMap<K, Vec<RV>>,
+pub struct HashJoinInnerUniqueRight<L, K, RV> 
+    where L: Iterator,
+{
+    left: Peekable<L>,
+    map: HashMap<K, RV>,
 }
 
-impl<L, K, RV> HashJoinLeftOuter<L, K, RV> 
-    where K: Hash + Eq,
+impl<L, K, RV> HashJoinInnerUniqueRight<L, K, RV> 
+    where L: Iterator,
+          K: Hash + Eq,
 {
-    /// Create a `HashJoinLeftOuter` iterator.
-    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+    /// Create a `HashJoinInnerUniqueRight` iterator, or fail on the first duplicate key of the
+    /// `right` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Result<Self, DuplicateKey<K>>
         where L: Iterator<Item=LI::Item>,
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut map: HashMap<K, Vec<RV>> = HashMap::new();
-        for (k, v) in right.into_iter() {
-            let values = map.entry(k).or_insert(Vec::with_capacity(1));
-            values.push(v);
-        }
-        HashJoinLeftOuter {
-            left: left.into_iter(),
-            map: map,
+        let mut left = left.into_iter().peekable();
+        let mut map: HashMap<K, RV> = HashMap::new();
+        // an empty left iterator yields nothing, so the right one is neither consumed, nor checked
+        // for the duplicates
+        if left.peek().is_some() {
+            for (index, (k, v)) in right.into_iter().enumerate() {
+                if map.contains_key(&k) {
+                    return Err(DuplicateKey { index, key: k });
+                }
+                map.insert(k, v);
+            }
         }
+        Ok(HashJoinInnerUniqueRight {
+            left,
+            map,
+        })
     }
 }
 
-impl<L, K, LV, RV> Iterator for HashJoinLeftOuter<L, K, RV> 
+impl<L, K, LV, RV> Iterator for HashJoinInnerUniqueRight<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
           RV: Clone,
 {
-    type Item = EitherOrBoth<LV, Vec<RV>>;
+    type Item = (LV, RV);
     
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.left.next() {
                 Some((lk, lv)) => match self.map.get(&lk) {
-                    Some(rvv) => return Some(Both(lv, rvv.clone())),
-                    None => return Some(Left(lv)),
+                    Some(rv) => return Some((lv, rv.clone())),
+                    None => continue,
                 },
                 None => return None,
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // the unmatched left values are skipped
+        (0, self.left.size_hint().1)
+    }
 }
 
 /// See [`hash_join_right_excl()`](trait.Joinkit.html#method.hash_join_right_excl) for the
@@ -198,14 +1142,32 @@ impl<L, K, RV> HashJoinRightExcl<L, K, RV>
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut map: HashMap<K, (Vec<RV>, bool)> = HashMap::new();
-        for (k, v) in right.into_iter() {
-            let values = map.entry(k).or_insert((Vec::with_capacity(1), false));
-            values.0.push(v);
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinRightExcl` iterator with the map pre-sized for `capacity` distinct right keys.
+    pub fn with_capacity<LI, RI>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let map = flag_into_map(right, capacity);
+        HashJoinRightExcl {
+            left: left.into_iter(),
+            map,
+            excl_iter: None,
         }
+    }
+
+    /// Create a `HashJoinRightExcl` iterator, which probes an already built map of the grouped right
+    /// values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
         HashJoinRightExcl {
             left: left.into_iter(),
-            map: map,
+            map: flag_map(map),
             excl_iter: None,
         }
     }
@@ -215,18 +1177,17 @@ impl<L, K, RV> HashJoinRightExcl<L, K, RV>
     /// Once the left iterator is exhausted, the info about which keys were matched is complete.
     /// To be able to iterate over map's values we need to move it into its `IntoIter`.
     fn set_excl_iter(&mut self) {
-        let map = mem::replace(&mut self.map, HashMap::<K, (Vec<RV>, bool)>::new());
+        let map = mem::take(&mut self.map);
         self.excl_iter = Some(map.into_iter());
     }
 }
 
-impl<L, K, LV, RV> Iterator for HashJoinRightExcl<L, K, RV> 
+impl<L, K, LV, RV> HashJoinRightExcl<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
 {
-    type Item = Vec<RV>;
-    
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Advance the join, yielding the next unmatched right key together with its values.
+    fn next_with_key(&mut self) -> Option<(K, Vec<RV>)> {
         loop {
             match self.excl_iter {
                 // the left iterator is not yet exhausted
@@ -242,9 +1203,9 @@ impl<L, K, LV, RV> Iterator for HashJoinRightExcl<L, K, RV>
                 },
                 // iterate over unmatched values
                 Some(ref mut r) => match r.next() {
-                    Some((_, (rvv, matched))) => {
+                    Some((rk, (rvv, matched))) => {
                         if !matched {
-                            return Some(rvv);
+                            return Some((rk, rvv));
                         } else {
                             continue;
                         }
@@ -256,6 +1217,92 @@ impl<L, K, LV, RV> Iterator for HashJoinRightExcl<L, K, RV>
     }
 }
 
+impl<L, K, LV, RV> Iterator for HashJoinRightExcl<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = Vec<RV>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_key().map(|(_, rvv)| rvv)
+    }
+}
+
+/// See [`hash_join_right_excl_with_key()`](trait.Joinkit.html#method.hash_join_right_excl_with_key)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinRightExclWithKey<L, K, RV> {
+    inner: HashJoinRightExcl<L, K, RV>,
+}
+
+impl<L, K, RV> HashJoinRightExclWithKey<L, K, RV> 
+    where K: Hash + Eq,
+{
+    /// Create a `HashJoinRightExclWithKey` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightExclWithKey {
+            inner: HashJoinRightExcl::new(left, right),
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinRightExclWithKey<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    type Item = (K, Vec<RV>);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_with_key()
+    }
+}
+
+/// The unmatched right values of a right or full outer hash join, see
+/// [`HashJoinRightOuter::into_right_excl()`](struct.HashJoinRightOuter.html#method.into_right_excl).
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinUnmatchedRight<K, RV> {
+    iter: IntoIter<K, (Vec<RV>, bool)>,
+}
+
+impl<K, RV> HashJoinUnmatchedRight<K, RV> 
+    where K: Hash + Eq,
+{
+    /// Flags the right values in the `map` matched by the rest of the `left` iterator, unless it
+    /// is exhausted already, i.e. the `excl_iter` has been created.
+    fn drain<L, LV>(left: L, 
+                    mut map: HashMap<K, (Vec<RV>, bool)>, 
+                    excl_iter: Option<IntoIter<K, (Vec<RV>, bool)>>) -> Self
+        where L: Iterator<Item=(K, LV)>,
+    {
+        let iter = match excl_iter {
+            Some(iter) => iter,
+            None => {
+                for (lk, _) in left {
+                    if let Some(rt) = map.get_mut(&lk) {
+                        rt.1 = true; // flag as matched
+                    }
+                }
+                map.into_iter()
+            },
+        };
+        HashJoinUnmatchedRight {
+            iter,
+        }
+    }
+}
+
+impl<K, RV> Iterator for HashJoinUnmatchedRight<K, RV> {
+    type Item = Vec<RV>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|&(_, (_, matched))| !matched).map(|(_, (rvv, _))| rvv)
+    }
+}
+
 /// See [`hash_join_right_outer()`](trait.Joinkit.html#method.hash_join_right_outer) for the
 /// description and examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
@@ -276,14 +1323,32 @@ impl<L, K, RV> HashJoinRightOuter<L, K, RV>
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut map: HashMap<K, (Vec<RV>, bool)> = HashMap::new();
-        for (k, v) in right.into_iter() {
-            let values = map.entry(k).or_insert((Vec::with_capacity(1), false));
-            values.0.push(v);
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinRightOuter` iterator with the map pre-sized for `capacity` distinct right keys.
+    pub fn with_capacity<LI, RI>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let map = flag_into_map(right, capacity);
+        HashJoinRightOuter {
+            left: left.into_iter(),
+            map,
+            excl_iter: None,
         }
+    }
+
+    /// Create a `HashJoinRightOuter` iterator, which probes an already built map of the grouped right
+    /// values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
         HashJoinRightOuter {
             left: left.into_iter(),
-            map: map,
+            map: flag_map(map),
             excl_iter: None,
         }
     }
@@ -293,11 +1358,41 @@ impl<L, K, RV> HashJoinRightOuter<L, K, RV>
     /// Once the left iterator is exhausted, the info about which keys were matched is complete.
     /// To be able to iterate over map's values we need to move it into its `IntoIter`.
     fn set_excl_iter(&mut self) {
-        let map = mem::replace(&mut self.map, HashMap::<K, (Vec<RV>, bool)>::new());
+        let map = mem::take(&mut self.map);
         self.excl_iter = Some(map.into_iter());
     }
 }
 
+impl<L, K, LV, RV> HashJoinRightOuter<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    /// Consume the adaptor and return an iterator over the unmatched right values only, e.g. to
+    /// handle them separately, once the matched ones have been processed.
+    ///
+    /// If the left iterator is not exhausted yet, the remaining left values are consumed first,
+    /// only to flag the right values they match, so they are not yielded at all.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::Both;
+    ///
+    /// let l = vec![("1", "1;A"), ("2", "2;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Y"), ("3", "3;Z")].into_iter();
+    /// let mut it = l.hash_join_right_outer(r);
+    ///
+    /// // the first phase: only the first left value is joined here
+    /// assert_eq!(it.next(), Some(Both("1;A", vec!["1;X"])));
+    /// // the second phase: the rest of the left values is drained, without being yielded
+    /// let mut excl = it.into_right_excl();
+    /// assert_eq!(excl.next(), Some(vec!["3;Z"]));
+    /// assert_eq!(excl.next(), None);
+    /// ```
+    pub fn into_right_excl(self) -> HashJoinUnmatchedRight<K, RV> {
+        HashJoinUnmatchedRight::drain(self.left, self.map, self.excl_iter)
+    }
+}
+
 impl<L, K, LV, RV> Iterator for HashJoinRightOuter<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
@@ -336,6 +1431,103 @@ impl<L, K, LV, RV> Iterator for HashJoinRightOuter<L, K, RV>
     }
 }
 
+/// See [`hash_join_right_outer_flat()`](trait.Joinkit.html#method.hash_join_right_outer_flat) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct HashJoinRightOuterFlat<L, K, LV, RV> {
+    left: L,
+    map: HashMap<K, (Vec<RV>, bool)>,
+    /// the current left value along with its key and the index of the next matched right value
+    group: Option<(LV, K, usize)>,
+    /// exclusion iterator - yields the unmatched values from the map. It is created once the left
+    /// iterator is exhausted
+    excl_iter: Option<IntoIter<K, (Vec<RV>, bool)>>,
+    /// the unmatched right values of the current key
+    excl_group: vec::IntoIter<RV>,
+}
+
+impl<L, K, LV, RV> HashJoinRightOuterFlat<L, K, LV, RV> 
+    where K: Hash + Eq,
+{
+    /// Create a `HashJoinRightOuterFlat` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightOuterFlat {
+            left: left.into_iter(),
+            map: flag_into_map(right, 0),
+            group: None,
+            excl_iter: None,
+            excl_group: Vec::new().into_iter(),
+        }
+    }
+
+    /// Create a `HashJoinRightOuterFlat` iterator, which probes an already built map of the grouped
+    /// right values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
+        HashJoinRightOuterFlat {
+            left: left.into_iter(),
+            map: flag_map(map),
+            group: None,
+            excl_iter: None,
+            excl_group: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<L, K, LV, RV> Iterator for HashJoinRightOuterFlat<L, K, LV, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+          LV: Clone,
+          RV: Clone,
+{
+    type Item = EitherOrBoth<LV, RV>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((lv, lk, i)) = self.group.take() {
+                let rvv = &self.map[&lk].0;
+                // the last right value takes the left value, the others clone it
+                if i + 1 == rvv.len() {
+                    return Some(Both(lv, rvv[i].clone()));
+                }
+                let item = Both(lv.clone(), rvv[i].clone());
+                self.group = Some((lv, lk, i + 1));
+                return Some(item);
+            }
+            if let Some(rv) = self.excl_group.next() {
+                return Some(Right(rv));
+            }
+            match self.excl_iter {
+                // the left iterator is not yet exhausted
+                None => match self.left.next() {
+                    Some((lk, lv)) => if let Some(rt) = self.map.get_mut(&lk) {
+                        rt.1 = true; // flag as matched
+                        self.group = Some((lv, lk, 0));
+                    },
+                    // the left iterator is exhausted so move the map into `self.excl_iter`.
+                    None => {
+                        let map = mem::take(&mut self.map);
+                        self.excl_iter = Some(map.into_iter());
+                    },
+                },
+                // iterate over unmatched values
+                Some(ref mut r) => match r.next() {
+                    Some((_, (rvv, matched))) => if !matched {
+                        self.excl_group = rvv.into_iter();
+                    },
+                    None => return None,
+                }
+            }
+        }
+    }
+}
+
 /// See [`hash_join_full_outer()`](trait.Joinkit.html#method.hash_join_full_outer) for the
 /// description and examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
@@ -356,14 +1548,32 @@ impl<L, K, RV> HashJoinFullOuter<L, K, RV>
               LI: IntoIterator<IntoIter=L>,
               RI: IntoIterator<Item=(K, RV)>
     {
-        let mut map: HashMap<K, (Vec<RV>, bool)> = HashMap::new();
-        for (k, v) in right.into_iter() {
-            let values = map.entry(k).or_insert((Vec::with_capacity(1), false));
-            values.0.push(v);
+        Self::with_capacity(left, right, 0)
+    }
+
+    /// Create a `HashJoinFullOuter` iterator with the map pre-sized for `capacity` distinct right keys.
+    pub fn with_capacity<LI, RI>(left: LI, right: RI, capacity: usize) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let map = flag_into_map(right, capacity);
+        HashJoinFullOuter {
+            left: left.into_iter(),
+            map,
+            excl_iter: None,
         }
+    }
+
+    /// Create a `HashJoinFullOuter` iterator, which probes an already built map of the grouped right
+    /// values instead of consuming a right iterator.
+    pub fn from_map<LI>(left: LI, map: HashMap<K, Vec<RV>>) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+    {
         HashJoinFullOuter {
             left: left.into_iter(),
-            map: map,
+            map: flag_map(map),
             excl_iter: None,
         }
     }
@@ -373,11 +1583,41 @@ impl<L, K, RV> HashJoinFullOuter<L, K, RV>
     /// Once the left iterator is exhausted, the info about which keys were matched is complete.
     /// To be able to iterate over map's values we need to move it into its `IntoIter`.
     fn set_excl_iter(&mut self) {
-        let map = mem::replace(&mut self.map, HashMap::<K, (Vec<RV>, bool)>::new());
+        let map = mem::take(&mut self.map);
         self.excl_iter = Some(map.into_iter());
     }
 }
 
+impl<L, K, LV, RV> HashJoinFullOuter<L, K, RV> 
+    where L: Iterator<Item=(K, LV)>,
+          K: Hash + Eq,
+{
+    /// Consume the adaptor and return an iterator over the unmatched right values only, e.g. to
+    /// handle them separately, once the matched ones have been processed.
+    ///
+    /// If the left iterator is not exhausted yet, the remaining left values are consumed first,
+    /// only to flag the right values they match, so they are not yielded at all, neither matched nor unmatched.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::Both;
+    ///
+    /// let l = vec![("1", "1;A"), ("2", "2;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Y"), ("3", "3;Z")].into_iter();
+    /// let mut it = l.hash_join_full_outer(r);
+    ///
+    /// // the first phase: only the first left value is joined here
+    /// assert_eq!(it.next(), Some(Both("1;A", vec!["1;X"])));
+    /// // the second phase: the rest of the left values is drained, without being yielded
+    /// let mut excl = it.into_right_excl();
+    /// assert_eq!(excl.next(), Some(vec!["3;Z"]));
+    /// assert_eq!(excl.next(), None);
+    /// ```
+    pub fn into_right_excl(self) -> HashJoinUnmatchedRight<K, RV> {
+        HashJoinUnmatchedRight::drain(self.left, self.map, self.excl_iter)
+    }
+}
+
 impl<L, K, LV, RV> Iterator for HashJoinFullOuter<L, K, RV> 
     where L: Iterator<Item=(K, LV)>,
           K: Hash + Eq,
@@ -0,0 +1,128 @@
+//! The join types shared by both strategies, as named on the command line, and the strategies
+//! themselves.
+
+use core::fmt;
+use core::str::FromStr;
+
+/// The type of the join, see the [`Joinkit`](trait.Joinkit.html) adaptors for their semantics.
+///
+/// It can be parsed from, and is displayed as, the name used by the `--mode` option of the
+/// binaries.
+///
+/// ```
+/// use joinkit::JoinMode;
+///
+/// assert_eq!(Ok(JoinMode::LeftOuter), "left-outer".parse());
+/// assert_eq!("left-outer", JoinMode::LeftOuter.to_string());
+/// assert!("left".parse::<JoinMode>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinMode {
+    /// An intersection between the left and the right input
+    Inner,
+    /// A difference between the left and the right input
+    LeftExcl,
+    /// A union of `Inner` and `LeftExcl`
+    LeftOuter,
+    /// A difference between the right and the left input
+    RightExcl,
+    /// A union of `Inner` and `RightExcl`
+    RightOuter,
+    /// A union of `Inner`, `LeftExcl` and `RightExcl`
+    FullOuter,
+}
+
+impl JoinMode {
+    /// The names of all the join modes, in the order of the variants.
+    pub const NAMES: [&'static str; 6] = ["inner",
+                                          "left-excl",
+                                          "left-outer",
+                                          "right-excl",
+                                          "right-outer",
+                                          "full-outer",];
+
+    /// Return the name of the join mode.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            JoinMode::Inner => JoinMode::NAMES[0],
+            JoinMode::LeftExcl => JoinMode::NAMES[1],
+            JoinMode::LeftOuter => JoinMode::NAMES[2],
+            JoinMode::RightExcl => JoinMode::NAMES[3],
+            JoinMode::RightOuter => JoinMode::NAMES[4],
+            JoinMode::FullOuter => JoinMode::NAMES[5],
+        }
+    }
+
+    /// Return `true` if the join yields the left values without a match.
+    pub fn keeps_unmatched_left(&self) -> bool {
+        matches!(*self, JoinMode::LeftExcl | JoinMode::LeftOuter | JoinMode::FullOuter)
+    }
+
+    /// Return `true` if the join yields the right values without a match.
+    pub fn keeps_unmatched_right(&self) -> bool {
+        matches!(*self, JoinMode::RightExcl | JoinMode::RightOuter | JoinMode::FullOuter)
+    }
+
+    /// Return the join mode yielding the same results with the left and the right input swapped,
+    /// e.g. `RightOuter` for `LeftOuter`.
+    pub fn swapped(&self) -> JoinMode {
+        match *self {
+            JoinMode::LeftExcl => JoinMode::RightExcl,
+            JoinMode::LeftOuter => JoinMode::RightOuter,
+            JoinMode::RightExcl => JoinMode::LeftExcl,
+            JoinMode::RightOuter => JoinMode::LeftOuter,
+            m => m,
+        }
+    }
+}
+
+impl fmt::Display for JoinMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JoinMode {
+    type Err = ParseJoinModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "inner" => Ok(JoinMode::Inner),
+            "left-excl" => Ok(JoinMode::LeftExcl),
+            "left-outer" => Ok(JoinMode::LeftOuter),
+            "right-excl" => Ok(JoinMode::RightExcl),
+            "right-outer" => Ok(JoinMode::RightOuter),
+            "full-outer" => Ok(JoinMode::FullOuter),
+            _ => Err(ParseJoinModeError(())),
+        }
+    }
+}
+
+/// The error returned when parsing an unknown join mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseJoinModeError(());
+
+impl fmt::Display for ParseJoinModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("unknown join mode, expected one of: ")?;
+        for (i, name) in JoinMode::NAMES.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for ParseJoinModeError {}
+
+/// The join strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Hash Join - the right input is loaded into `HashMap`
+    Hash,
+    /// Merge Join - both inputs must be sorted on the key
+    Merge,
+}
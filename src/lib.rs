@@ -1,5 +1,6 @@
 #![warn(missing_docs)]
 #![crate_name="joinkit"]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! Joinkit provides iterator adaptors for efficient SQL-like joins.
 //! 
@@ -7,13 +8,13 @@
 //!
 //! There are two join strategies, which fit different scenarios:
 //! - **Hash Join** - a shorter data stream is loaded entirely into memory (`HashMap`), while the
-//! longer can be arbitrarily large and is matched against `HashMap` sequentially. The greatest
-//! advantage is that data do not need to be sorted and it has amortized O(n) complexity, therefore
-//! it is very efficient.  This is the right choice if data is not sorted and the smaller stream
-//! fits into memory. 
+//!   longer can be arbitrarily large and is matched against `HashMap` sequentially. The greatest
+//!   advantage is that data do not need to be sorted and it has amortized O(n) complexity, therefore
+//!   it is very efficient.  This is the right choice if data is not sorted and the smaller stream
+//!   fits into memory. 
 //! - **Merge Join** - the data streams *must* be sorted, but can be *both* arbitrarily large. This
-//! is the right choice if the data is already sorted, as in this case it is slightly more
-//! efficient than Hash Join. 
+//!   is the right choice if the data is already sorted, as in this case it is slightly more
+//!   efficient than Hash Join. 
 //!
 //! To use the iterator adaptors in this crate, import `Joinkit trait`:
 //!
@@ -21,38 +22,125 @@
 //! use joinkit::Joinkit;
 //! ```
 //!
-//! The crate contains also 2 binaries `hjoin` and `mjoin`, which can be used to perform `Hash
-//! Join` and `Merge Join` on command line. 
+//! The hash join adaptors, as well as the `util` and `cli` modules, require the default `std`
+//! feature. Without it, the crate is `no_std` and provides the merge join adaptors only, which
+//! need nothing but the `alloc` crate. The optional `regex` feature lets the binaries split the
+//! fields on a regular expression, see [`util::Separator`](util/trait.Separator.html). The optional
+//! `serde` feature makes [`EitherOrBoth`](enum.EitherOrBoth.html) (de)serializable.
+//!
+//! The crate contains also 3 binaries: `join`, which can perform either `Hash Join` or `Merge
+//! Join` on command line, selected by `--strategy`, and `hjoin` and `mjoin`, which perform `Hash
+//! Join` and `Merge Join` respectively. 
 
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate clap;
-extern crate itertools;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
 
-use std::iter::{IntoIterator};
-use std::cmp::Ordering;
+use core::iter::{IntoIterator};
+use core::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use std::vec;
+#[cfg(feature = "std")]
 use std::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
-pub use merge_join::{MergeJoinInner, MergeJoinLeftExcl, MergeJoinLeftOuter, MergeJoinFullOuter};
-pub use hash_join::{HashJoinInner, HashJoinLeftExcl, HashJoinLeftOuter, HashJoinRightExcl,
-HashJoinRightOuter, HashJoinFullOuter};
+pub use merge_join::{MergeJoinKey, KeyCmp, GroupRef, CappedGroupRef, MergeJoinInner,
+MergeJoinInnerProgress, MergeJoinInnerKeyed, MergeJoinInnerRef, MergeJoinInterval, MergeJoinInner3,
+MergeJoinMapInner, MergeJoinInnerGroups, MergeJoinInnerByThen, MergeJoinInnerGroupsCapped,
+OverflowAction, GroupTooLarge, MergeJoinRecords, MergeJoinLeftExcl, MergeJoinLeftExclKeyed,
+MergeJoinLeftExclContext, MergeJoinLeftOuter, MergeJoinLeftOption, MergeJoinLeftOuterGroups,
+MergeJoinFullOuter, TieBreak, MergeJoinFullOuterTieBreak};
+#[cfg(feature = "std")]
+pub use hash_join::{group_into_map, HashJoinMap, HashJoinInner, HashJoinInnerTake, HashJoinInnerPairs,
+HashJoinInnerPairsRef, HashJoinInnerRef, HashJoinInnerCount, HashJoinInnerReduce, HashJoinLeftExcl,
+HashJoinLeftOuter, HashJoinLeftOption, HashJoinLeftOuterRef, HashJoinLeftOuterUnmatched,
+HashJoinFirst, HashJoinInnerUniqueRight, DuplicateKey, HashJoinRightExcl, HashJoinRightExclWithKey,
+HashJoinUnmatchedRight, HashJoinRightOuter, HashJoinRightOuterFlat, HashJoinFullOuter};
 
+pub use adaptors::{WithKey, CheckSortedBy, AssertSortedBy, OutOfOrder, ChainSortedBy, Ungroup,
+GroupJoinedByKey};
+pub use join_mode::{JoinMode, ParseJoinModeError, Strategy};
+
+#[cfg(feature = "std")]
 pub mod util;
+#[cfg(feature = "std")]
+pub mod cli;
 mod merge_join;
+#[cfg(feature = "std")]
 mod hash_join;
+mod adaptors;
+mod join_mode;
 
 /// A value yielded by `merge_join` and `hash_join` outer iterators.
 /// Contains one or two values, depending on which input iterator is exhausted.
 ///
-#[derive(Clone, PartialEq, Eq, Debug)]
+/// The values are ordered by the variant first, as in `Left < Both < Right`, and then by the
+/// contained values.
+///
+/// With the `serde` feature, it is (de)serialized externally tagged, e.g. `{"Both": [l, r]}`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EitherOrBoth<L, R> {
-    /// Neither input iterator is exhausted yet, yielding two values.
-    Both(L, R),
     /// The parameter iterator is exhausted, only yielding a value from the `self` iterator.
     Left(L),
+    /// Neither input iterator is exhausted yet, yielding two values.
+    Both(L, R),
     /// The `self` iterator is exhausted, only yielding a value from the parameter iterator.
     Right(R),
 }
 
+impl<L, R> EitherOrBoth<L, R> {
+    /// Convert from `&EitherOrBoth<L, R>` to `EitherOrBoth<&L, &R>`, the same as
+    /// `Option::as_ref()`, e.g. to inspect the values without consuming the join result.
+    ///
+    /// ```
+    /// use joinkit::EitherOrBoth::{self, Both};
+    ///
+    /// let e: EitherOrBoth<String, u32> = Both("a".to_owned(), 1);
+    /// assert_eq!(e.as_ref(), Both(&"a".to_owned(), &1));
+    /// ```
+    pub fn as_ref(&self) -> EitherOrBoth<&L, &R> {
+        match *self {
+            EitherOrBoth::Left(ref l) => EitherOrBoth::Left(l),
+            EitherOrBoth::Both(ref l, ref r) => EitherOrBoth::Both(l, r),
+            EitherOrBoth::Right(ref r) => EitherOrBoth::Right(r),
+        }
+    }
+
+    /// Convert from `&mut EitherOrBoth<L, R>` to `EitherOrBoth<&mut L, &mut R>`, the same as
+    /// `Option::as_mut()`, e.g. to modify the values in place.
+    ///
+    /// ```
+    /// use joinkit::EitherOrBoth::{self, Both, Left};
+    ///
+    /// let mut e: EitherOrBoth<String, String> = Both(" a".to_owned(), "B".to_owned());
+    /// if let Both(l, r) = e.as_mut() {
+    ///     *l = l.trim().to_owned();
+    ///     r.make_ascii_lowercase();
+    /// }
+    /// assert_eq!(e, Both("a".to_owned(), "b".to_owned()));
+    /// ```
+    pub fn as_mut(&mut self) -> EitherOrBoth<&mut L, &mut R> {
+        match *self {
+            EitherOrBoth::Left(ref mut l) => EitherOrBoth::Left(l),
+            EitherOrBoth::Both(ref mut l, ref mut r) => EitherOrBoth::Both(l, r),
+            EitherOrBoth::Right(ref mut r) => EitherOrBoth::Right(r),
+        }
+    }
+}
+
 /// Trait `Joinkit` provides the extra iterator adaptors for efficient SQL-like joins.
 pub trait Joinkit : Iterator {
     /// Return an iterator adaptor that [inner
@@ -82,7 +170,376 @@ pub trait Joinkit : Iterator {
               R: IntoIterator,
               F: FnMut(&Self::Item, &R::Item) -> Ordering
     {
-        MergeJoinInner::new(self, other.into_iter(), cmp)
+        MergeJoinInner::new(self, other, cmp)
+    }
+
+    /// Same as [`merge_join_inner_by()`](#method.merge_join_inner_by), but the `on_step` closure is
+    /// called with the number of the left items processed so far after every `every` of them, e.g.
+    /// to show the progress of a long join.
+    ///
+    /// The overhead is a counter increment per left item, plus the call of `on_step` every `every`
+    /// items. **Note**, the left items following the end of the right iterator are not processed
+    /// at all, so they are not counted.
+    ///
+    /// Iterator element type is `(L::Item, R::Item)`.
+    ///
+    /// # Panics
+    ///
+    /// If `every` is zero.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e')].into_iter();
+    /// let r = vec![(2, 'x'), (4, 'y'), (5, 'z')].into_iter();
+    /// let mut steps = Vec::new();
+    /// let joined: Vec<_> = l
+    ///     .merge_join_inner_by_with_progress(r, |x, y| Ord::cmp(&x.0, &y.0), 2, |n| steps.push(n))
+    ///     .collect();
+    ///
+    /// assert_eq!(joined.len(), 3);
+    /// assert_eq!(steps, vec![2, 4]);
+    /// ```
+    fn merge_join_inner_by_with_progress<R, F, P>(self, other: R, cmp: F, every: usize, on_step: P) 
+        -> MergeJoinInnerProgress<Self, R::IntoIter, F, P> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering,
+              P: FnMut(usize),
+    {
+        MergeJoinInnerProgress::new(self, other, cmp, every, on_step)
+    }
+
+    /// Return an iterator adaptor that inner joins the two input iterators of `(key, value)`
+    /// tuples in ascending order, the same as [`merge_join_inner_by()`](#method.merge_join_inner_by),
+    /// except the `cmp` closure compares only the keys.
+    ///
+    /// The key of the most recently yielded pair can be inspected by
+    /// [`last_key()`](struct.MergeJoinInnerKeyed.html#method.last_key), e.g. to correlate the
+    /// output with the driving key while debugging. The key is cloned into the adaptor, reusing the
+    /// allocation of the previous one, if any.
+    ///
+    /// Iterator element type is `((K, LV), (K, RV))`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(0, "0;A"), (1, "1;B")].into_iter();
+    /// let r = vec![(1, "1;X"), (2, "2;Z")].into_iter();
+    /// let mut it = l.merge_join_inner_keyed_by(r, Ord::cmp);
+    /// assert_eq!(it.last_key(), None);
+    ///
+    /// assert_eq!(it.next(), Some(((1, "1;B"), (1, "1;X"))));
+    /// assert_eq!(it.last_key(), Some(&1));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner_keyed_by<R, K, LV, RV, F>(self, other: R, cmp: F) 
+        -> MergeJoinInnerKeyed<Self, R::IntoIter, K, F> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              R: IntoIterator<Item=(K, RV)>,
+              K: Clone,
+              F: FnMut(&K, &K) -> Ordering
+    {
+        MergeJoinInnerKeyed::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators of
+    /// references in ascending order, e.g. two slices joined via `.iter()`.
+    ///
+    /// This is the same as [`merge_join_inner_by()`](#method.merge_join_inner_by), except the
+    /// `cmp` closure takes the referenced items directly and the references are yielded as they
+    /// are, so the items are neither moved nor cloned and need not be `Clone`.
+    ///
+    /// Iterator element type is `(&LT, &RT)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l: &[(u32, &str)] = &[(0, "0;A"), (1, "1;B")];
+    /// let r: &[(u32, &str)] = &[(1, "1;X"), (2, "2;Z")];
+    /// let mut it = l.iter().merge_join_inner_ref_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// assert_eq!(it.next(), Some((&(1, "1;B"), &(1, "1;X"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner_ref_by<'a, 'b, LT, RT, R, F>(self, other: R, cmp: F) 
+                                                     -> MergeJoinInnerRef<Self, R::IntoIter, F> 
+        where Self: Sized + Iterator<Item=&'a LT>,
+              R: IntoIterator<Item=&'b RT>,
+              LT: 'a + ?Sized,
+              RT: 'b + ?Sized,
+              F: FnMut(&LT, &RT) -> Ordering
+    {
+        MergeJoinInnerRef::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that joins the left keys extracted by the `key` closure to the
+    /// ranges `[lo, hi]` (inclusive) of the right input iterator of references containing them,
+    /// e.g. to look up the IP addresses in a table of address blocks.
+    ///
+    /// The left input iterator must be sorted by the key and the ranges must be sorted by `lo` and
+    /// must not overlap, both in ascending order. The ranges are then skipped monotonically as the
+    /// left keys increase, and each left item is yielded along with the one range containing its
+    /// key, if any. The left items outside of all the ranges are skipped. A range may contain any
+    /// number of the left keys and is not cloned.
+    ///
+    /// Iterator element type is `(Self::Item, &RT)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let blocks: &[(u32, u32, &str)] = &[(10, 19, "A"), (30, 39, "B")];
+    /// let hits = vec![(5, "x"), (12, "y"), (19, "z"), (25, "w"), (30, "v")].into_iter();
+    /// let mut it = hits.merge_join_interval_by(blocks, |h| h.0, |b| b.0, |b| b.1);
+    ///
+    /// assert_eq!(it.next(), Some(((12, "y"), &(10, 19, "A"))));
+    /// assert_eq!(it.next(), Some(((19, "z"), &(10, 19, "A"))));
+    /// assert_eq!(it.next(), Some(((30, "v"), &(30, 39, "B"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_interval_by<'b, RT, R, K, KF, LF, HF>(self, ranges: R, key: KF, lo: LF, hi: HF)
+        -> MergeJoinInterval<Self, R::IntoIter, KF, LF, HF>
+        where Self: Sized,
+              R: IntoIterator<Item=&'b RT>,
+              RT: 'b + ?Sized,
+              K: Ord,
+              KF: FnMut(&Self::Item) -> K,
+              LF: FnMut(&RT) -> K,
+              HF: FnMut(&RT) -> K,
+    {
+        MergeJoinInterval::new(self, ranges, key, lo, hi)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the three input iterators
+    /// in ascending order, in a single pass. The resulting iterator is the intersection of the
+    /// three input iterators.
+    ///
+    /// This is the same as joining the result of
+    /// [`merge_join_inner_by()`](#method.merge_join_inner_by) with the third iterator, without the
+    /// intermediate pairs. The `self` iterator is compared to `b` by `cmp_ab` and `b` is compared
+    /// to `c` by `cmp_bc`, so the `b` iterator is the pivot: unless the `self` item and the `b`
+    /// item compare equal, the lesser of them is skipped; otherwise the `b` item and the `c` item
+    /// are compared likewise. Only if all three items compare equal, they are yielded together.
+    ///
+    /// All three input iterators must be sorted and unique on the join key to produce the correct
+    /// results.
+    ///
+    /// Iterator element type is `(Self::Item, B::Item, C::Item)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let a = vec![(0, "0;A"), (1, "1;B"), (2, "2;C"), (4, "4;E")].into_iter();
+    /// let b = vec![(1, "1;X"), (2, "2;Y"), (3, "3;Z")].into_iter();
+    /// let c = vec![(2, 'q'), (3, 'r'), (4, 's')].into_iter();
+    /// let mut it = a.merge_join_inner3_by(b, c, |x, y| Ord::cmp(&x.0, &y.0), 
+    ///                                           |y, z| Ord::cmp(&y.0, &z.0));
+    ///
+    /// assert_eq!(it.next(), Some(((2, "2;C"), (2, "2;Y"), (2, 'q'))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner3_by<B, C, F, G>(self, b: B, c: C, cmp_ab: F, cmp_bc: G) 
+                                        -> MergeJoinInner3<Self, B::IntoIter, C::IntoIter, F, G> 
+        where Self: Sized,
+              B: IntoIterator,
+              C: IntoIterator,
+              F: FnMut(&Self::Item, &B::Item) -> Ordering,
+              G: FnMut(&B::Item, &C::Item) -> Ordering
+    {
+        MergeJoinInner3::new(self, b, c, cmp_ab, cmp_bc)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order, fusing each matched pair into a single value by the `merge` closure.
+    ///
+    /// This is the same as [`merge_join_inner_by()`](#method.merge_join_inner_by) followed by
+    /// `map()`, but the joined values can be built directly, without the intermediate tuple.
+    ///
+    /// Iterator element type is `O`, the type returned by `merge`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Row { id: u32, a: &'static str, b: &'static str }
+    ///
+    /// let l = vec![(0, "A"), (1, "B")].into_iter();
+    /// let r = vec![(1, "X"), (2, "Z")].into_iter();
+    /// let mut it = l.merge_join_map_inner_by(r, 
+    ///                                        |x, y| Ord::cmp(&x.0, &y.0), 
+    ///                                        |(id, a), (_, b)| Row { id, a, b });
+    ///
+    /// assert_eq!(it.next(), Some(Row { id: 1, a: "B", b: "X" }));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_map_inner_by<R, F, M, O>(self, other: R, cmp: F, merge: M) 
+                                           -> MergeJoinMapInner<Self, R::IntoIter, F, M> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering,
+              M: FnMut(Self::Item, R::Item) -> O,
+    {
+        MergeJoinMapInner::new(self, other, cmp, merge)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order, grouping the items with equal keys.
+    ///
+    /// Unlike [`merge_join_inner_by()`](#method.merge_join_inner_by), the input iterators do
+    /// *not* need to be unique on the join key, but they still must be sorted. For each matched
+    /// key, the adaptor yields the whole run of the left items along with the whole run of the
+    /// right items, so only a single key run per side is buffered at any time. The runs are
+    /// grouped by comparing them against the first matched item of the other side.
+    ///
+    /// Each yielded group is a freshly allocated pair of vectors. To avoid the allocation per
+    /// group, call [`next_group()`](struct.MergeJoinInnerGroups.html#method.next_group) instead,
+    /// which borrows the runs as slices of the reused internal buffers.
+    ///
+    /// Iterator element type is `(Vec<L::Item>, Vec<R::Item>)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(0, 10), (1, 20), (1, 30)].into_iter();
+    /// let r = vec![(1, "X"), (1, "Y"), (2, "Z")].into_iter();
+    /// let mut it = l.merge_join_inner_groups_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// // sum the left amounts against the right rows
+    /// let (lvv, rvv) = it.next().unwrap();
+    /// assert_eq!(lvv.iter().map(|l| l.1).sum::<i32>(), 50);
+    /// assert_eq!(rvv, vec![(1, "X"), (1, "Y")]);
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner_groups_by<R, F>(self, other: R, cmp: F) 
+                                        -> MergeJoinInnerGroups<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinInnerGroups::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that inner joins the two input iterators in ascending order of
+    /// the `cmp` closure, pairing every left item with every right item of the same key, where the
+    /// pairs of each key are yielded in the order of the `then` closure.
+    ///
+    /// The input iterators must be sorted by `cmp`, but need not be unique on the join key. Each
+    /// matched key run of both sides is buffered, as by
+    /// [`merge_join_inner_groups_by()`](#method.merge_join_inner_groups_by), and its pairs are
+    /// cloned and sorted by `then` (stably), before the first one is yielded, so the memory is
+    /// bounded by the product of the largest key run of each side.
+    ///
+    /// Iterator element type is `(L::Item, R::Item)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(1, "B"), (1, "A")].into_iter();
+    /// let r = vec![(1, 2), (1, 1)].into_iter();
+    /// // the pairs of each key ordered by the left and then the right value
+    /// let mut it = l.merge_join_inner_by_then(r, |x, y| Ord::cmp(&x.0, &y.0), 
+    ///                                         |x, y| Ord::cmp(&((x.0).1, (x.1).1), &((y.0).1, (y.1).1)));
+    ///
+    /// assert_eq!(it.next(), Some(((1, "A"), (1, 1))));
+    /// assert_eq!(it.next(), Some(((1, "A"), (1, 2))));
+    /// assert_eq!(it.next(), Some(((1, "B"), (1, 1))));
+    /// assert_eq!(it.next(), Some(((1, "B"), (1, 2))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner_by_then<R, F, G>(self, other: R, cmp: F, then: G) 
+                                         -> MergeJoinInnerByThen<Self, R::IntoIter, F, G> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering,
+              G: FnMut(&(Self::Item, R::Item), &(Self::Item, R::Item)) -> Ordering,
+    {
+        MergeJoinInnerByThen::new(self, other, cmp, then)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order, grouping the items with equal keys, but at most `max` items per side.
+    ///
+    /// This is the same as [`merge_join_inner_groups_by()`](#method.merge_join_inner_groups_by),
+    /// guarding against the accidental cartesian products of large key runs: if either run of a
+    /// matched key contains more than `max` items, the `action` is taken:
+    ///
+    /// * `OverflowAction::Error` - yield `Err(GroupTooLarge)` with the first item of both runs,
+    ///   identifying the key, and stop.
+    /// * `OverflowAction::Skip` - skip the key, as if it was not matched.
+    ///
+    /// Either way, at most `max + 1` items per side are buffered.
+    ///
+    /// As with `merge_join_inner_groups_by()`, calling
+    /// [`next_group()`](struct.MergeJoinInnerGroupsCapped.html#method.next_group) instead of
+    /// `next()` borrows the runs from the reused internal buffers, avoiding the allocation per group.
+    ///
+    /// Iterator element type is `Result<(Vec<L::Item>, Vec<R::Item>), GroupTooLarge<L::Item,
+    /// R::Item>>`.
+    ///
+    /// ```
+    /// use joinkit::{Joinkit, OverflowAction};
+    ///
+    /// let l = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd')];
+    /// let r = vec![(1, "X"), (2, "Y"), (2, "Z")];
+    /// let cmp = |x: &(i32, char), y: &(i32, &str)| Ord::cmp(&x.0, &y.0);
+    ///
+    /// let mut it = l.clone().into_iter().merge_join_inner_grouped_capped_by(r.clone(), cmp, 1, OverflowAction::Skip);
+    /// assert_eq!(it.next(), None);
+    ///
+    /// let mut it = l.into_iter().merge_join_inner_grouped_capped_by(r, cmp, 2, OverflowAction::Error);
+    /// assert_eq!(it.next(), Some(Ok((vec![(1, 'b'), (1, 'c')], vec![(1, "X")]))));
+    /// assert_eq!(it.next(), Some(Ok((vec![(2, 'd')], vec![(2, "Y"), (2, "Z")]))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner_grouped_capped_by<R, F>(self, other: R, cmp: F, max: usize, action: OverflowAction) 
+                                                -> MergeJoinInnerGroupsCapped<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinInnerGroupsCapped::new(self, other, cmp, max, action)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order, pairing every left item with every right item of the same key.
+    ///
+    /// The input iterators must be sorted by the keys, which are computed by the `left_key` and
+    /// `right_key` closures, but they do *not* need to be unique on the key. Only the current run of
+    /// the right items is buffered - in a single buffer reused for all the runs - whereas the left
+    /// items are streamed. Each key is computed exactly once per item.
+    ///
+    /// Iterator element type is `(L::Item, R::Item)`. The right item is cloned for each pair,
+    /// whereas the left item is cloned for each but its last pair.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec!["0;A", "1;B", "1;C"].into_iter();
+    /// let r = vec!["1;X", "1;Y", "2;Z"].into_iter();
+    /// let key = |s: &&str| s.split(';').next().unwrap().to_owned();
+    /// let mut it = l.merge_join_records_by(r, key, key);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", "1;X")));
+    /// assert_eq!(it.next(), Some(("1;B", "1;Y")));
+    /// assert_eq!(it.next(), Some(("1;C", "1;X")));
+    /// assert_eq!(it.next(), Some(("1;C", "1;Y")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_records_by<R, K, LF, RF>(self, other: R, left_key: LF, right_key: RF) 
+                                           -> MergeJoinRecords<Self, R::IntoIter, K, LF, RF> 
+        where Self: Sized,
+              R: IntoIterator,
+              K: Ord,
+              LF: FnMut(&Self::Item) -> K,
+              RF: FnMut(&R::Item) -> K,
+    {
+        MergeJoinRecords::new(self, other, left_key, right_key)
     }
 
     /// Return an iterator adaptor that *left exclusive joins* the two input iterators in
@@ -114,7 +571,77 @@ pub trait Joinkit : Iterator {
               R: IntoIterator,
               F: FnMut(&Self::Item, &R::Item) -> Ordering
     {
-        MergeJoinLeftExcl::new(self, other.into_iter(), cmp)
+        MergeJoinLeftExcl::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that left exclusive joins the two input iterators in ascending
+    /// order, the same as [`merge_join_left_excl_by()`](#method.merge_join_left_excl_by), except
+    /// only the key of the next right item is buffered, not the item itself.
+    ///
+    /// The keys are computed by the `left_key` and `right_key` closures, exactly once per item.
+    /// Since the right items are never yielded, each is dropped as soon as its key is computed,
+    /// whereas `merge_join_left_excl_by()` keeps the next right item alive in a `Peekable`. This
+    /// pays off for the large right items with a cheap key, e.g. the records owning a large
+    /// payload. Otherwise, prefer `merge_join_left_excl_by()`, which computes no key.
+    ///
+    /// The both input iterators must be sorted and unique on the join key to produce the correct
+    /// results.
+    ///
+    /// Iterator element type is `L::Item`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(0, "A"), (1, "B"), (3, "C")].into_iter();
+    /// let r = vec![(1, vec![0u8; 1024]), (2, vec![0u8; 1024])].into_iter();
+    /// let mut it = l.merge_join_left_excl_by_key(r, |x| x.0, |y| y.0);
+    ///
+    /// assert_eq!(it.next(), Some((0, "A")));
+    /// assert_eq!(it.next(), Some((3, "C")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_excl_by_key<R, K, LF, RF>(self, other: R, left_key: LF, right_key: RF) 
+                                                 -> MergeJoinLeftExclKeyed<Self, R::IntoIter, K, LF, RF> 
+        where Self: Sized,
+              R: IntoIterator,
+              K: Ord,
+              LF: FnMut(&Self::Item) -> K,
+              RF: FnMut(&R::Item) -> K,
+    {
+        MergeJoinLeftExclKeyed::new(self, other, left_key, right_key)
+    }
+
+    /// Return an iterator adaptor that *left exclusive joins* the two input iterators in
+    /// ascending order, reporting the nearest right values for each unmatched left value.
+    ///
+    /// Along with each left value, which does not match the right input iterator, the adaptor
+    /// yields the immediately lower and the immediately higher right value, if any. This helps to
+    /// find the "off-by-one" key mismatches during the data reconciliation (e.g. `10` vs `10 `).
+    ///
+    /// The both input iterators must be sorted and unique on the join key to produce the correct
+    /// results. The neighbors are cloned from the right input iterator, so `R::Item` must be
+    /// `Clone`.
+    ///
+    /// Iterator element type is `(L::Item, Option<R::Item>, Option<R::Item>)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("10", "A"), ("15", "B")].into_iter();
+    /// let r = vec![("10", "X"), ("20", "Y")].into_iter();
+    /// let mut it = l.merge_join_left_excl_with_context_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// assert_eq!(it.next(), Some((("15", "B"), Some(("10", "X")), Some(("20", "Y")))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_excl_with_context_by<R, F>(self, other: R, cmp: F) 
+                                        -> MergeJoinLeftExclContext<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              R::Item: Clone,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftExclContext::new(self, other, cmp)
     }
 
     /// Return an iterator adaptor that [left outer
@@ -148,160 +675,1130 @@ pub trait Joinkit : Iterator {
               R: IntoIterator,
               F: FnMut(&Self::Item, &R::Item) -> Ordering
     {
-        MergeJoinLeftOuter::new(self, other.into_iter(), cmp)
+        MergeJoinLeftOuter::new(self, other, cmp)
     }
 
-    /// Return an iterator adaptor that [full outer
-    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Full_outer_join) the two input iterators
-    /// in ascending order. The resulting iterator contains all the records from the both input
-    /// iterators.
+    /// Same as [`merge_join_left_outer_by()`](#method.merge_join_left_outer_by), but each left
+    /// item is paired with an `Option` of the matching right item instead of being wrapped in
+    /// `EitherOrBoth`, since a left outer join yields no right-only items.
     ///
-    /// The both input iterators must be sorted and unique on the join key (e.g. by
-    /// [grouping](http://bluss.github.io/rust-itertools/doc/itertools/trait.Itertools.html#method.group_by)
-    /// them, if necessary) to produce the correct results.
+    /// Iterator element type is `(L::Item, Option<R::Item>)`.
     ///
-    /// Iterator element type is [`EitherOrBoth<L::Item, R::Item>`](enum.EitherOrBoth.html).
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z")].into_iter();
+    /// let mut it = l.merge_join_left_option_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// assert_eq!(it.next(), Some((("0", "0;A"), None)));
+    /// assert_eq!(it.next(), Some((("1", "1;B"), Some(("1", "1;X")))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_option_by<R, F>(self, other: R, cmp: F) 
+                                        -> MergeJoinLeftOption<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftOption::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that [left outer
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Left_outer_join) the two input iterators
+    /// in ascending order, joining each left item with the whole run of the matching right items.
+    ///
+    /// Unlike [`merge_join_left_outer_by()`](#method.merge_join_left_outer_by), the input
+    /// iterators do *not* need to be unique on the join key, but they still must be sorted. Each
+    /// left item is yielded once: along with the clone of the right key run, if matched, otherwise
+    /// alone. Only a single right key run is buffered at any time. If the left iterator is
+    /// exhausted first, the remaining right items are not consumed.
+    ///
+    /// Iterator element type is [`EitherOrBoth<L::Item, Vec<R::Item>>`](enum.EitherOrBoth.html).
     ///
     /// ```
     /// use joinkit::Joinkit;
-    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    /// use joinkit::EitherOrBoth::{Left, Both};
     ///
+    /// let l = vec![(0, "A"), (1, "B"), (1, "C")].into_iter();
+    /// let r = vec![(1, "X"), (1, "Y"), (2, "Z")].into_iter();
+    /// let mut it = l.merge_join_left_outer_groups_by(r, |x, y| Ord::cmp(&x.0, &y.0));
     ///
-    /// // tuples of (key, [value,...]), where the key is extracted from the value
-    /// // notice the values are grouped by the key
-    /// let l = vec![("0",vec!["0;A"]), ("1", vec!["1;B"])].into_iter();
-    /// let r = vec![("1",vec!["1;X", "1;Y"]), ("2", vec!["2;Z"])].into_iter();
-    /// let mut it = l.merge_join_full_outer_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    /// assert_eq!(it.next(), Some(Left((0, "A"))));
+    /// assert_eq!(it.next(), Some(Both((1, "B"), vec![(1, "X"), (1, "Y")])));
+    /// assert_eq!(it.next(), Some(Both((1, "C"), vec![(1, "X"), (1, "Y")])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_outer_groups_by<R, F>(self, other: R, cmp: F) 
+                                             -> MergeJoinLeftOuterGroups<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              R::Item: Clone,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftOuterGroups::new(self, other, cmp)
+    }
+
+    /// Same as [`hash_join_right_outer()`](#method.hash_join_right_outer), but the right values are
+    /// yielded one at a time instead of grouped by the key.
     ///
-    /// assert_eq!(it.next(), Some(Left(("0", vec!["0;A"]))));
-    /// assert_eq!(it.next(), Some(Both(("1", vec!["1;B"]), ("1", vec!["1;X", "1;Y"]))));
-    /// assert_eq!(it.next(), Some(Right(("2", vec!["2;Z"]))));
+    /// Each left value is yielded as `Both(LV, RV)` with each of its matched right values, and
+    /// each unmatched right value as `Right(RV)` once the left iterator is exhausted. The `LV` is
+    /// cloned for each but the last matched right value; the matched `RV` is cloned from
+    /// `HashMap`, but the vectors of the grouped values never are.
+    ///
+    /// Iterator element type is [`EitherOrBoth<LV, RV>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Both, Right};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_right_outer_flat(r);
+    ///
+    /// assert_eq!(it.next(), Some(Both("1;B", "1;X")));
+    /// assert_eq!(it.next(), Some(Both("1;B", "1;Y")));
+    /// assert_eq!(it.next(), Some(Right("2;Z")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_right_outer_flat<K, LV, RI, RV>(self, other: RI) -> HashJoinRightOuterFlat<Self, K, LV, RV> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
+              LV: Clone,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightOuterFlat::new(self, other)
+    }
+
+    /// Return an iterator adaptor that [full outer
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Full_outer_join) the two input iterators
+    /// in ascending order. The resulting iterator contains all the records from the both input
+    /// iterators.
+    ///
+    /// The both input iterators must be sorted and unique on the join key (e.g. by
+    /// [grouping](http://bluss.github.io/rust-itertools/doc/itertools/trait.Itertools.html#method.group_by)
+    /// them, if necessary) to produce the correct results.
+    ///
+    /// Iterator element type is [`EitherOrBoth<L::Item, R::Item>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    ///
+    /// // tuples of (key, [value,...]), where the key is extracted from the value
+    /// // notice the values are grouped by the key
+    /// let l = vec![("0",vec!["0;A"]), ("1", vec!["1;B"])].into_iter();
+    /// let r = vec![("1",vec!["1;X", "1;Y"]), ("2", vec!["2;Z"])].into_iter();
+    /// let mut it = l.merge_join_full_outer_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// assert_eq!(it.next(), Some(Left(("0", vec!["0;A"]))));
+    /// assert_eq!(it.next(), Some(Both(("1", vec!["1;B"]), ("1", vec!["1;X", "1;Y"]))));
+    /// assert_eq!(it.next(), Some(Right(("2", vec!["2;Z"]))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_full_outer_by<R, F>(self, other: R, cmp: F) 
+                                         -> MergeJoinFullOuter<Self, R::IntoIter, F> 
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    {
+        MergeJoinFullOuter::new(self, other, cmp)
+    }
+
+    /// Return an iterator adaptor that full outer joins the two input iterators in ascending
+    /// order, the same as [`merge_join_full_outer_by()`](#method.merge_join_full_outer_by),
+    /// except the order of the unmatched items with tied keys is given by `tie_break`.
+    ///
+    /// The keys are tied, if they are adjacent in the order of both input iterators, but they do
+    /// not match, e.g. `"a"` and `"A"` in the input sorted case-insensitively and joined
+    /// case-sensitively. The comparator returns `None` for such keys and `Some(Ordering)`
+    /// otherwise. At a tie, both the left and the right item are ready to be yielded: with
+    /// [`TieBreak::LeftFirst`](enum.TieBreak.html) (the default, which is how
+    /// `merge_join_full_outer_by()` orders them, if its comparator returns `Ordering::Less` on a
+    /// tie) the left one is yielded first, with `TieBreak::RightFirst` the right one.
+    ///
+    /// Iterator element type is [`EitherOrBoth<L::Item, R::Item>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use joinkit::{Joinkit, TieBreak};
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// // sorted case-insensitively, joined case-sensitively
+    /// let cmp = |x: &&str, y: &&str| match x.to_lowercase().cmp(&y.to_lowercase()) {
+    ///     Ordering::Equal if x != y => None,
+    ///     o => Some(o),
+    /// };
+    /// let l = vec!["a", "b"].into_iter();
+    /// let r = vec!["A", "b"].into_iter();
+    /// let mut it = l.merge_join_full_outer_tie_break_by(r, cmp, TieBreak::RightFirst);
+    ///
+    /// assert_eq!(it.next(), Some(Right("A")));
+    /// assert_eq!(it.next(), Some(Left("a")));
+    /// assert_eq!(it.next(), Some(Both("b", "b")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_full_outer_tie_break_by<R, F>(self, other: R, cmp: F, tie_break: TieBreak)
+                                         -> MergeJoinFullOuterTieBreak<Self, R::IntoIter, F>
+        where Self: Sized,
+              R: IntoIterator,
+              F: FnMut(&Self::Item, &R::Item) -> Option<Ordering>
+    {
+        MergeJoinFullOuterTieBreak::new(self, other, cmp, tie_break)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order. The resulting iterator is the intersection of the two input iterators.
+    ///
+    /// This is the same as [`merge_join_inner_by()`](#method.merge_join_inner_by), except the
+    /// items are compared on the keys provided by [`MergeJoinKey`](trait.MergeJoinKey.html),
+    /// so no comparator is needed. The both item types must have the same key type.
+    ///
+    /// The both input iterators must be sorted and unique on the join key to produce the correct
+    /// results.
+    ///
+    /// Iterator element type is `(L::Item, R::Item)`.
+    ///
+    /// ```
+    /// use joinkit::{Joinkit, MergeJoinKey};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Order { id: u32, amount: u32 }
+    /// #[derive(Debug, PartialEq)]
+    /// struct Customer { id: u32, name: &'static str }
+    ///
+    /// impl MergeJoinKey for Order {
+    ///     type Key = u32;
+    ///     fn key(&self) -> u32 { self.id }
+    /// }
+    /// impl MergeJoinKey for Customer {
+    ///     type Key = u32;
+    ///     fn key(&self) -> u32 { self.id }
+    /// }
+    ///
+    /// let l = vec![Order { id: 0, amount: 10 }, Order { id: 1, amount: 20 }].into_iter();
+    /// let r = vec![Customer { id: 1, name: "B" }, Customer { id: 2, name: "C" }].into_iter();
+    /// let mut it = l.merge_join_inner(r);
+    ///
+    /// assert_eq!(it.next(), Some((Order { id: 1, amount: 20 }, Customer { id: 1, name: "B" })));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_inner<R>(self, other: R) 
+                           -> MergeJoinInner<Self, R::IntoIter, KeyCmp<Self::Item, R::Item>> 
+        where Self: Sized,
+              Self::Item: MergeJoinKey,
+              R: IntoIterator,
+              R::Item: MergeJoinKey<Key=<Self::Item as MergeJoinKey>::Key>,
+    {
+        MergeJoinInner::new(self, other, merge_join::cmp_keys)
+    }
+
+    /// Return an iterator adaptor that *left exclusive joins* the two input iterators in
+    /// ascending order, comparing the items on the keys provided by
+    /// [`MergeJoinKey`](trait.MergeJoinKey.html).
+    ///
+    /// See [`merge_join_left_excl_by()`](#method.merge_join_left_excl_by) for the description.
+    ///
+    /// Iterator element type is `L::Item`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// // tuples of (key, value) implement `MergeJoinKey` on the first element
+    /// let l = vec![(0, "0;A"), (1, "1;B")].into_iter();
+    /// let r = vec![(1, "1;X"), (2, "2;Z")].into_iter();
+    /// let mut it = l.merge_join_left_excl(r);
+    ///
+    /// assert_eq!(it.next(), Some((0, "0;A")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_excl<R>(self, other: R) 
+                               -> MergeJoinLeftExcl<Self, R::IntoIter, KeyCmp<Self::Item, R::Item>> 
+        where Self: Sized,
+              Self::Item: MergeJoinKey,
+              R: IntoIterator,
+              R::Item: MergeJoinKey<Key=<Self::Item as MergeJoinKey>::Key>,
+    {
+        MergeJoinLeftExcl::new(self, other, merge_join::cmp_keys)
+    }
+
+    /// Return an iterator adaptor that *left outer joins* the two input iterators in ascending
+    /// order, comparing the items on the keys provided by
+    /// [`MergeJoinKey`](trait.MergeJoinKey.html).
+    ///
+    /// See [`merge_join_left_outer_by()`](#method.merge_join_left_outer_by) for the description.
+    ///
+    /// Iterator element type is [`EitherOrBoth<L::Item, R::Item>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both};
+    ///
+    /// let l = vec![(0, "0;A"), (1, "1;B")].into_iter();
+    /// let r = vec![(1, "1;X"), (2, "2;Z")].into_iter();
+    /// let mut it = l.merge_join_left_outer(r);
+    ///
+    /// assert_eq!(it.next(), Some(Left((0, "0;A"))));
+    /// assert_eq!(it.next(), Some(Both((1, "1;B"), (1, "1;X"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_left_outer<R>(self, other: R) 
+                                -> MergeJoinLeftOuter<Self, R::IntoIter, KeyCmp<Self::Item, R::Item>> 
+        where Self: Sized,
+              Self::Item: MergeJoinKey,
+              R: IntoIterator,
+              R::Item: MergeJoinKey<Key=<Self::Item as MergeJoinKey>::Key>,
+    {
+        MergeJoinLeftOuter::new(self, other, merge_join::cmp_keys)
+    }
+
+    /// Return an iterator adaptor that *full outer joins* the two input iterators in ascending
+    /// order, comparing the items on the keys provided by
+    /// [`MergeJoinKey`](trait.MergeJoinKey.html).
+    ///
+    /// See [`merge_join_full_outer_by()`](#method.merge_join_full_outer_by) for the description.
+    ///
+    /// Iterator element type is [`EitherOrBoth<L::Item, R::Item>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// let l = vec![(0, "0;A"), (1, "1;B")].into_iter();
+    /// let r = vec![(1, "1;X"), (2, "2;Z")].into_iter();
+    /// let mut it = l.merge_join_full_outer(r);
+    ///
+    /// assert_eq!(it.next(), Some(Left((0, "0;A"))));
+    /// assert_eq!(it.next(), Some(Both((1, "1;B"), (1, "1;X"))));
+    /// assert_eq!(it.next(), Some(Right((2, "2;Z"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn merge_join_full_outer<R>(self, other: R) 
+                                -> MergeJoinFullOuter<Self, R::IntoIter, KeyCmp<Self::Item, R::Item>> 
+        where Self: Sized,
+              Self::Item: MergeJoinKey,
+              R: IntoIterator,
+              R::Item: MergeJoinKey<Key=<Self::Item as MergeJoinKey>::Key>,
+    {
+        MergeJoinFullOuter::new(self, other, merge_join::cmp_keys)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
+    /// ascending order. The resulting iterator is the intersection of the two input iterators.
+    ///
+    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
+    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
+    /// unique on the key.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
+    /// Clone`.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is `(LV, vec![RV,...])`. 
+    /// The `RV` is cloned from `HashMap` for each joined value. A single `RV` can be expected to
+    /// be joined (and cloned) multiple times to `LV`. To increase performance, consider wrapping
+    /// `RV` into `std::rc::Rc` pointer to avoid unnecessary allocations.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// // tuples of (key, value), where the key is extracted from the value
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner(r);
+    ///
+    /// // notice the grouped right values
+    /// assert_eq!(it.next(), Some(("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner<K, RI, RV>(self, other: RI) -> HashJoinInner<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInner::new(self, other)
+    }
+
+    /// Return an iterator adaptor that inner joins the two input iterators on a normalized key,
+    /// e.g. a lowercased or trimmed column, while the items themselves are yielded unchanged.
+    ///
+    /// The key of each left and right item is computed by the `left_norm` and the `right_norm`
+    /// closure respectively and used only for the matching. Otherwise, it is the same as
+    /// [`hash_join_inner()`](#method.hash_join_inner) of the items paired with their keys by
+    /// [`with_key()`](#method.with_key).
+    ///
+    /// Iterator element type is `(Self::Item, vec![RI::Item,...])`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec!["Foo", "Bar"].into_iter();
+    /// let r = vec!["foo", "baz", "FOO"].into_iter();
+    /// let mut it = l.hash_join_inner_by_norm_key(r, |v| v.to_lowercase(), |v| v.to_lowercase());
+    ///
+    /// // the original casing is preserved
+    /// assert_eq!(it.next(), Some(("Foo", vec!["foo", "FOO"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_by_norm_key<K, RI, LF, RF>(self, other: RI, left_norm: LF, right_norm: RF) 
+        -> HashJoinInner<WithKey<Self, LF>, K, RI::Item> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator,
+              RI::Item: Clone,
+              LF: FnMut(&Self::Item) -> K,
+              RF: FnMut(&RI::Item) -> K,
+    {
+        HashJoinInner::new(WithKey::new(self, left_norm), WithKey::new(other.into_iter(), right_norm))
+    }
+
+    /// Return an iterator adaptor that inner joins the two input iterators, the same as
+    /// [`hash_join_inner()`](#method.hash_join_inner), except the right input iterator yields the
+    /// whole right items, whose key is extracted by the `right_key` closure.
+    ///
+    /// It guarantees the *entire* right items are stored in the `HashMap` and yielded, rather than
+    /// the values projected from them, so the right items need not be destructured upfront.
+    /// **Note**, the whole right input is kept in memory, so it pays off to project the right
+    /// items to the needed values instead, if the rest is not needed.
+    ///
+    /// Iterator element type is `(LV, vec![RI::Item,...])`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Customer { id: u32, name: &'static str, city: &'static str }
+    ///
+    /// let l = vec![(1, "order A"), (2, "order B")].into_iter();
+    /// let r = vec![Customer { id: 1, name: "Alice", city: "Oslo" }];
+    /// let mut it = l.hash_join_inner_full_right(r, |c| c.id);
+    ///
+    /// assert_eq!(it.next(), Some(("order A", vec![Customer { id: 1, name: "Alice", city: "Oslo" }])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_full_right<K, RI, RF>(self, other: RI, right_key: RF) 
+        -> HashJoinInner<Self, K, RI::Item> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator,
+              RI::Item: Clone,
+              RF: FnMut(&RI::Item) -> K,
+    {
+        HashJoinInner::new(self, WithKey::new(other.into_iter(), right_key))
+    }
+
+    /// Return an iterator adaptor that inner joins the two input iterators, the same as
+    /// [`hash_join_inner()`](#method.hash_join_inner), except the right input iterator is
+    /// fallible, e.g. it reads the records from a file.
+    ///
+    /// The right iterator element type must be `Result<(K, RV), E>`. The first error of the right
+    /// iterator stops building the `HashMap` and is returned instead of the adaptor, so the errors
+    /// can be propagated with `?` rather than unwrapped. As with `hash_join_inner()`, the right
+    /// iterator is not consumed at all, if the left iterator is empty, so its errors are not
+    /// detected then.
+    ///
+    /// Iterator element type is `(LV, vec![RV,...])`. 
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")];
+    /// let r: Vec<Result<_, &str>> = vec![Ok(("1", "1;X")), Ok(("1", "1;Y"))];
+    /// let mut it = l.clone().into_iter().try_hash_join_inner(r).unwrap();
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    ///
+    /// let r = vec![Ok(("1", "1;X")), Err("invalid record"), Ok(("1", "1;Y"))];
+    /// assert_eq!(l.into_iter().try_hash_join_inner(r).err(), Some("invalid record"));
+    /// ```
+    #[cfg(feature = "std")]
+    fn try_hash_join_inner<K, RI, RV, E>(self, other: RI) -> Result<HashJoinInner<Self, K, RV>, E> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=Result<(K, RV), E>>
+    {
+        HashJoinInner::try_new(self, other)
+    }
+
+    /// Inner join the two input iterators and collect the result into `HashMap` of each matched
+    /// key and its right values, e.g. to build a lookup of the right values for the left keys.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`, but only the
+    /// left keys are used: the left values are dropped. The right input iterator element type must
+    /// be `(K, RV)`, where `K: Hash + Eq`. As with [`hash_join_inner()`](#method.hash_join_inner),
+    /// the right iterator is not consumed at all, if the left iterator is empty.
+    ///
+    /// Each vector holds all the right values of its key in the order of the right input iterator,
+    /// regardless of how many times the key occurs in the left input iterator, so the right
+    /// values are moved, not cloned.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("1", "1;A"), ("3", "3;C"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let map = l.hash_join_inner_collect(r);
+    ///
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map["1"], vec!["1;X", "1;Y"]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_collect<K, LV, RI, RV>(self, other: RI) -> HashMap<K, Vec<RV>> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        let mut left = self.peekable();
+        let mut map = if left.peek().is_some() {group_into_map(other)} else {HashMap::new()};
+        // a key repeated in the left input iterator has been moved out on its first match already
+        left.filter_map(|(lk, _)| map.remove_entry(&lk)).collect()
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// moving each group of the right values out of `HashMap` on its first match.
+    ///
+    /// This is the same as [`hash_join_inner()`](#method.hash_join_inner) for the left input
+    /// iterator unique on the key, but without cloning the right values. For a key repeated in the
+    /// left input iterator, only the first left value is matched: the later ones miss, as the
+    /// right values have been moved out already.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq`.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is `(LV, Vec<RV>)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B"), ("1", "1;C")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner_take(r);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", vec!["1;X", "1;Y"])));
+    /// // the second left value of the key misses
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_take<K, RI, RV>(self, other: RI) -> HashJoinInnerTake<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInnerTake::new(self, other)
+    }
+
+    /// Same as [`hash_join_inner()`](#method.hash_join_inner), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinInner<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInner::with_capacity(self, other, expected_keys)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// where the left input iterator is already grouped by the key.
+    ///
+    /// This is the same as [`hash_join_inner()`](#method.hash_join_inner), except the left input
+    /// iterator element type must be `(K, vec![LV,...])`, so the already grouped data need not be
+    /// flattened before the join. The right input iterator element type must be `(K, RV)`, where
+    /// `K: Hash + Eq` and `RV: Clone`.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`.
+    ///
+    /// Iterator element type is `(vec![LV,...], vec![RV,...])`, i.e. a single element per matched
+    /// left group, which carries the whole left group along with all the right values of the key.
+    /// If the left input iterator is not unique on the key, each left group is joined separately.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", vec!["0;A"]), ("1", vec!["1;B", "1;C"])].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner_grouped_left(r);
+    ///
+    /// assert_eq!(it.next(), Some((vec!["1;B", "1;C"], vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_grouped_left<K, LV, RI, RV>(self, other: RI) -> HashJoinInner<Self, K, RV> 
+        where Self: Sized + Iterator<Item=(K, Vec<LV>)>,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInner::new(self, other)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// yielding a single pair for each matched right value.
+    ///
+    /// Unlike [`hash_join_inner()`](#method.hash_join_inner), which clones the whole group of the
+    /// matched right values into each item, the pairs are yielded lazily from a cursor into the
+    /// group, cloning a single right value at a time. So a large group matched by many left values
+    /// takes no more transient memory than a single right value, as long as the pairs are
+    /// consumed incrementally. It is the owning companion of
+    /// [`hash_join_inner_pairs_ref()`](#method.hash_join_inner_pairs_ref), which borrows the
+    /// right values from an already built map instead.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq` and `LV:
+    /// Clone`. The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and
+    /// `RV: Clone`. The `LV` is cloned for each but the last matched right value. As with
+    /// `hash_join_inner()`, the right iterator is not consumed at all, if the left iterator is
+    /// empty.
+    ///
+    /// Iterator element type is `(LV, RV)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner_pairs(r);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", "1;X")));
+    /// assert_eq!(it.next(), Some(("1;B", "1;Y")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_pairs<K, LV, RI, RV>(self, other: RI) -> HashJoinInnerPairs<Self, K, LV, RV> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
+              LV: Clone,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInnerPairs::new(self, other)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the input iterator with
+    /// an already built `HashMap`, yielding a single pair for each matched right value.
+    ///
+    /// Unlike [`hash_join_inner()`](#method.hash_join_inner), the right values are borrowed from
+    /// the map instead of being cloned, so `RV` does not need to be `Clone`. The map can be
+    /// built by [`group_into_map()`](fn.group_into_map.html) and must outlive the adaptor.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq` and `LV:
+    /// Clone`. The `LV` is cloned for each but the last matched right value.
+    ///
+    /// Iterator element type is `(LV, &RV)`.
+    ///
+    /// ```
+    /// use joinkit::{self, Joinkit};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let map = joinkit::group_into_map(vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")]);
+    /// let mut it = l.hash_join_inner_pairs_ref(&map);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", &"1;X")));
+    /// assert_eq!(it.next(), Some(("1;B", &"1;Y")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_pairs_ref<K, LV, RV>(self, map: &HashMap<K, Vec<RV>>) 
+                                            -> HashJoinInnerPairsRef<'_, Self, K, LV, RV> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
+              LV: Clone,
+    {
+        HashJoinInnerPairsRef::new(self, map)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the input iterator with
+    /// an already built `HashMap` of the grouped right values.
+    ///
+    /// This turns the adaptor into a thin probe over a map the caller maintains, e.g. a cached
+    /// lookup table: the map is borrowed, not consumed, so it can be probed again by another left
+    /// iterator. The right values are borrowed from the map, so `RV` does not need to be `Clone`.
+    /// To hand over an owned map instead, use
+    /// [`HashJoinInner::from_map()`](struct.HashJoinInner.html#method.from_map).
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`.
+    ///
+    /// Iterator element type is `(LV, &[RV])`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use joinkit::Joinkit;
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("1", vec!["1;X", "1;Y"]);
+    /// map.insert("2", vec!["2;Z"]);
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let mut it = l.hash_join_inner_with_map(&map);
+    /// assert_eq!(it.next(), Some(("1;B", &["1;X", "1;Y"][..])));
+    /// assert_eq!(it.next(), None);
+    ///
+    /// // the map is still available
+    /// let l = vec![("2", "2;C")].into_iter();
+    /// let mut it = l.hash_join_inner_with_map(&map);
+    /// assert_eq!(it.next(), Some(("2;C", &["2;Z"][..])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_with_map<K, LV, RV>(self, map: &HashMap<K, Vec<RV>>) 
+                                           -> HashJoinInnerRef<'_, Self, K, RV> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
+    {
+        HashJoinInnerRef::new(self, map)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// yielding the number of the matched right values instead of the values themselves.
+    ///
+    /// This is the same as [`hash_join_inner()`](#method.hash_join_inner), except only the count
+    /// of the right values per key is stored, so `RV` need not be `Clone`. The unmatched left
+    /// values are skipped, i.e. the count is never zero.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is `(LV, usize)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B"), ("2", "2;C")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_inner_count(r);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", 2)));
+    /// assert_eq!(it.next(), Some(("2;C", 1)));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_count<K, RI, RV>(self, other: RI) -> HashJoinInnerCount<Self, K> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinInnerCount::new(self, other)
+    }
+
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// folding the right values of each key into a single value.
+    ///
+    /// This is the same as [`hash_join_inner()`](#method.hash_join_inner), except the right
+    /// values of each key are folded by the `reduce` closure, in the order of the right input
+    /// iterator, while the `HashMap` is built. The map therefore stores a single `RV` per key,
+    /// e.g. for the aggregate lookups.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is `(LV, RV)`. The `RV` is cloned from `HashMap` for each joined
+    /// value.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B"), ("2", "2;C")].into_iter();
+    /// // the amounts per key
+    /// let r = vec![("1", 10), ("2", 5), ("1", 20)].into_iter();
+    /// let mut it = l.hash_join_inner_reduce(r, |acc, amount| acc + amount);
+    ///
+    /// assert_eq!(it.next(), Some(("1;B", 30)));
+    /// assert_eq!(it.next(), Some(("2;C", 5)));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_inner_reduce<K, RI, RV, F>(self, other: RI, reduce: F) -> HashJoinInnerReduce<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>,
+              F: FnMut(RV, RV) -> RV,
+    {
+        HashJoinInnerReduce::new(self, other, reduce)
+    }
+
+    /// Return an iterator adaptor that *left exclusive joins* the two input iterators. The
+    /// resulting iterator contains only those records from the left input iterator, which do not
+    /// match the right input iterator. There is no direct equivalent in SQL.
+    ///
+    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
+    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
+    /// unique on the key.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq`.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is `LV`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// // tuples of (key, value), where the key is extracted from the value
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_excl(r);
+    ///
+    /// assert_eq!(it.next(), Some("0;A"));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_excl<K, RI, RV>(self, other: RI) -> HashJoinLeftExcl<Self, K> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftExcl::new(self, other)
+    }
+
+    /// Same as [`hash_join_left_excl()`](#method.hash_join_left_excl), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_excl_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some("0;A"));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_excl_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinLeftExcl<Self, K> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftExcl::with_capacity(self, other, expected_keys)
+    }
+
+    /// Return an iterator adaptor that [left outer
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Left_outer_join) the two input
+    /// iterators.  The resulting iterator contains all the records from the left input iterator,
+    /// even if they do not match the right input iterator.
+    ///
+    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
+    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
+    /// unique on the key.
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
+    /// Clone`.
+    ///
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
+    ///
+    /// Iterator element type is [`EitherOrBoth<LV, RV>`](enum.EitherOrBoth.html).
+    /// The `RV` is cloned from `HashMap` for each joined value. It is expected a single `RV` will
+    /// be joined (and cloned) multiple times to `LV`. To increase performance, consider wrapping
+    /// `RV` into `std::rc::Rc` pointer to avoid unnecessary allocations.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// // tuples of (key, value), where the key is extracted from the value
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_outer(r);
+    ///
+    /// // notice the grouped right values
+    /// assert_eq!(it.next(), Some(Left("0;A")));
+    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_outer<K, RI, RV>(self, other: RI) -> HashJoinLeftOuter<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftOuter::new(self, other)
+    }
+
+    /// Same as [`hash_join_left_outer()`](#method.hash_join_left_outer), but each left value is
+    /// paired with an `Option` of the matching right values instead of being wrapped in
+    /// `EitherOrBoth`, since a left outer join yields no right-only values.
+    ///
+    /// Iterator element type is `(LV, Option<Vec<RV>>)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_option(r);
+    ///
+    /// assert_eq!(it.next(), Some(("0;A", None)));
+    /// assert_eq!(it.next(), Some(("1;B", Some(vec!["1;X", "1;Y"]))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_option<K, RI, RV>(self, other: RI) -> HashJoinLeftOption<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftOption::new(self, other)
+    }
+
+    /// Same as [`hash_join_left_outer()`](#method.hash_join_left_outer), but the duplicate right
+    /// values of each key are removed, when the `HashMap` is built.
+    ///
+    /// The right values are equal if the whole `RV` values are equal, regardless of the key, which
+    /// is the same for all values of a group anyway. Only the first of the equal values is kept,
+    /// so the order of the right values is the order of their first occurrences in the right
+    /// input iterator. This prevents the fan-out of the left values due to the duplicate right
+    /// data, e.g. in the enrichment joins.
+    ///
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
+    /// Hash + Eq + Clone`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;Y"), ("2", "2;Z"), ("1", "1;X"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_outer_dedup(r);
+    ///
+    /// assert_eq!(it.next(), Some(Left("0;A")));
+    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;Y", "1;X"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_outer_dedup<K, RI, RV>(self, other: RI) -> HashJoinLeftOuter<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Hash + Eq + Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftOuter::new_dedup(self, other)
+    }
+
+    /// Same as [`hash_join_left_outer()`](#method.hash_join_left_outer), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_left_outer_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some(Left("0;A")));
+    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_left_outer_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinLeftOuter<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinLeftOuter::with_capacity(self, other, expected_keys)
+    }
+
+    /// Return an iterator adaptor that [left outer
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Left_outer_join) the input iterator
+    /// with an already built `HashMap` of the grouped right values.
+    ///
+    /// This is the left outer counterpart of
+    /// [`hash_join_inner_with_map()`](#method.hash_join_inner_with_map): the map is borrowed and
+    /// the right values are yielded as slices into it. To hand over an owned map instead, use the
+    /// `from_map()` constructor of [`HashJoinLeftOuter`](struct.HashJoinLeftOuter.html),
+    /// [`HashJoinRightExcl`](struct.HashJoinRightExcl.html),
+    /// [`HashJoinRightOuter`](struct.HashJoinRightOuter.html) or
+    /// [`HashJoinFullOuter`](struct.HashJoinFullOuter.html).
+    ///
+    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`.
+    ///
+    /// Iterator element type is [`EitherOrBoth<LV, &[RV]>`](enum.EitherOrBoth.html).
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both};
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert("1", vec!["1;X", "1;Y"]);
+    /// map.insert("2", vec!["2;Z"]);
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let mut it = l.hash_join_left_outer_with_map(&map);
+    /// assert_eq!(it.next(), Some(Left("0;A")));
+    /// assert_eq!(it.next(), Some(Both("1;B", &["1;X", "1;Y"][..])));
     /// assert_eq!(it.next(), None);
     /// ```
-    fn merge_join_full_outer_by<R, F>(self, other: R, cmp: F) 
-                                         -> MergeJoinFullOuter<Self, R::IntoIter, F> 
-        where Self: Sized,
-              R: IntoIterator,
-              F: FnMut(&Self::Item, &R::Item) -> Ordering
+    #[cfg(feature = "std")]
+    fn hash_join_left_outer_with_map<K, LV, RV>(self, map: &HashMap<K, Vec<RV>>) 
+                                                -> HashJoinLeftOuterRef<'_, Self, K, RV> 
+        where Self: Sized + Iterator<Item=(K, LV)>,
+              K: Hash + Eq,
     {
-        MergeJoinFullOuter::new(self, other.into_iter(), cmp)
+        HashJoinLeftOuterRef::new(self, map)
     }
 
-    /// Return an iterator adaptor that [inner
-    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators in
-    /// ascending order. The resulting iterator is the intersection of the two input iterators.
-    ///
-    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
-    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
-    /// unique on the key.
-    ///
-    /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
-    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
-    /// Clone`.
-    ///
-    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`.
+    /// Same as [`hash_join_left_outer()`](#method.hash_join_left_outer), but the keys of the
+    /// unmatched left values are collected along the way, e.g. for a reconciliation report,
+    /// without a second pass over the data.
     ///
-    /// Iterator element type is `(LV, vec![RV,...])`. 
-    /// The `RV` is cloned from `HashMap` for each joined value. A single `RV` can be expected to
-    /// be joined (and cloned) multiple times to `LV`. To increase performance, consider wrapping
-    /// `RV` into `std::rc::Rc` pointer to avoid unnecessary allocations.
+    /// The keys are retrieved by
+    /// [`unmatched_keys()`](struct.HashJoinLeftOuterUnmatched.html#method.unmatched_keys) or
+    /// [`into_unmatched_keys()`](struct.HashJoinLeftOuterUnmatched.html#method.into_unmatched_keys)
+    /// and they are complete only once the iterator is exhausted. The keys are moved from the left
+    /// iterator, so `K` need not be `Clone`.
     ///
     /// ```
     /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both};
     ///
-    /// // tuples of (key, value), where the key is extracted from the value
-    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let l = vec![("0", "0;A"), ("1", "1;B"), ("3", "3;D")].into_iter();
     /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
-    /// let mut it = l.hash_join_inner(r);
+    /// let mut it = l.hash_join_left_outer_unmatched(r);
     ///
-    /// // notice the grouped right values
-    /// assert_eq!(it.next(), Some(("1;B", vec!["1;X", "1;Y"])));
-    /// assert_eq!(it.next(), None);
+    /// let joined: Vec<_> = it.by_ref().collect();
+    /// assert_eq!(joined, vec![Left("0;A"), Both("1;B", vec!["1;X", "1;Y"]), Left("3;D")]);
+    /// assert_eq!(it.unmatched_keys(), &["0", "3"]);
     /// ```
-    fn hash_join_inner<K, RI, RV>(self, other: RI) -> HashJoinInner<Self, K, RV> 
+    #[cfg(feature = "std")]
+    fn hash_join_left_outer_unmatched<K, RI, RV>(self, other: RI) -> HashJoinLeftOuterUnmatched<Self, K, RV> 
         where Self: Sized,
               K: Hash + Eq,
               RV: Clone,
               RI: IntoIterator<Item=(K, RV)>
     {
-        HashJoinInner::new(self, other)
+        HashJoinLeftOuterUnmatched::new(self, other)
     }
 
-    /// Return an iterator adaptor that *left exclusive joins* the two input iterators. The
-    /// resulting iterator contains only those records from the left input iterator, which do not
-    /// match the right input iterator. There is no direct equivalent in SQL.
+    /// Return an iterator adaptor that [left outer
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Left_outer_join) the two input
+    /// iterators, joining at most the first matching right value to each left value.
     ///
-    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
-    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
-    /// unique on the key.
+    /// This is a lookup into the right input iterator: only the first value of each key, in the
+    /// order of the right input iterator, is stored in `HashMap`; the later duplicates are ignored.
     ///
     /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
-    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq`.
+    /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
+    /// Clone`.
     ///
-    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`.
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, unless
+    /// the left iterator is empty, since nothing can be joined then.
     ///
-    /// Iterator element type is `LV`.
+    /// Iterator element type is `(LV, Option<RV>)`.
     ///
     /// ```
     /// use joinkit::Joinkit;
     ///
-    /// // tuples of (key, value), where the key is extracted from the value
     /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
     /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
-    /// let mut it = l.hash_join_left_excl(r);
+    /// let mut it = l.hash_join_first(r);
     ///
-    /// assert_eq!(it.next(), Some("0;A"));
+    /// assert_eq!(it.next(), Some(("0;A", None)));
+    /// assert_eq!(it.next(), Some(("1;B", Some("1;X"))));
     /// assert_eq!(it.next(), None);
     /// ```
-    fn hash_join_left_excl<K, RI, RV>(self, other: RI) -> HashJoinLeftExcl<Self, K> 
+    #[cfg(feature = "std")]
+    fn hash_join_first<K, RI, RV>(self, other: RI) -> HashJoinFirst<Self, K, RV> 
         where Self: Sized,
               K: Hash + Eq,
+              RV: Clone,
               RI: IntoIterator<Item=(K, RV)>
     {
-        HashJoinLeftExcl::new(self, other)
+        HashJoinFirst::new(self, other)
     }
 
-    /// Return an iterator adaptor that [left outer
-    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Left_outer_join) the two input
-    /// iterators.  The resulting iterator contains all the records from the left input iterator,
-    /// even if they do not match the right input iterator.
+    /// Return an iterator adaptor that [inner
+    /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Inner_join) the two input iterators,
+    /// requiring the keys of the right input iterator to be unique.
     ///
-    /// The input iterators do *not* need to be sorted. The right input iterator is loaded into
-    /// `HashMap` and grouped by the key automatically. Neither the left input iterator need to be
-    /// unique on the key.
+    /// This is a lookup into a table, which must contain a single value for each key, e.g. a
+    /// dimension table. A duplicate key in the right input iterator is reported as
+    /// [`DuplicateKey`](hash_join/struct.DuplicateKey.html) instead of grouping its values.
     ///
     /// The left input iterator element type must be `(K, LV)`, where `K: Hash + Eq`. 
     /// The right input iterator element type must be `(K, RV)`, where `K: Hash + Eq` and `RV:
     /// Clone`.
     ///
-    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`.
+    /// When the join adaptor is created, the right iterator is **consumed** into `HashMap`, up to
+    /// the first duplicate key, unless the left iterator is empty, since nothing can be joined
+    /// then. The duplicate keys are not reported in that case either.
     ///
-    /// Iterator element type is [`EitherOrBoth<LV, RV>`](enum.EitherOrBoth.html).
-    /// The `RV` is cloned from `HashMap` for each joined value. It is expected a single `RV` will
-    /// be joined (and cloned) multiple times to `LV`. To increase performance, consider wrapping
-    /// `RV` into `std::rc::Rc` pointer to avoid unnecessary allocations.
+    /// Iterator element type is `(LV, RV)`.
     ///
     /// ```
     /// use joinkit::Joinkit;
-    /// use joinkit::EitherOrBoth::{Left, Both, Right};
     ///
-    /// // tuples of (key, value), where the key is extracted from the value
-    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let l = vec![("0", "0;A"), ("1", "1;B"), ("1", "1;C")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z")].into_iter();
+    /// let joined: Vec<_> = l.hash_join_inner_unique_right(r).unwrap().collect();
+    ///
+    /// assert_eq!(joined, vec![("1;B", "1;X"), ("1;C", "1;X")]);
+    ///
+    /// let l = vec![("0", "0;A")].into_iter();
     /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
-    /// let mut it = l.hash_join_left_outer(r);
+    /// let e = l.hash_join_inner_unique_right(r).err().unwrap();
     ///
-    /// // notice the grouped right values
-    /// assert_eq!(it.next(), Some(Left("0;A")));
-    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;X", "1;Y"])));
-    /// assert_eq!(it.next(), None);
+    /// assert_eq!((e.index, e.key), (2, "1"));
     /// ```
-    fn hash_join_left_outer<K, RI, RV>(self, other: RI) -> HashJoinLeftOuter<Self, K, RV> 
+    #[cfg(feature = "std")]
+    fn hash_join_inner_unique_right<K, RI, RV>(self, other: RI) 
+        -> Result<HashJoinInnerUniqueRight<Self, K, RV>, DuplicateKey<K>> 
         where Self: Sized,
               K: Hash + Eq,
               RV: Clone,
               RI: IntoIterator<Item=(K, RV)>
     {
-        HashJoinLeftOuter::new(self, other)
+        HashJoinInnerUniqueRight::new(self, other)
     }
 
     /// Return an iterator adaptor that *right exclusive joins* the two input iterators. The resulting
@@ -330,6 +1827,7 @@ pub trait Joinkit : Iterator {
     /// assert_eq!(it.next(), Some(vec!["2;Z"]));
     /// assert_eq!(it.next(), None);
     /// ```
+    #[cfg(feature = "std")]
     fn hash_join_right_excl<K, RI, RV>(self, other: RI) -> HashJoinRightExcl<Self, K, RV> 
         where Self: Sized,
               K: Hash + Eq,
@@ -338,6 +1836,55 @@ pub trait Joinkit : Iterator {
         HashJoinRightExcl::new(self, other)
     }
 
+    /// Same as [`hash_join_right_excl()`](#method.hash_join_right_excl), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_right_excl_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some(vec!["2;Z"]));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_right_excl_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinRightExcl<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightExcl::with_capacity(self, other, expected_keys)
+    }
+
+    /// Same as [`hash_join_right_excl()`](#method.hash_join_right_excl), but each unmatched right
+    /// key is yielded together with its values, e.g. to report the keys, which exist only in the
+    /// right input iterator, when checking the referential integrity.
+    ///
+    /// Iterator element type is `(K, vec![RV,...])`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_right_excl_with_key(r);
+    ///
+    /// assert_eq!(it.next(), Some(("2", vec!["2;Z"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_right_excl_with_key<K, RI, RV>(self, other: RI) -> HashJoinRightExclWithKey<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightExclWithKey::new(self, other)
+    }
+
     /// Return an iterator adaptor that [right outer
     /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Right_outer_join) the two input
     /// iterators.  The resulting iterator contains all the records from the right input iterator,
@@ -372,6 +1919,7 @@ pub trait Joinkit : Iterator {
     /// assert_eq!(it.next(), Some(Right(vec!["2;Z"])));
     /// assert_eq!(it.next(), None);
     /// ```
+    #[cfg(feature = "std")]
     fn hash_join_right_outer<K, RI, RV>(self, other: RI) -> HashJoinRightOuter<Self, K, RV> 
         where Self: Sized,
               K: Hash + Eq,
@@ -381,6 +1929,33 @@ pub trait Joinkit : Iterator {
         HashJoinRightOuter::new(self, other)
     }
 
+    /// Same as [`hash_join_right_outer()`](#method.hash_join_right_outer), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_right_outer_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), Some(Right(vec!["2;Z"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_right_outer_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinRightOuter<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinRightOuter::with_capacity(self, other, expected_keys)
+    }
+
     /// Return an iterator adaptor that [full outer
     /// joins](https://en.wikipedia.org/wiki/Join_%28SQL%29#Full_outer_join) the two input
     /// iterators.  The resulting iterator contains all the records from the both input iterators.
@@ -415,6 +1990,7 @@ pub trait Joinkit : Iterator {
     /// assert_eq!(it.next(), Some(Right(vec!["2;Z"])));
     /// assert_eq!(it.next(), None);
     /// ```
+    #[cfg(feature = "std")]
     fn hash_join_full_outer<K, RI, RV>(self, other: RI) -> HashJoinFullOuter<Self, K, RV> 
         where Self: Sized,
               K: Hash + Eq,
@@ -423,6 +1999,275 @@ pub trait Joinkit : Iterator {
     {
         HashJoinFullOuter::new(self, other)
     }
+
+    /// Same as [`hash_join_full_outer()`](#method.hash_join_full_outer), but the `HashMap` is pre-sized for
+    /// `expected_keys` distinct right keys.
+    ///
+    /// Over- or under-estimating `expected_keys` affects only the performance, not the result.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    /// use joinkit::EitherOrBoth::{Left, Both, Right};
+    ///
+    /// let l = vec![("0", "0;A"), ("1", "1;B")].into_iter();
+    /// let r = vec![("1", "1;X"), ("2", "2;Z"), ("1", "1;Y")].into_iter();
+    /// let mut it = l.hash_join_full_outer_sized(r, 2);
+    ///
+    /// assert_eq!(it.next(), Some(Left("0;A")));
+    /// assert_eq!(it.next(), Some(Both("1;B", vec!["1;X", "1;Y"])));
+    /// assert_eq!(it.next(), Some(Right(vec!["2;Z"])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn hash_join_full_outer_sized<K, RI, RV>(self, other: RI, expected_keys: usize) -> HashJoinFullOuter<Self, K, RV> 
+        where Self: Sized,
+              K: Hash + Eq,
+              RV: Clone,
+              RI: IntoIterator<Item=(K, RV)>
+    {
+        HashJoinFullOuter::with_capacity(self, other, expected_keys)
+    }
+
+    /// Split the input iterator into the items, whose key extracted by the `key` closure is in the
+    /// `keys` set, and the items, whose key is not, e.g. to semi and anti join against a set of
+    /// keys maintained elsewhere.
+    ///
+    /// The unmatched items are the same as those of
+    /// [`hash_join_left_excl()`](#method.hash_join_left_excl), but the set is given directly,
+    /// instead of being collected from another iterator. Since either part could be drained before
+    /// the other one, the input iterator is consumed eagerly and both parts are buffered in the
+    /// order of the input.
+    ///
+    /// Returns `(matched, unmatched)`, where the element type of both iterators is `Self::Item`.
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use joinkit::Joinkit;
+    ///
+    /// let blocked: HashSet<u32> = vec![2, 3].into_iter().collect();
+    /// let users = vec![(1, "A"), (2, "B"), (3, "C"), (4, "D")].into_iter();
+    /// let (mut blocked_users, mut active_users) = users.partition_by_membership(blocked, |u| u.0);
+    ///
+    /// assert_eq!(active_users.next(), Some((1, "A")));
+    /// assert_eq!(active_users.next(), Some((4, "D")));
+    /// assert_eq!(active_users.next(), None);
+    /// assert_eq!(blocked_users.next(), Some((2, "B")));
+    /// assert_eq!(blocked_users.next(), Some((3, "C")));
+    /// assert_eq!(blocked_users.next(), None);
+    /// ```
+    #[cfg(feature = "std")]
+    fn partition_by_membership<K, F>(self, keys: HashSet<K>, mut key: F) 
+                                     -> (vec::IntoIter<Self::Item>, vec::IntoIter<Self::Item>) 
+        where Self: Sized,
+              K: Hash + Eq,
+              F: FnMut(&Self::Item) -> K,
+    {
+        let (matched, unmatched): (Vec<Self::Item>, Vec<Self::Item>) = 
+            self.partition(|v| keys.contains(&key(v)));
+        (matched.into_iter(), unmatched.into_iter())
+    }
+
+    /// Return an iterator adaptor that pairs each item with its key, as required by the hash
+    /// join adaptors.
+    ///
+    /// The key is computed by the `key` closure, which borrows the item.
+    ///
+    /// Iterator element type is `(K, Self::Item)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Order { id: u32, amount: u32 }
+    /// #[derive(Debug, PartialEq, Clone)]
+    /// struct Customer { id: u32, name: &'static str }
+    ///
+    /// # #[cfg(feature = "std")] {
+    /// let l = vec![Order { id: 0, amount: 10 }, Order { id: 1, amount: 20 }].into_iter();
+    /// let r = vec![Customer { id: 1, name: "B" }, Customer { id: 2, name: "C" }].into_iter();
+    /// let mut it = l.with_key(|o| o.id).hash_join_inner(r.with_key(|c| c.id));
+    ///
+    /// assert_eq!(it.next(), Some((Order { id: 1, amount: 20 }, 
+    ///                             vec![Customer { id: 1, name: "B" }])));
+    /// assert_eq!(it.next(), None);
+    /// # }
+    /// ```
+    fn with_key<K, F>(self, key: F) -> WithKey<Self, F>
+        where Self: Sized,
+              F: FnMut(&Self::Item) -> K,
+    {
+        WithKey::new(self, key)
+    }
+
+    /// Return an iterator adaptor that yields the items of the input iterator, checking they are
+    /// sorted by the `cmp` closure, as required by the merge join adaptors.
+    ///
+    /// An item is out of order if `cmp` orders it before its predecessor, i.e. the equal items are
+    /// allowed. The out-of-order items are yielded as `Err` and the check continues with the next
+    /// item compared against it. To compare each item with its successor without `Clone`, the
+    /// adaptor reads one item ahead of the one it yields.
+    ///
+    /// Iterator element type is `Result<Self::Item, OutOfOrder<Self::Item>>`.
+    ///
+    /// ```
+    /// use joinkit::{Joinkit, OutOfOrder};
+    ///
+    /// let mut it = vec![1, 2, 2, 1, 3].into_iter().check_sorted_by(Ord::cmp);
+    ///
+    /// assert_eq!(it.next(), Some(Ok(1)));
+    /// assert_eq!(it.next(), Some(Ok(2)));
+    /// assert_eq!(it.next(), Some(Ok(2)));
+    /// assert_eq!(it.next(), Some(Err(OutOfOrder { index: 3, item: 1 })));
+    /// assert_eq!(it.next(), Some(Ok(3)));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn check_sorted_by<F>(self, cmp: F) -> CheckSortedBy<Self, F>
+        where Self: Sized,
+              F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        CheckSortedBy::new(self, cmp)
+    }
+
+    /// Return an iterator adaptor that yields the items of the input iterator, panicking on the
+    /// first item ordered by the `cmp` closure before its predecessor.
+    ///
+    /// This is the panicking form of [`check_sorted_by()`](#method.check_sorted_by), e.g. to
+    /// catch an unsorted input of the merge join adaptors early.
+    ///
+    /// Iterator element type is `Self::Item`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(0, "0;A"), (1, "1;B")].into_iter().assert_sorted_by(|a, b| Ord::cmp(&a.0, &b.0));
+    /// let r = vec![(1, "1;X"), (2, "2;Z")].into_iter().assert_sorted_by(|a, b| Ord::cmp(&a.0, &b.0));
+    /// let mut it = l.merge_join_inner_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// assert_eq!(it.next(), Some(((1, "1;B"), (1, "1;X"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn assert_sorted_by<F>(self, cmp: F) -> AssertSortedBy<Self, F>
+        where Self: Sized,
+              F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        AssertSortedBy::new(self, cmp)
+    }
+
+    /// Return an iterator adaptor that merges two iterators, both sorted by the `cmp` closure,
+    /// into one sorted iterator, e.g. to union the sorted shards of an input of the merge join
+    /// adaptors without re-sorting them.
+    ///
+    /// Unlike the merge join adaptors, all the items of both inputs are yielded. Of the equal
+    /// items, those of the left input are yielded first, i.e. the merge is stable. If an input is
+    /// not sorted, the output is not sorted either.
+    ///
+    /// Iterator element type is `Self::Item`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let shard1 = vec![1, 4, 7].into_iter();
+    /// let shard2 = vec![2, 4, 9, 10].into_iter();
+    /// let ids: Vec<_> = shard1.chain_sorted_by(shard2, Ord::cmp).collect();
+    ///
+    /// assert_eq!(ids, vec![1, 2, 4, 4, 7, 9, 10]);
+    /// ```
+    fn chain_sorted_by<R, F>(self, other: R, cmp: F) -> ChainSortedBy<Self, R::IntoIter, F>
+        where Self: Sized,
+              R: IntoIterator<Item=Self::Item>,
+              F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        ChainSortedBy::new(self, other.into_iter(), cmp)
+    }
+
+    /// Return an iterator over the items of the input iterator sorted by the `cmp` closure, e.g.
+    /// to prepare a small input for the merge join adaptors.
+    ///
+    /// **Beware**, the whole input iterator is collected into a `Vec` and sorted, before the first
+    /// item is yielded, so it must fit in memory. The sort is stable, i.e. the equal items keep
+    /// their order. If only one of the inputs is small, consider the hash join adaptors instead,
+    /// which buffer the right input only and need no sorting at all.
+    ///
+    /// Iterator element type is `Self::Item`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(2, "2;B"), (0, "0;A"), (1, "1;C")].into_iter();
+    /// let r = vec![(1, "1;X"), (2, "2;Y")].into_iter();
+    /// let cmp = |x: &(u32, &str), y: &(u32, &str)| Ord::cmp(&x.0, &y.0);
+    /// let mut it = l.sorted_by(cmp).merge_join_inner_by(r.sorted_by(cmp), cmp);
+    ///
+    /// assert_eq!(it.next(), Some(((1, "1;C"), (1, "1;X"))));
+    /// assert_eq!(it.next(), Some(((2, "2;B"), (2, "2;Y"))));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn sorted_by<F>(self, cmp: F) -> vec::IntoIter<Self::Item>
+        where Self: Sized,
+              F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        let mut v: Vec<Self::Item> = self.collect();
+        v.sort_by(cmp);
+        v.into_iter()
+    }
+
+    /// Return an iterator adaptor that expands the groups of values back into the individual
+    /// `(K, V)` items, e.g. the grouped results of a join.
+    ///
+    /// This is the inverse of grouping the values by their key: each value is yielded along with
+    /// its key in the original order, and the empty groups are skipped. The key is cloned for each
+    /// but the last value of its group.
+    ///
+    /// Iterator element type is `(K, V)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let groups = vec![("1", vec!["a", "b"]), ("2", vec![]), ("3", vec!["c"])].into_iter();
+    /// let mut it = groups.ungroup();
+    ///
+    /// assert_eq!(it.next(), Some(("1", "a")));
+    /// assert_eq!(it.next(), Some(("1", "b")));
+    /// assert_eq!(it.next(), Some(("3", "c")));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn ungroup<K, V>(self) -> Ungroup<Self, K, V>
+        where Self: Sized + Iterator<Item=(K, Vec<V>)>,
+              K: Clone,
+    {
+        Ungroup::new(self)
+    }
+
+    /// Return an iterator adaptor that groups the consecutive items with the same key, e.g. to
+    /// group the results of a flat join back by the left key.
+    ///
+    /// The key is computed by the `key` closure, exactly once per item. The input iterator must be
+    /// already ordered by the key, as the output of the merge join adaptors is - otherwise the
+    /// same key is yielded in several groups. This is the counterpart of
+    /// [`ungroup()`](#method.ungroup).
+    ///
+    /// Iterator element type is `(K, Vec<Self::Item>)`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// // (id, name) and (id, order)
+    /// let l = vec![(1, "A"), (2, "B")].into_iter();
+    /// let r = vec![(1, "X"), (1, "Y"), (2, "Z")].into_iter();
+    /// let joined = l.merge_join_records_by(r, |x| x.0, |y| y.0);
+    /// let mut it = joined.group_joined_by_key(|&(l, _)| l.0);
+    ///
+    /// assert_eq!(it.next(), Some((1, vec![((1, "A"), (1, "X")), ((1, "A"), (1, "Y"))])));
+    /// assert_eq!(it.next(), Some((2, vec![((2, "B"), (2, "Z"))])));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    fn group_joined_by_key<K, F>(self, key: F) -> GroupJoinedByKey<Self, K, F>
+        where Self: Sized,
+              K: PartialEq,
+              F: FnMut(&Self::Item) -> K,
+    {
+        GroupJoinedByKey::new(self, key)
+    }
 }
 
 impl<T: ?Sized> Joinkit for T where T: Iterator { }
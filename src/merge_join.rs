@@ -3,22 +3,73 @@
 //! The supported join types:
 //!
 //! * [`INNER JOIN`](trait.Joinkit.html#method.merge_join_inner_by) - an intersection between the
-//! left and the right iterator.
+//!   left and the right iterator.
 //! * [`LEFT EXCL JOIN`](trait.Joinkit.html#method.merge_join_left_excl_by) - a difference
-//! between the left and the right iterator (not directly in SQL).
+//!   between the left and the right iterator (not directly in SQL).
 //! * [`LEFT OUTER JOIN`](trait.Joinkit.html#method.merge_join_left_outer_by) - a union of `INNER
-//! JOIN` and `LEFT EXCL JOIN`.
+//!   JOIN` and `LEFT EXCL JOIN`.
 //! * `RIGHT EXCL JOIN` - use the `LEFT EXCL JOIN` with left and right iterators swapped.
 //! * `RIGHT OUTER JOIN` - use the `LEFT OUTER JOIN` with left and right iterators swapped.
 //! * [`FULL OUTER JOIN`](trait.Joinkit.html#method.merge_join_full_outer_by) - a union of `LEFT
-//! EXCL JOIN` , `INNER JOIN` and `RIGHT EXCL JOIN`.
+//!   EXCL JOIN` , `INNER JOIN` and `RIGHT EXCL JOIN`.
 //!
 //! A merge join strategy requires the two iterators to be sorted, but can be *both* arbitrarily
 //! large.
 
-use std::iter::{Peekable,};
-use std::cmp::Ordering;
+use core::iter::{Peekable,};
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem;
+#[cfg(not(feature = "std"))]
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use std::vec;
 use super::EitherOrBoth::{self, Right, Left, Both};
+
+/// A type with an associated join key used by the closure-free merge join adaptors, e.g.
+/// [`merge_join_inner()`](trait.Joinkit.html#method.merge_join_inner).
+///
+/// Both input iterators may have different item types, as long as their keys are of the same
+/// type.
+///
+/// The trait is implemented for all `(K, V)` tuples, where the key is the first element.
+pub trait MergeJoinKey {
+    /// The type of the join key.
+    type Key: Ord;
+
+    /// Return the join key of `self`.
+    fn key(&self) -> Self::Key;
+}
+
+impl<K, V> MergeJoinKey for (K, V)
+    where K: Ord + Clone,
+{
+    type Key = K;
+
+    fn key(&self) -> K {
+        self.0.clone()
+    }
+}
+
+/// The comparator used by the closure-free merge join adaptors, comparing the items on their
+/// [`MergeJoinKey`](trait.MergeJoinKey.html) keys.
+pub type KeyCmp<L, R> = fn(&L, &R) -> Ordering;
+
+/// The left and right key runs of a matched key, borrowed from the reused buffers of
+/// [`MergeJoinInnerGroups::next_group()`](struct.MergeJoinInnerGroups.html#method.next_group).
+pub type GroupRef<'a, L, R> = (&'a [L], &'a [R]);
+
+/// The result of
+/// [`MergeJoinInnerGroupsCapped::next_group()`](struct.MergeJoinInnerGroupsCapped.html#method.next_group).
+pub type CappedGroupRef<'a, L, R> = Result<GroupRef<'a, L, R>, GroupTooLarge<L, R>>;
+
+/// Compares the keys of two `MergeJoinKey` values.
+pub(crate) fn cmp_keys<L, R>(l: &L, r: &R) -> Ordering
+    where L: MergeJoinKey,
+          R: MergeJoinKey<Key=L::Key>,
+{
+    Ord::cmp(&l.key(), &r.key())
+}
  
 /// See [`merge_join_inner_by()`](trait.Joinkit.html#method.merge_join_inner_by) for the description and
 /// examples.
@@ -32,11 +83,1114 @@ pub struct MergeJoinInner<L, R, F>
     cmp: F,
 }
 
-impl<L, R, F> MergeJoinInner<L, R, F>
-    where L: Iterator,
-          R: Iterator,
+impl<L, R, F> MergeJoinInner<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInner` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinInner` iterator from the already peekable input iterators, e.g. to keep
+    /// the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinInner {
+            left,
+            right,
+            cmp,
+        }
+    }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// This is useful to resume the processing of the remaining items manually, e.g. after
+    /// breaking out of the join early. Any item, which has been peeked by the adaptor, but not
+    /// yet consumed, is still buffered in the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinInner<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ord = match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r),
+                _ => return None,
+            };
+
+            match ord {
+                Ordering::Less => {self.left.next();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => match (self.left.next(), self.right.next()) {
+                    (Some(l), Some(r)) => return Some((l, r)),
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every item is matched at most once
+        let upper = match (self.left.size_hint().1, self.right.size_hint().1) {
+            (Some(l), Some(r)) => Some(l.min(r)),
+            (l, r) => l.or(r),
+        };
+        (0, upper)
+    }
+}
+
+/// See [`merge_join_inner_by_with_progress()`](trait.Joinkit.html#method.merge_join_inner_by_with_progress)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerProgress<L, R, F, P> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    /// the number of the left items between the calls of `on_step`
+    every: usize,
+    on_step: P,
+    /// the number of the left items consumed so far
+    consumed: usize,
+}
+
+impl<L, R, F, P> MergeJoinInnerProgress<L, R, F, P>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInnerProgress` iterator.
+    ///
+    /// # Panics
+    ///
+    /// If `every` is zero.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F, every: usize, on_step: P) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering,
+              P: FnMut(usize),
+    {
+        assert!(every > 0, "the progress must be reported every positive number of items");
+        MergeJoinInnerProgress {
+            left: left.into_iter().peekable(),
+            right: right.into_iter().peekable(),
+            cmp,
+            every,
+            on_step,
+            consumed: 0,
+        }
+    }
+
+    /// Consume the next left item, reporting the progress, if it is its turn.
+    fn next_left(&mut self) -> Option<L::Item>
+        where P: FnMut(usize),
+    {
+        let l = self.left.next();
+        if l.is_some() {
+            self.consumed += 1;
+            if self.consumed.is_multiple_of(self.every) {
+                (self.on_step)(self.consumed);
+            }
+        }
+        l
+    }
+}
+
+impl<L, R, F, P> Iterator for MergeJoinInnerProgress<L, R, F, P> 
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering,
+          P: FnMut(usize),
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ord = match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r),
+                _ => return None,
+            };
+
+            match ord {
+                Ordering::Less => {self.next_left();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => match (self.next_left(), self.right.next()) {
+                    (Some(l), Some(r)) => return Some((l, r)),
+                    _ => return None,
+                }
+            }
+        }
+    }
+}
+
+/// See [`merge_join_inner_keyed_by()`](trait.Joinkit.html#method.merge_join_inner_keyed_by) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerKeyed<L, R, K, F> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    last_key: Option<K>,
+}
+
+impl<L, R, K, F> MergeJoinInnerKeyed<L, R, K, F>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInnerKeyed` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&K, &K) -> Ordering
+    {
+        MergeJoinInnerKeyed {
+            left: left.into_iter().peekable(),
+            right: right.into_iter().peekable(),
+            cmp,
+            last_key: None,
+        }
+    }
+
+    /// Return the key of the most recently yielded pair, or `None`, if no pair has been yielded
+    /// yet.
+    pub fn last_key(&self) -> Option<&K> {
+        self.last_key.as_ref()
+    }
+}
+
+impl<L, R, K, LV, RV, F> Iterator for MergeJoinInnerKeyed<L, R, K, F> 
+    where L: Iterator<Item=(K, LV)>,
+          R: Iterator<Item=(K, RV)>,
+          K: Clone,
+          F: FnMut(&K, &K) -> Ordering
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ord = match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(&l.0, &r.0),
+                _ => return None,
+            };
+
+            match ord {
+                Ordering::Less => {self.left.next();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => match (self.left.next(), self.right.next()) {
+                    (Some(l), Some(r)) => {
+                        // the previous key is overwritten in place, reusing its allocation
+                        match self.last_key {
+                            Some(ref mut k) => k.clone_from(&l.0),
+                            None => self.last_key = Some(l.0.clone()),
+                        }
+                        return Some((l, r));
+                    },
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every item is matched at most once
+        let upper = match (self.left.size_hint().1, self.right.size_hint().1) {
+            (Some(l), Some(r)) => Some(l.min(r)),
+            (l, r) => l.or(r),
+        };
+        (0, upper)
+    }
+}
+
+/// See [`merge_join_inner_ref_by()`](trait.Joinkit.html#method.merge_join_inner_ref_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerRef<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+}
+
+impl<'a, 'b, L, R, LT, RT, F> MergeJoinInnerRef<L, R, F>
+    where L: Iterator<Item=&'a LT>,
+          R: Iterator<Item=&'b RT>,
+          LT: 'a + ?Sized,
+          RT: 'b + ?Sized,
+{
+    /// Create a `MergeJoinInnerRef` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&LT, &RT) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinInnerRef` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&LT, &RT) -> Ordering
+    {
+        MergeJoinInnerRef {
+            left,
+            right,
+            cmp,
+        }
+    }
+}
+
+impl<'a, 'b, L, R, LT, RT, F> Iterator for MergeJoinInnerRef<L, R, F> 
+    where L: Iterator<Item=&'a LT>,
+          R: Iterator<Item=&'b RT>,
+          LT: 'a + ?Sized,
+          RT: 'b + ?Sized,
+          F: FnMut(&LT, &RT) -> Ordering
+{
+    type Item = (&'a LT, &'b RT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // the references are copied out of the iterators, the items are never moved
+            let (l, r) = match (self.left.peek(), self.right.peek()) {
+                (Some(&l), Some(&r)) => (l, r),
+                _ => return None,
+            };
+
+            match (self.cmp)(l, r) {
+                Ordering::Less => {self.left.next();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => {
+                    self.left.next();
+                    self.right.next();
+                    return Some((l, r));
+                },
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every item is matched at most once
+        let upper = match (self.left.size_hint().1, self.right.size_hint().1) {
+            (Some(l), Some(r)) => Some(l.min(r)),
+            (l, r) => l.or(r),
+        };
+        (0, upper)
+    }
+}
+
+/// See [`merge_join_interval_by()`](trait.Joinkit.html#method.merge_join_interval_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInterval<L, R, KF, LF, HF> 
+    where R: Iterator,
+{
+    left: L,
+    ranges: Peekable<R>,
+    key: KF,
+    lo: LF,
+    hi: HF,
+}
+
+impl<'b, L, R, RT, K, KF, LF, HF> MergeJoinInterval<L, R, KF, LF, HF>
+    where L: Iterator,
+          R: Iterator<Item=&'b RT>,
+          RT: 'b + ?Sized,
+          K: Ord,
+          KF: FnMut(&L::Item) -> K,
+          LF: FnMut(&RT) -> K,
+          HF: FnMut(&RT) -> K,
+{
+    /// Create a `MergeJoinInterval` iterator.
+    pub fn new<LI, RI>(left: LI, ranges: RI, key: KF, lo: LF, hi: HF) -> Self
+        where LI: IntoIterator<IntoIter=L>,
+              RI: IntoIterator<IntoIter=R>,
+    {
+        MergeJoinInterval {
+            left: left.into_iter(),
+            ranges: ranges.into_iter().peekable(),
+            key,
+            lo,
+            hi,
+        }
+    }
+}
+
+impl<'b, L, R, RT, K, KF, LF, HF> Iterator for MergeJoinInterval<L, R, KF, LF, HF> 
+    where L: Iterator,
+          R: Iterator<Item=&'b RT>,
+          RT: 'b + ?Sized,
+          K: Ord,
+          KF: FnMut(&L::Item) -> K,
+          LF: FnMut(&RT) -> K,
+          HF: FnMut(&RT) -> K,
+{
+    type Item = (L::Item, &'b RT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let l = self.left.next()?;
+            let k = (self.key)(&l);
+            // the ranges ending before the key can not contain any of the following keys either
+            loop {
+                let r = *self.ranges.peek()?;
+                if (self.hi)(r) >= k {
+                    break;
+                }
+                self.ranges.next();
+            }
+            // the range is kept for the following keys, which may fall into it as well
+            let r = *self.ranges.peek()?;
+            if (self.lo)(r) <= k {
+                return Some((l, r));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every left item is matched at most once
+        (0, self.left.size_hint().1)
+    }
+}
+
+/// See [`merge_join_inner3_by()`](trait.Joinkit.html#method.merge_join_inner3_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInner3<A, B, C, F, G> 
+    where A: Iterator,
+          B: Iterator,
+          C: Iterator,
+{
+    a: Peekable<A>,
+    b: Peekable<B>,
+    c: Peekable<C>,
+    cmp_ab: F,
+    cmp_bc: G,
+}
+
+impl<A, B, C, F, G> MergeJoinInner3<A, B, C, F, G>
+    where A: Iterator,
+          B: Iterator,
+          C: Iterator,
+{
+    /// Create a `MergeJoinInner3` iterator.
+    pub fn new<AI, BI, CI>(a: AI, b: BI, c: CI, cmp_ab: F, cmp_bc: G) -> Self
+        where AI: IntoIterator<IntoIter=A>,
+              BI: IntoIterator<IntoIter=B>,
+              CI: IntoIterator<IntoIter=C>,
+              F: FnMut(&A::Item, &B::Item) -> Ordering,
+              G: FnMut(&B::Item, &C::Item) -> Ordering
+    {
+        Self::from_peekable(a.into_iter().peekable(), b.into_iter().peekable(), c.into_iter().peekable(), 
+                            cmp_ab, cmp_bc)
+    }
+
+    /// Create a `MergeJoinInner3` iterator from the already peekable input iterators, e.g. to keep
+    /// the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(a: Peekable<A>, b: Peekable<B>, c: Peekable<C>, cmp_ab: F, cmp_bc: G) -> Self
+        where F: FnMut(&A::Item, &B::Item) -> Ordering,
+              G: FnMut(&B::Item, &C::Item) -> Ordering
+    {
+        MergeJoinInner3 {
+            a,
+            b,
+            c,
+            cmp_ab,
+            cmp_bc,
+        }
+    }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// Any item, which has been peeked by the adaptor, but not yet consumed, is still buffered in
+    /// the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<A>, Peekable<B>, Peekable<C>) {
+        (self.a, self.b, self.c)
+    }
+}
+
+impl<A, B, C, F, G> Iterator for MergeJoinInner3<A, B, C, F, G> 
+    where A: Iterator,
+          B: Iterator,
+          C: Iterator,
+          F: FnMut(&A::Item, &B::Item) -> Ordering,
+          G: FnMut(&B::Item, &C::Item) -> Ordering
+{
+    type Item = (A::Item, B::Item, C::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // the middle iterator is compared to both others, so only the least of the two
+            // compared items is advanced at a time
+            let (ord_ab, ord_bc) = match (self.a.peek(), self.b.peek(), self.c.peek()) {
+                (Some(a), Some(b), Some(c)) => match (self.cmp_ab)(a, b) {
+                    Ordering::Equal => (Ordering::Equal, (self.cmp_bc)(b, c)),
+                    ord => (ord, Ordering::Equal),
+                },
+                _ => return None,
+            };
+
+            match (ord_ab, ord_bc) {
+                (Ordering::Less, _) => {self.a.next();},
+                (Ordering::Greater, _) => {self.b.next();},
+                (Ordering::Equal, Ordering::Less) => {self.b.next();},
+                (Ordering::Equal, Ordering::Greater) => {self.c.next();},
+                (Ordering::Equal, Ordering::Equal) => match (self.a.next(), self.b.next(), self.c.next()) {
+                    (Some(a), Some(b), Some(c)) => return Some((a, b, c)),
+                    _ => return None,
+                },
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every item is matched at most once
+        let upper = [self.a.size_hint().1, self.b.size_hint().1, self.c.size_hint().1]
+            .iter()
+            .filter_map(|&u| u)
+            .min();
+        (0, upper)
+    }
+}
+
+/// See [`merge_join_map_inner_by()`](trait.Joinkit.html#method.merge_join_map_inner_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinMapInner<L, R, F, M> 
+    where L: Iterator,
+          R: Iterator,
+{
+    inner: MergeJoinInner<L, R, F>,
+    merge: M,
+}
+
+impl<L, R, F, M> MergeJoinMapInner<L, R, F, M>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinMapInner` iterator.
+    pub fn new<LI, RI, O>(left: LI, right: RI, cmp: F, merge: M) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering,
+              M: FnMut(L::Item, R::Item) -> O,
+    {
+        MergeJoinMapInner {
+            inner: MergeJoinInner::new(left, right, cmp),
+            merge,
+        }
+    }
+
+    /// Create a `MergeJoinMapInner` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable<O>(left: Peekable<L>, right: Peekable<R>, cmp: F, merge: M) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering,
+              M: FnMut(L::Item, R::Item) -> O,
+    {
+        MergeJoinMapInner {
+            inner: MergeJoinInner::from_peekable(left, right, cmp),
+            merge,
+        }
+    }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// See [`MergeJoinInner::into_parts()`](struct.MergeJoinInner.html#method.into_parts).
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        self.inner.into_parts()
+    }
+}
+
+impl<L, R, F, M, O> Iterator for MergeJoinMapInner<L, R, F, M> 
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering,
+          M: FnMut(L::Item, R::Item) -> O,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (self.merge)(l, r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// See [`merge_join_inner_groups_by()`](trait.Joinkit.html#method.merge_join_inner_groups_by)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerGroups<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    /// the left key run of the last group - the buffer is reused by `next_group()`
+    lbuf: Vec<L::Item>,
+    /// the right key run of the last group - the buffer is reused by `next_group()`
+    rbuf: Vec<R::Item>,
+}
+
+impl<L, R, F> MergeJoinInnerGroups<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInnerGroups` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinInnerGroups` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinInnerGroups {
+            left,
+            right,
+            cmp,
+            lbuf: Vec::new(),
+            rbuf: Vec::new(),
+        }
+    }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// Any item, which has been peeked by the adaptor, but not yet consumed, is still buffered in
+    /// the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
+}
+
+impl<L, R, F> MergeJoinInnerGroups<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    /// Advance to the next matched key and return its left and right key runs as slices.
+    ///
+    /// Unlike [`next()`](#method.next), the runs are collected into the internal buffers, which
+    /// are cleared and reused for every group, so no allocation is made once the buffers have
+    /// grown to the size of the largest group. The slices borrow the adaptor and are therefore
+    /// only valid until the next call to `next_group()` or `next()`.
+    ///
+    /// ```
+    /// use joinkit::Joinkit;
+    ///
+    /// let l = vec![(0, 10), (1, 20), (1, 30), (2, 40)].into_iter();
+    /// let r = vec![(1, "X"), (1, "Y"), (2, "Z")].into_iter();
+    /// let mut it = l.merge_join_inner_groups_by(r, |x, y| Ord::cmp(&x.0, &y.0));
+    ///
+    /// let mut sums = Vec::new();
+    /// while let Some((lvv, rvv)) = it.next_group() {
+    ///     sums.push((lvv.iter().map(|l| l.1).sum::<i32>(), rvv.len()));
+    /// }
+    /// assert_eq!(sums, vec![(50, 2), (40, 1)]);
+    /// ```
+    pub fn next_group(&mut self) -> Option<GroupRef<'_, L::Item, R::Item>> {
+        if self.fill_group() {
+            Some((&self.lbuf, &self.rbuf))
+        } else {
+            None
+        }
+    }
+
+    /// Collect the key runs of the next matched key into `self.lbuf` and `self.rbuf`, returning
+    /// `false` once either input iterator is exhausted.
+    fn fill_group(&mut self) -> bool {
+        self.lbuf.clear();
+        self.rbuf.clear();
+        loop {
+            let ord = match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r),
+                _ => return false,
+            };
+
+            match ord {
+                Ordering::Less => {self.left.next();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => match (self.left.next(), self.right.next()) {
+                    (Some(l), Some(r)) => {
+                        // collect the rest of the key runs, comparing them against the first
+                        // item of the other side
+                        self.lbuf.push(l);
+                        while let Some(l) = self.left.peek() {
+                            if (self.cmp)(l, &r) != Ordering::Equal {
+                                break;
+                            }
+                            self.lbuf.extend(self.left.next());
+                        }
+                        self.rbuf.push(r);
+                        while let Some(r) = self.right.peek() {
+                            if (self.cmp)(&self.lbuf[0], r) != Ordering::Equal {
+                                break;
+                            }
+                            self.rbuf.extend(self.right.next());
+                        }
+                        return true;
+                    },
+                    _ => return false,
+                }
+            }
+        }
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinInnerGroups<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    type Item = (Vec<L::Item>, Vec<R::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fill_group() {
+            Some((mem::take(&mut self.lbuf), mem::take(&mut self.rbuf)))
+        } else {
+            None
+        }
+    }
+}
+
+/// See [`merge_join_inner_by_then()`](trait.Joinkit.html#method.merge_join_inner_by_then) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerByThen<L, R, F, G> 
+    where L: Iterator,
+          R: Iterator,
+{
+    groups: MergeJoinInnerGroups<L, R, F>,
+    then: G,
+    /// the sorted pairs of the current key run
+    run: vec::IntoIter<(L::Item, R::Item)>,
+}
+
+impl<L, R, F, G> MergeJoinInnerByThen<L, R, F, G>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInnerByThen` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F, then: G) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering,
+              G: FnMut(&(L::Item, R::Item), &(L::Item, R::Item)) -> Ordering,
+    {
+        MergeJoinInnerByThen {
+            groups: MergeJoinInnerGroups::new(left, right, cmp),
+            then,
+            run: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<L, R, F, G> Iterator for MergeJoinInnerByThen<L, R, F, G> 
+    where L: Iterator,
+          R: Iterator,
+          L::Item: Clone,
+          R::Item: Clone,
+          F: FnMut(&L::Item, &R::Item) -> Ordering,
+          G: FnMut(&(L::Item, R::Item), &(L::Item, R::Item)) -> Ordering,
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.run.next() {
+                return Some(pair);
+            }
+            // the pairs are cloned anyway, so the groups are borrowed from the reused buffers
+            let (lvv, rvv) = self.groups.next_group()?;
+            let mut pairs = Vec::with_capacity(lvv.len() * rvv.len());
+            for l in lvv {
+                for r in rvv {
+                    pairs.push((l.clone(), r.clone()));
+                }
+            }
+            pairs.sort_by(&mut self.then);
+            self.run = pairs.into_iter();
+        }
+    }
+}
+
+/// What to do with a key run exceeding the maximum group size, see
+/// [`merge_join_inner_grouped_capped_by()`](trait.Joinkit.html#method.merge_join_inner_grouped_capped_by).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowAction {
+    /// Yield a [`GroupTooLarge`](struct.GroupTooLarge.html) error and stop.
+    Error,
+    /// Skip the key, as if it was not matched.
+    Skip,
+}
+
+/// The error yielded by
+/// [`merge_join_inner_grouped_capped_by()`](trait.Joinkit.html#method.merge_join_inner_grouped_capped_by)
+/// for a matched key with more than `max` items in either input iterator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupTooLarge<L, R> {
+    /// the first left item of the key
+    pub left: L,
+    /// the first right item of the key
+    pub right: R,
+    /// the maximum group size
+    pub max: usize,
+}
+
+impl<L, R> fmt::Display for GroupTooLarge<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the key group exceeds the maximum size of {} items", self.max)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L: fmt::Debug, R: fmt::Debug> ::std::error::Error for GroupTooLarge<L, R> {}
+
+/// See
+/// [`merge_join_inner_grouped_capped_by()`](trait.Joinkit.html#method.merge_join_inner_grouped_capped_by)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinInnerGroupsCapped<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    max: usize,
+    action: OverflowAction,
+    /// set once the error has been yielded
+    done: bool,
+    /// the left key run of the last group - the buffer is reused by `next_group()`
+    lbuf: Vec<L::Item>,
+    /// the right key run of the last group - the buffer is reused by `next_group()`
+    rbuf: Vec<R::Item>,
+}
+
+impl<L, R, F> MergeJoinInnerGroupsCapped<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinInnerGroupsCapped` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F, max: usize, action: OverflowAction) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp, max, action)
+    }
+
+    /// Create a `MergeJoinInnerGroupsCapped` iterator from the already peekable input iterators,
+    /// e.g. to keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, 
+                         right: Peekable<R>, 
+                         cmp: F, 
+                         max: usize, 
+                         action: OverflowAction) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinInnerGroupsCapped {
+            left,
+            right,
+            cmp,
+            max,
+            action,
+            done: false,
+            lbuf: Vec::new(),
+            rbuf: Vec::new(),
+        }
+    }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// Any item, which has been peeked by the adaptor, but not yet consumed, is still buffered in
+    /// the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
+}
+
+impl<L, R, F> MergeJoinInnerGroupsCapped<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    /// Advance to the next matched key and return its left and right key runs as slices, or the
+    /// error for a key run exceeding the maximum group size.
+    ///
+    /// As with [`MergeJoinInnerGroups::next_group()`](struct.MergeJoinInnerGroups.html#method.next_group),
+    /// the runs are collected into the internal buffers, which are cleared and reused for every
+    /// group. The slices are only valid until the next call to `next_group()` or `next()`.
+    ///
+    /// ```
+    /// use joinkit::{Joinkit, OverflowAction};
+    ///
+    /// let l = vec![(0, 10), (1, 20), (2, 40)].into_iter();
+    /// let r = vec![(1, "X"), (2, "Y"), (2, "Z")].into_iter();
+    /// let mut it = l.merge_join_inner_grouped_capped_by(r, |x, y| Ord::cmp(&x.0, &y.0), 1,
+    ///                                                    OverflowAction::Skip);
+    ///
+    /// // the key 2 exceeds the maximum group size, so it is skipped
+    /// let mut sizes = Vec::new();
+    /// while let Some(Ok((lvv, rvv))) = it.next_group() {
+    ///     sizes.push((lvv[0].1, rvv.len()));
+    /// }
+    /// assert_eq!(sizes, vec![(20, 1)]);
+    /// ```
+    pub fn next_group(&mut self) -> Option<CappedGroupRef<'_, L::Item, R::Item>> {
+        match self.fill_group() {
+            Ok(true) => Some(Ok((&self.lbuf, &self.rbuf))),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Collect the key runs of the next matched key into `self.lbuf` and `self.rbuf`, returning
+    /// `false` once either input iterator is exhausted or the error has been returned.
+    fn fill_group(&mut self) -> Result<bool, GroupTooLarge<L::Item, R::Item>> {
+        if self.done {
+            return Ok(false);
+        }
+        loop {
+            self.lbuf.clear();
+            self.rbuf.clear();
+            let ord = match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r),
+                _ => return Ok(false),
+            };
+
+            match ord {
+                Ordering::Less => {self.left.next();},
+                Ordering::Greater =>{self.right.next();},
+                Ordering::Equal => match (self.left.next(), self.right.next()) {
+                    (Some(l), Some(r)) => {
+                        // the items beyond the maximum are not buffered, but still consumed in
+                        // order to skip the whole key
+                        let mut overflow = false;
+                        self.lbuf.push(l);
+                        while let Some(l) = self.left.peek() {
+                            if (self.cmp)(l, &r) != Ordering::Equal {
+                                break;
+                            }
+                            let l = self.left.next();
+                            if self.lbuf.len() < self.max {
+                                self.lbuf.extend(l);
+                            } else {
+                                overflow = true;
+                            }
+                        }
+                        self.rbuf.push(r);
+                        while let Some(r) = self.right.peek() {
+                            if (self.cmp)(&self.lbuf[0], r) != Ordering::Equal {
+                                break;
+                            }
+                            let r = self.right.next();
+                            if self.rbuf.len() < self.max {
+                                self.rbuf.extend(r);
+                            } else {
+                                overflow = true;
+                            }
+                        }
+                        if !overflow && self.max > 0 {
+                            return Ok(true);
+                        }
+                        if self.action == OverflowAction::Error {
+                            self.done = true;
+                            let (left, right) = (self.lbuf.swap_remove(0), self.rbuf.swap_remove(0));
+                            return Err(GroupTooLarge { left, right, max: self.max });
+                        }
+                    },
+                    _ => return Ok(false),
+                }
+            }
+        }
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinInnerGroupsCapped<L, R, F> 
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    type Item = Result<(Vec<L::Item>, Vec<R::Item>), GroupTooLarge<L::Item, R::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.fill_group() {
+            Ok(true) => Some(Ok((mem::take(&mut self.lbuf), mem::take(&mut self.rbuf)))),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// See [`merge_join_records_by()`](trait.Joinkit.html#method.merge_join_records_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinRecords<L, R, K, LF, RF> 
+    where L: Iterator,
+          R: Iterator,
+{
+    left: L,
+    right: R,
+    left_key: LF,
+    right_key: RF,
+    /// the next right item along with its key
+    head: Option<(K, R::Item)>,
+    /// the key of the current right run
+    run_key: Option<K>,
+    /// the current right run - the buffer is reused for all the runs
+    run: Vec<R::Item>,
+    /// the current left item along with the index of the next right item in the run to pair
+    cur: Option<(L::Item, usize)>,
+}
+
+impl<L, R, K, LF, RF> MergeJoinRecords<L, R, K, LF, RF>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinRecords` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, left_key: LF, right_key: RF) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              LF: FnMut(&L::Item) -> K,
+              RF: FnMut(&R::Item) -> K,
+    {
+        MergeJoinRecords {
+            left: left.into_iter(),
+            right: right.into_iter(),
+            left_key,
+            right_key,
+            head: None,
+            run_key: None,
+            run: Vec::new(),
+            cur: None,
+        }
+    }
+}
+
+impl<L, R, K, LF, RF> MergeJoinRecords<L, R, K, LF, RF>
+    where L: Iterator,
+          R: Iterator,
+          K: Ord,
+          RF: FnMut(&R::Item) -> K,
+{
+    /// Collect the right run matching the left key `lk` into `self.run`, skipping the smaller
+    /// right items.
+    fn fill_run(&mut self, lk: K) {
+        self.run.clear();
+        loop {
+            if self.head.is_none() {
+                let right_key = &mut self.right_key;
+                self.head = self.right.next().map(|r| (right_key(&r), r));
+            }
+            let ord = match self.head {
+                Some((ref rk, _)) => Ord::cmp(rk, &lk),
+                None => break,
+            };
+            match ord {
+                Ordering::Less => {self.head = None;},
+                Ordering::Equal => self.run.extend(self.head.take().map(|(_, r)| r)),
+                Ordering::Greater => break,
+            }
+        }
+        self.run_key = Some(lk);
+    }
+}
+
+impl<L, R, K, LF, RF> Iterator for MergeJoinRecords<L, R, K, LF, RF> 
+    where L: Iterator,
+          R: Iterator,
+          L::Item: Clone,
+          R::Item: Clone,
+          K: Ord,
+          LF: FnMut(&L::Item) -> K,
+          RF: FnMut(&R::Item) -> K,
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((l, i)) = self.cur.take() {
+                let r = self.run[i].clone();
+                // the last right item of the run takes the left item, the others clone it
+                if i + 1 == self.run.len() {
+                    return Some((l, r));
+                }
+                self.cur = Some((l.clone(), i + 1));
+                return Some((l, r));
+            }
+            let l = self.left.next()?;
+            let lk = (self.left_key)(&l);
+            if self.run_key.as_ref() != Some(&lk) {
+                self.fill_run(lk);
+            }
+            if !self.run.is_empty() {
+                self.cur = Some((l, 0));
+            }
+        }
+    }
+}
+
+/// See [`merge_join_left_excl_by()`](trait.Joinkit.html#method.merge_join_left_excl_by) for the
+/// description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinLeftExcl<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    fused: Option<Ordering>,
+}
+
+impl<L, R, F> MergeJoinLeftExcl<L, R, F> where
+    L: Iterator,
+    R: Iterator,
 {
-    /// Create a `MergeJoinInner` iterator.
+    /// Create a `MergeJoinLeftExcl` iterator.
     pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
         where L: Iterator<Item=LI::Item>,
               LI: IntoIterator<IntoIter=L>,
@@ -44,44 +1198,150 @@ impl<L, R, F> MergeJoinInner<L, R, F>
               RI: IntoIterator<IntoIter=R>,
               F: FnMut(&L::Item, &R::Item) -> Ordering
     {
-        MergeJoinInner {
-            left: left.into_iter().peekable(),
-            right: right.into_iter().peekable(),
-            cmp: cmp,
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinLeftExcl` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftExcl {
+            left,
+            right,
+            cmp,
+            fused: None,
         }
     }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// This is useful to resume the processing of the remaining items manually, e.g. after
+    /// breaking out of the join early. Any item, which has been peeked by the adaptor, but not
+    /// yet consumed, is still buffered in the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
 }
 
-impl<L, R, F> Iterator for MergeJoinInner<L, R, F> 
+impl<L, R, F> Iterator for MergeJoinLeftExcl<L, R, F> 
     where L: Iterator,
           R: Iterator,
           F: FnMut(&L::Item, &R::Item) -> Ordering
 {
-    type Item = (L::Item, R::Item);
+    type Item = L::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let ord = match (self.left.peek(), self.right.peek()) {
-                (Some(l), Some(r)) => (self.cmp)(l, r),
-                _ => return None,
+            let ord = match self.fused {
+                Some(o) => o,
+                None => match (self.left.peek(), self.right.peek()) {
+                    (Some(l), Some(r)) => (self.cmp)(l, r),
+                    (Some(_), None) => {
+                        self.fused = Some(Ordering::Less);
+                        Ordering::Less
+                    }
+                    _ => return None,
+                }
             };
 
             match ord {
-                Ordering::Less => {self.left.next();},
-                Ordering::Greater =>{self.right.next();},
-                Ordering::Equal => match (self.left.next(), self.right.next()) {
-                    (Some(l), Some(r)) => return Some((l, r)),
-                    _ => return None,
+                Ordering::Less => return self.left.next(),
+                Ordering::Greater => {self.right.next();},
+                Ordering::Equal => {
+                    self.left.next();
+                    self.right.next();
                 }
             }
         }
     }
 }
 
-/// See [`merge_join_left_excl_by()`](trait.Joinkit.html#method.merge_join_left_excl_by) for the
-/// description and examples.
+/// See [`merge_join_left_excl_by_key()`](trait.Joinkit.html#method.merge_join_left_excl_by_key)
+/// for the description and examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
-pub struct MergeJoinLeftExcl<L, R, F> where
+pub struct MergeJoinLeftExclKeyed<L, R, K, LF, RF> {
+    left: L,
+    right: R,
+    left_key: LF,
+    right_key: RF,
+    /// the key of the next right item, instead of the item itself
+    head: Option<K>,
+    /// whether the right iterator is exhausted
+    right_done: bool,
+}
+
+impl<L, R, K, LF, RF> MergeJoinLeftExclKeyed<L, R, K, LF, RF>
+    where L: Iterator,
+          R: Iterator,
+{
+    /// Create a `MergeJoinLeftExclKeyed` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, left_key: LF, right_key: RF) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              LF: FnMut(&L::Item) -> K,
+              RF: FnMut(&R::Item) -> K,
+    {
+        MergeJoinLeftExclKeyed {
+            left: left.into_iter(),
+            right: right.into_iter(),
+            left_key,
+            right_key,
+            head: None,
+            right_done: false,
+        }
+    }
+}
+
+impl<L, R, K, LF, RF> Iterator for MergeJoinLeftExclKeyed<L, R, K, LF, RF> 
+    where L: Iterator,
+          R: Iterator,
+          K: Ord,
+          LF: FnMut(&L::Item) -> K,
+          RF: FnMut(&R::Item) -> K,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let l = self.left.next()?;
+            let lk = (self.left_key)(&l);
+            loop {
+                if self.head.is_none() && !self.right_done {
+                    // the right item is dropped right away, only its key is kept
+                    match self.right.next() {
+                        Some(r) => self.head = Some((self.right_key)(&r)),
+                        None => self.right_done = true,
+                    }
+                }
+                let ord = match self.head {
+                    Some(ref rk) => Ord::cmp(&lk, rk),
+                    None => return Some(l),
+                };
+                match ord {
+                    Ordering::Less => return Some(l),
+                    Ordering::Greater => {self.head = None;},
+                    Ordering::Equal => {
+                        self.head = None;
+                        break;
+                    },
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.left.size_hint().1)
+    }
+}
+
+/// See
+/// [`merge_join_left_excl_with_context_by()`](trait.Joinkit.html#method.merge_join_left_excl_with_context_by)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinLeftExclContext<L, R, F> where
     L: Iterator,
     R: Iterator,
 {
@@ -89,13 +1349,15 @@ pub struct MergeJoinLeftExcl<L, R, F> where
     right: Peekable<R>,
     cmp: F,
     fused: Option<Ordering>,
+    /// the last consumed right value, i.e. the lower neighbor of the current left value
+    prev: Option<R::Item>,
 }
 
-impl<L, R, F> MergeJoinLeftExcl<L, R, F> where
+impl<L, R, F> MergeJoinLeftExclContext<L, R, F> where
     L: Iterator,
     R: Iterator,
 {
-    /// Create a `MergeJoinLeftExcl` iterator.
+    /// Create a `MergeJoinLeftExclContext` iterator.
     pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
         where L: Iterator<Item=LI::Item>,
               LI: IntoIterator<IntoIter=L>,
@@ -103,21 +1365,31 @@ impl<L, R, F> MergeJoinLeftExcl<L, R, F> where
               RI: IntoIterator<IntoIter=R>,
               F: FnMut(&L::Item, &R::Item) -> Ordering
     {
-        MergeJoinLeftExcl {
-            left: left.into_iter().peekable(),
-            right: right.into_iter().peekable(),
-            cmp: cmp,
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinLeftExclContext` iterator from the already peekable input iterators, e.g.
+    /// to keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftExclContext {
+            left,
+            right,
+            cmp,
             fused: None,
+            prev: None,
         }
     }
 }
 
-impl<L, R, F> Iterator for MergeJoinLeftExcl<L, R, F> 
+impl<L, R, F> Iterator for MergeJoinLeftExclContext<L, R, F> 
     where L: Iterator,
           R: Iterator,
+          R::Item: Clone,
           F: FnMut(&L::Item, &R::Item) -> Ordering
 {
-    type Item = L::Item;
+    type Item = (L::Item, Option<R::Item>, Option<R::Item>);
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
@@ -134,11 +1406,14 @@ impl<L, R, F> Iterator for MergeJoinLeftExcl<L, R, F>
             };
 
             match ord {
-                Ordering::Less => return self.left.next(),
-                Ordering::Greater => {self.right.next();},
+                Ordering::Less => match self.left.next() {
+                    Some(l) => return Some((l, self.prev.clone(), self.right.peek().cloned())),
+                    None => return None,
+                },
+                Ordering::Greater => {self.prev = self.right.next();},
                 Ordering::Equal => {
                     self.left.next();
-                    self.right.next();
+                    self.prev = self.right.next();
                 }
             }
         }
@@ -169,14 +1444,31 @@ impl<L, R, F> MergeJoinLeftOuter<L, R, F> where
               R: Iterator<Item=RI::Item>,
               RI: IntoIterator<IntoIter=R>,
               F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinLeftOuter` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
     {
         MergeJoinLeftOuter {
-            left: left.into_iter().peekable(),
-            right: right.into_iter().peekable(),
-            cmp: cmp,
+            left,
+            right,
+            cmp,
             fused: None,
         }
     }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// This is useful to resume the processing of the remaining items manually, e.g. after
+    /// breaking out of the join early. Any item, which has been peeked by the adaptor, but not
+    /// yet consumed, is still buffered in the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
 }
 
 impl<L, R, F> Iterator for MergeJoinLeftOuter<L, R, F>
@@ -215,6 +1507,126 @@ impl<L, R, F> Iterator for MergeJoinLeftOuter<L, R, F>
     }
 }
 
+/// See [`merge_join_left_option_by()`](trait.Joinkit.html#method.merge_join_left_option_by) for
+/// the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinLeftOption<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    inner: MergeJoinLeftOuter<L, R, F>,
+}
+
+impl<L, R, F> MergeJoinLeftOption<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    /// Create a `MergeJoinLeftOption` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftOption {
+            inner: MergeJoinLeftOuter::new(left, right, cmp),
+        }
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinLeftOption<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    type Item = (L::Item, Option<R::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|e| match e {
+            Left(l) => (l, None),
+            Both(l, r) => (l, Some(r)),
+            Right(_) => unreachable!("a left outer join yields no right-only items"),
+        })
+    }
+}
+
+/// See [`merge_join_left_outer_groups_by()`](trait.Joinkit.html#method.merge_join_left_outer_groups_by)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinLeftOuterGroups<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    /// the right key run matched by the last left item, if any
+    run: Vec<R::Item>,
+}
+
+impl<L, R, F> MergeJoinLeftOuterGroups<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    /// Create a `MergeJoinLeftOuterGroups` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        MergeJoinLeftOuterGroups {
+            left: left.into_iter().peekable(),
+            right: right.into_iter().peekable(),
+            cmp,
+            run: Vec::new(),
+        }
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinLeftOuterGroups<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+          R::Item: Clone,
+          F: FnMut(&L::Item, &R::Item) -> Ordering
+{
+    type Item = EitherOrBoth<L::Item, Vec<R::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let l = self.left.next()?;
+        let matches_run = match self.run.first() {
+            Some(r) => (self.cmp)(&l, r) == Ordering::Equal,
+            None => false,
+        };
+        if !matches_run {
+            // skip the lesser right items and collect the run of the new key, if any
+            self.run.clear();
+            while let Some(r) = self.right.peek() {
+                match (self.cmp)(&l, r) {
+                    Ordering::Greater => {self.right.next();},
+                    Ordering::Equal => self.run.extend(self.right.next()),
+                    Ordering::Less => break,
+                }
+            }
+            if self.run.is_empty() {
+                return Some(Left(l));
+            }
+        }
+        // the run is moved out to the last left item matching it
+        let next_matches = match self.left.peek() {
+            Some(next) => (self.cmp)(next, &self.run[0]) == Ordering::Equal,
+            None => false,
+        };
+        if next_matches {
+            Some(Both(l, self.run.clone()))
+        } else {
+            Some(Both(l, mem::take(&mut self.run)))
+        }
+    }
+}
+
 /// See [`merge_join_full_outer_by()`](trait.Joinkit.html#method.merge_join_full_outer_by) for the
 /// description and examples.
 #[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
@@ -239,14 +1651,31 @@ impl<L, R, F> MergeJoinFullOuter<L, R, F> where
               R: Iterator<Item=RI::Item>,
               RI: IntoIterator<IntoIter=R>,
               F: FnMut(&L::Item, &R::Item) -> Ordering
+    {
+        Self::from_peekable(left.into_iter().peekable(), right.into_iter().peekable(), cmp)
+    }
+
+    /// Create a `MergeJoinFullOuter` iterator from the already peekable input iterators, e.g. to
+    /// keep the items peeked by the caller, without wrapping them again.
+    pub fn from_peekable(left: Peekable<L>, right: Peekable<R>, cmp: F) -> Self
+        where F: FnMut(&L::Item, &R::Item) -> Ordering
     {
         MergeJoinFullOuter {
-            left: left.into_iter().peekable(),
-            right: right.into_iter().peekable(),
-            cmp: cmp,
+            left,
+            right,
+            cmp,
             fused: None,
         }
     }
+
+    /// Consume the adaptor and return the underlying input iterators in their current state.
+    ///
+    /// This is useful to resume the processing of the remaining items manually, e.g. after
+    /// breaking out of the join early. Any item, which has been peeked by the adaptor, but not
+    /// yet consumed, is still buffered in the returned `Peekable`.
+    pub fn into_parts(self) -> (Peekable<L>, Peekable<R>) {
+        (self.left, self.right)
+    }
 }
 
 impl<L, R, F> Iterator for MergeJoinFullOuter<L, R, F>
@@ -257,36 +1686,121 @@ impl<L, R, F> Iterator for MergeJoinFullOuter<L, R, F>
     type Item = EitherOrBoth<L::Item, R::Item>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            let ord = match self.fused {
-                Some(o) => o,
-                None => match (self.left.peek(), self.right.peek()) {
-                    (Some(l), Some(r)) => (self.cmp)(l, r),
-                    (Some(_), None) => {
-                        self.fused = Some(Ordering::Less);
-                        Ordering::Less
-                    }
-                    (None, Some(_)) => {
-                        self.fused = Some(Ordering::Greater);
-                        Ordering::Greater
-                    }
-                    _ => return None,
+        let ord = match self.fused {
+            Some(o) => o,
+            None => match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r),
+                (Some(_), None) => {
+                    self.fused = Some(Ordering::Less);
+                    Ordering::Less
                 }
-            };
+                (None, Some(_)) => {
+                    self.fused = Some(Ordering::Greater);
+                    Ordering::Greater
+                }
+                _ => return None,
+            }
+        };
 
-            match ord {
-                Ordering::Less => match self.left.next() {
-                    Some(l) => return Some(Left(l)),
-                    None => return None,
-                },
-                Ordering::Greater => match self.right.next() {
-                    Some(r) => return Some(Right(r)),
-                    None => return None,
-                },
-                Ordering::Equal => match (self.left.next(), self.right.next()) {
-                    (Some(l), Some(r)) => return Some(Both(l, r)),
-                    _ => return None,
+        match ord {
+            Ordering::Less => self.left.next().map(Left),
+            Ordering::Greater => self.right.next().map(Right),
+            Ordering::Equal => match (self.left.next(), self.right.next()) {
+                (Some(l), Some(r)) => Some(Both(l, r)),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The order of two unmatched items, which are tied in the order of the input iterators, used by
+/// [`merge_join_full_outer_tie_break_by()`](trait.Joinkit.html#method.merge_join_full_outer_tie_break_by).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// Yield the left item first, the same as `merge_join_full_outer_by()` does for a comparator
+    /// returning `Ordering::Less` on a tie (the default).
+    #[default]
+    LeftFirst,
+    /// Yield the right item first.
+    RightFirst,
+}
+
+impl TieBreak {
+    /// Return the ordering of the tied left item against the tied right item.
+    fn ordering(self) -> Ordering {
+        match self {
+            TieBreak::LeftFirst => Ordering::Less,
+            TieBreak::RightFirst => Ordering::Greater,
+        }
+    }
+}
+
+/// See [`merge_join_full_outer_tie_break_by()`](trait.Joinkit.html#method.merge_join_full_outer_tie_break_by)
+/// for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct MergeJoinFullOuterTieBreak<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+    tie_break: TieBreak,
+    fused: Option<Ordering>,
+}
+
+impl<L, R, F> MergeJoinFullOuterTieBreak<L, R, F> where
+    L: Iterator,
+    R: Iterator,
+{
+    /// Create a `MergeJoinFullOuterTieBreak` iterator.
+    pub fn new<LI, RI>(left: LI, right: RI, cmp: F, tie_break: TieBreak) -> Self
+        where L: Iterator<Item=LI::Item>,
+              LI: IntoIterator<IntoIter=L>,
+              R: Iterator<Item=RI::Item>,
+              RI: IntoIterator<IntoIter=R>,
+              F: FnMut(&L::Item, &R::Item) -> Option<Ordering>
+    {
+        MergeJoinFullOuterTieBreak {
+            left: left.into_iter().peekable(),
+            right: right.into_iter().peekable(),
+            cmp,
+            tie_break,
+            fused: None,
+        }
+    }
+}
+
+impl<L, R, F> Iterator for MergeJoinFullOuterTieBreak<L, R, F>
+    where L: Iterator,
+          R: Iterator,
+          F: FnMut(&L::Item, &R::Item) -> Option<Ordering>
+{
+    type Item = EitherOrBoth<L::Item, R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ord = match self.fused {
+            Some(o) => o,
+            None => match (self.left.peek(), self.right.peek()) {
+                (Some(l), Some(r)) => (self.cmp)(l, r).unwrap_or_else(|| self.tie_break.ordering()),
+                (Some(_), None) => {
+                    self.fused = Some(Ordering::Less);
+                    Ordering::Less
                 }
+                (None, Some(_)) => {
+                    self.fused = Some(Ordering::Greater);
+                    Ordering::Greater
+                }
+                _ => return None,
+            }
+        };
+
+        match ord {
+            Ordering::Less => self.left.next().map(Left),
+            Ordering::Greater => self.right.next().map(Right),
+            Ordering::Equal => match (self.left.next(), self.right.next()) {
+                (Some(l), Some(r)) => Some(Both(l, r)),
+                _ => None,
             }
         }
     }
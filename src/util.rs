@@ -1,14 +1,22 @@
 //! This module contains various utilities/helper functions
 
-use itertools::Itertools;
 use clap;
-use std::io::{BufRead, Write, BufWriter,};
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead, Write, BufWriter,};
+use std::iter;
 use std::ptr;
 use std::borrow::Cow;
-use super::Joinkit;
+use std::cmp::Ordering;
+use std::cell::RefCell;
+use std::ops::Deref;
+#[cfg(feature = "regex")]
+use regex::Regex;
+use super::{Joinkit, JoinMode, Strategy};
+use super::EitherOrBoth::{self, Left, Both, Right};
 
 /// Recognized datatypes
-#[derive(Debug, PartialEq, Eq,)]
+#[derive(Debug, Clone, PartialEq, Eq,)]
 pub enum DataType {
     /// Signed integer 64
     I,
@@ -19,7 +27,7 @@ pub enum DataType {
 }
 
 /// Union of numeric and character types
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum VarData {
     /// Contains a number represented by `i64`
     I(i64),
@@ -29,17 +37,586 @@ pub enum VarData {
     S(String),
 }
 
+impl fmt::Display for VarData {
+    /// Writes the number in its canonical form (e.g. `007` parsed as `u64` is written as `7`), or
+    /// the string as it is.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VarData::I(n) => write!(f, "{}", n),
+            VarData::U(n) => write!(f, "{}", n),
+            VarData::S(ref s) => f.write_str(s),
+        }
+    }
+}
+
+/// A key component borrowing the string data from the record, see
+/// [`try_extract_key_ref()`](fn.try_extract_key_ref.html).
+///
+/// It orders and compares equal exactly as the corresponding `VarData`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeyRef<'a> {
+    /// Contains a number represented by `i64`
+    I(i64),
+    /// Contains a number represented by `u64`
+    U(u64),
+    /// Contains a string, usually borrowed from the record
+    S(Cow<'a, str>),
+}
+
+impl<'a> KeyRef<'a> {
+    /// Convert the key component into the owned `VarData`, allocating the borrowed string.
+    pub fn into_owned(self) -> VarData {
+        match self {
+            KeyRef::I(n) => VarData::I(n),
+            KeyRef::U(n) => VarData::U(n),
+            KeyRef::S(s) => VarData::S(s.into_owned()),
+        }
+    }
+}
+
+impl<'a> From<&'a VarData> for KeyRef<'a> {
+    fn from(v: &'a VarData) -> Self {
+        match *v {
+            VarData::I(n) => KeyRef::I(n),
+            VarData::U(n) => KeyRef::U(n),
+            VarData::S(ref s) => KeyRef::S(Cow::Borrowed(s)),
+        }
+    }
+}
+
+/// The normalization applied to the string keys by [`normalized_key()`](fn.normalized_key.html).
+///
+/// The default normalization keeps the string as it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// remove the leading and the trailing whitespace
+    pub trim: bool,
+    /// fold the case, so the keys differing only in the case are equal
+    pub fold_case: bool,
+}
+
+/// A string key normalized before hashing, so it can be used in the hash joins in place of the
+/// raw string to match the keys differing only in the normalized features, e.g. in the case.
+///
+/// The normalized string is stored, so `Hash` and `Eq` (as well as `Ord`) are always consistent.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NormalizedKey(String);
+
+impl NormalizedKey {
+    /// Return the normalized string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Normalize the string key `s` as given by `norm`.
+///
+/// ```
+/// use joinkit::util::{normalized_key, Normalization};
+///
+/// let norm = Normalization { trim: true, fold_case: true };
+/// assert_eq!(normalized_key("ABC ", norm), normalized_key("abc", norm));
+/// assert_eq!(normalized_key(" Abc", norm).as_str(), "abc");
+/// ```
+pub fn normalized_key(s: &str, norm: Normalization) -> NormalizedKey {
+    let s = if norm.trim {s.trim()} else {s};
+    if norm.fold_case {
+        NormalizedKey(s.to_lowercase())
+    } else {
+        NormalizedKey(s.to_string())
+    }
+}
+
+/// The collation of the string keys, i.e. the order in which they are compared by the merge joins,
+/// e.g. to join the files sorted case-insensitively.
+///
+/// The default collation compares the strings by their code points, i.e. as `LC_ALL=C sort`,
+/// which orders `"Z"` before `"a"`.
+///
+/// * `fold_case` compares the letters converted to the upper case, i.e. the ASCII keys are
+///   ordered as by `LC_ALL=C sort -f`.
+/// * `fold_accents` compares the accented Latin letters (of the Latin-1 Supplement and the Latin
+///   Extended-A blocks) as their base letters, e.g. `é` as `e`, i.e. as sorted by the key with the
+///   accents stripped. The other letters, e.g. the ligatures like `œ`, are kept.
+///
+/// **Beware**, the keys equal under the collation are matched, e.g. `"abc"` and `"ABC"`.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use joinkit::util::Collation;
+///
+/// let c = Collation { fold_case: true, fold_accents: true };
+/// assert_eq!(c.cmp("Zoe", "abel"), Ordering::Greater);
+/// assert_eq!(c.cmp("Émile", "emile"), Ordering::Equal);
+/// assert_eq!(c.key("Łódź"), "LODZ");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Collation {
+    /// compare the letters regardless of their case
+    pub fold_case: bool,
+    /// compare the accented letters as their base letters
+    pub fold_accents: bool,
+}
+
+impl Collation {
+    /// Return the collation key of `s`, i.e. the string, whose code points are ordered as `s`
+    /// under the collation.
+    pub fn key(&self, s: &str) -> String {
+        self.chars(s).collect()
+    }
+
+    /// Compare the strings under the collation.
+    pub fn cmp(&self, a: &str, b: &str) -> Ordering {
+        self.chars(a).cmp(self.chars(b))
+    }
+
+    /// Return the characters of `s` folded as given by the collation.
+    fn chars<'a>(&self, s: &'a str) -> impl Iterator<Item=char> + 'a {
+        let (fold_case, fold_accents) = (self.fold_case, self.fold_accents);
+        s.chars()
+            .map(move |c| if fold_accents {base_letter(c)} else {c})
+            .flat_map(move |c| {
+                // a letter may have several upper case characters, e.g. `ß` has `SS`
+                let upper = if fold_case {Some(c.to_uppercase())} else {None};
+                let kept = if fold_case {None} else {Some(c)};
+                Iterator::flatten(upper.into_iter()).chain(kept)
+            })
+    }
+}
+
+/// Returns the base letter of an accented Latin letter, keeping its case, or the character as it
+/// is otherwise.
+fn base_letter(c: char) -> char {
+    let (base, upper) = match c {
+        'À'..='Å' => ('a', true),
+        'Ç' => ('c', true),
+        'È'..='Ë' => ('e', true),
+        'Ì'..='Ï' => ('i', true),
+        'Ñ' => ('n', true),
+        'Ò'..='Ö' | 'Ø' => ('o', true),
+        'Ù'..='Ü' => ('u', true),
+        'Ý' => ('y', true),
+        'à'..='å' => ('a', false),
+        'ç' => ('c', false),
+        'è'..='ë' => ('e', false),
+        'ì'..='ï' => ('i', false),
+        'ñ' => ('n', false),
+        'ò'..='ö' | 'ø' => ('o', false),
+        'ù'..='ü' => ('u', false),
+        'ý' | 'ÿ' => ('y', false),
+        '\u{100}'..='\u{17e}' => {
+            let n = c as u32;
+            let base = match n {
+                0x100..=0x105 => 'a',
+                0x106..=0x10d => 'c',
+                0x10e..=0x111 => 'd',
+                0x112..=0x11b => 'e',
+                0x11c..=0x123 => 'g',
+                0x124..=0x127 => 'h',
+                0x128..=0x131 => 'i',
+                0x134..=0x135 => 'j',
+                0x136..=0x138 => 'k',
+                0x139..=0x142 => 'l',
+                0x143..=0x148 => 'n',
+                0x14c..=0x151 => 'o',
+                0x154..=0x159 => 'r',
+                0x15a..=0x161 => 's',
+                0x162..=0x167 => 't',
+                0x168..=0x173 => 'u',
+                0x174..=0x175 => 'w',
+                0x176..=0x178 => 'y',
+                0x179..=0x17e => 'z',
+                _ => return c,
+            };
+            // the upper and the lower case letters alternate, except for a few
+            let upper = match n {
+                0x138 => false,
+                0x178 => true,
+                0x139..=0x148 | 0x179..=0x17e => !n.is_multiple_of(2),
+                _ => n.is_multiple_of(2),
+            };
+            (base, upper)
+        },
+        _ => return c,
+    };
+    if upper {base.to_ascii_uppercase()} else {base}
+}
+
+/// The error returned by the checked key extraction functions, e.g.
+/// [`try_extract_key()`](fn.try_extract_key.html).
+///
+/// The field indices are base-0, whereas the `Display` output reports them base-1, as they are
+/// specified on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// The key field cannot be converted into `i64`
+    ParseInt {
+        /// the index of the key field in the record
+        idx: usize,
+        /// the value of the key field
+        value: String,
+    },
+    /// The key field cannot be converted into `u64`
+    ParseUint {
+        /// the index of the key field in the record
+        idx: usize,
+        /// the value of the key field
+        value: String,
+    },
+    /// The numeric key field is parsed, but it is not written in the canonical form of the
+    /// number, e.g. `+1`, `007` or `-0`
+    NotCanonical {
+        /// the index of the key field in the record
+        idx: usize,
+        /// the value of the key field
+        value: String,
+    },
+    /// The key field index exceeds the number of fields in the record
+    IndexOutOfRange {
+        /// the index of the key field in the record
+        idx: usize,
+        /// the number of fields in the record
+        nfields: usize,
+    },
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyError::ParseInt { idx, ref value } => {
+                write!(f, "the value '{}' of the field {} cannot be converted into 'i64'",
+                       value, idx + 1)
+            },
+            KeyError::ParseUint { idx, ref value } => {
+                write!(f, "the value '{}' of the field {} cannot be converted into 'u64'",
+                       value, idx + 1)
+            },
+            KeyError::NotCanonical { idx, ref value } => {
+                write!(f, "the value '{}' of the field {} is not a canonical number", value, idx + 1)
+            },
+            KeyError::IndexOutOfRange { idx, nfields } => {
+                write!(f, "the key field {} exceeds the number of fields ({}) in the record",
+                       idx + 1, nfields)
+            },
+        }
+    }
+}
+
+impl error::Error for KeyError {}
+
+/// A field separator, which splits a record into fields.
+///
+/// It is implemented for `str`, i.e. a literal separator, and, with the `regex` feature, for
+/// `regex::Regex`, e.g. `\s+` for the fields separated by irregular whitespace. The regex
+/// separators ignore the matches at the start and at the end of the record, so the leading and
+/// the trailing whitespace do not produce empty fields. They are considerably slower than the
+/// literal ones, since finding each separator runs the regex and each record is searched twice
+/// more to trim it.
+///
+/// # Example
+/// ```
+/// use joinkit::util::Separator;
+///
+/// assert_eq!(Some((1, 3)), ";;".find_in("a;;b"));
+/// assert_eq!(" a ", ";".trim_record(" a "));
+/// ```
+pub trait Separator {
+    /// Returns the byte range of the first separator in `s`, if any.
+    fn find_in(&self, s: &str) -> Option<(usize, usize)>;
+
+    /// Returns the record without the separators, which are not considered as such at its start
+    /// or end. By default, the record is returned as is.
+    fn trim_record<'r>(&self, record: &'r str) -> &'r str {
+        record
+    }
+
+    /// Returns the quote, which encloses the fields not to be split by the separator, if any. By
+    /// default, the fields are not quoted.
+    fn quote(&self) -> Option<char> {
+        None
+    }
+}
+
+/// A field separator, which does not split the fields enclosed in the `quote`, e.g. to extract
+/// the keys of a CSV file with [`extract_key()`](fn.extract_key.html) and the other functions
+/// generic over the [`Separator`](trait.Separator.html).
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType, Quoted, VarData};
+///
+/// let sep = Quoted { sep: ",", quote: '"' };
+/// let key_idx = [(1, 0, DataType::S)];
+/// assert_eq!(Ok(vec![VarData::S("b\"c".to_owned())]),
+///            util::try_extract_key("\"a,x\",\"b\"\"c\"", &sep, &key_idx));
+/// assert_eq!(2, util::num_fields("\"a,x\",b", &sep));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Quoted<'a, S: ?Sized + 'a> {
+    /// the field separator outside of the quotes
+    pub sep: &'a S,
+    /// the quote
+    pub quote: char,
+}
+
+impl<'a, S: ?Sized + Separator> Separator for Quoted<'a, S> {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        self.sep.find_in(s)
+    }
+
+    fn trim_record<'r>(&self, record: &'r str) -> &'r str {
+        self.sep.trim_record(record)
+    }
+
+    fn quote(&self) -> Option<char> {
+        Some(self.quote)
+    }
+}
+
+impl Separator for str {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        s.find(self).map(|i| (i, i + self.len()))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Separator for Regex {
+    fn find_in(&self, s: &str) -> Option<(usize, usize)> {
+        // an empty match would never advance to the next field
+        self.find_iter(s).find(|m| m.start() < m.end()).map(|m| (m.start(), m.end()))
+    }
+
+    fn trim_record<'r>(&self, record: &'r str) -> &'r str {
+        let record = match self.find_in(record) {
+            Some((0, end)) => &record[end..],
+            _ => record,
+        };
+        match self.find_iter(record).filter(|m| m.start() < m.end()).last() {
+            Some(m) if m.end() == record.len() => &record[..m.start()],
+            _ => record,
+        }
+    }
+}
+
+/// An iterator over the fields of a record, yielding the field slices.
+///
+/// The fields are separated by the field separator, see [`Separator`](trait.Separator.html).
+/// Optionally, the fields can be enclosed in
+/// quotes, in which case the field separator inside the quotes does not split the field. A quote
+/// inside a quoted field must be escaped by doubling it, as in CSV. The quoted fields are yielded
+/// without the enclosing quotes, but the doubled quotes are left intact, since the slices are
+/// borrowed from the record. [`next_unescaped()`](#method.next_unescaped) unescapes them, e.g. for
+/// the keys, and [`next_raw()`](#method.next_raw) yields the fields as they are in the record,
+/// e.g. to write them into the output again.
+///
+/// An empty separator never splits the record, so it is yielded as a single field.
+///
+/// # Example
+/// ```
+/// use joinkit::util::Fields;
+///
+/// let fields: Vec<_> = Fields::new("a;b;;d;", ";").collect();
+/// assert_eq!(vec!["a", "b", "", "d", ""], fields);
+///
+/// let fields: Vec<_> = Fields::quoted("\"a;b\";c", ";", '"').collect();
+/// assert_eq!(vec!["a;b", "c"], fields);
+///
+/// let mut fields = Fields::quoted("\"a\"\"b\";c", ";", '"');
+/// assert_eq!(Some("a\"b".into()), fields.next_unescaped());
+/// assert_eq!(Some("c".into()), fields.next_unescaped());
+///
+/// let mut fields = Fields::quoted("\"a;b\";c", ";", '"');
+/// assert_eq!(Some("\"a;b\""), fields.next_raw());
+/// assert_eq!(Some("c"), fields.next_raw());
+/// ```
+#[derive(Debug)]
+pub struct Fields<'a, S: ?Sized + 'a = str> {
+    /// the rest of the record, `None` once the last field has been yielded
+    rest: Option<&'a str>,
+    field_sep: &'a S,
+    quote: Option<char>,
+}
+
+// not derived, since it would require `S: Clone`
+impl<'a, S: ?Sized> Clone for Fields<'a, S> {
+    fn clone(&self) -> Self {
+        Fields {
+            rest: self.rest,
+            field_sep: self.field_sep,
+            quote: self.quote,
+        }
+    }
+}
+
+impl<'a, S: ?Sized + Separator> Fields<'a, S> {
+    /// Create a `Fields` iterator, which splits the record on every field separator, unless it
+    /// is enclosed in the [`quote()`](trait.Separator.html#method.quote) of the separator.
+    pub fn new(record: &'a str, field_sep: &'a S) -> Self {
+        Fields {
+            rest: Some(field_sep.trim_record(record)),
+            field_sep,
+            quote: field_sep.quote(),
+        }
+    }
+
+    /// Create a `Fields` iterator, which does not split the fields enclosed in `quote`.
+    pub fn quoted(record: &'a str, field_sep: &'a S, quote: char) -> Self {
+        Fields {
+            rest: Some(field_sep.trim_record(record)),
+            field_sep,
+            quote: Some(quote),
+        }
+    }
+
+    /// Return the next field as it is in the record, including the enclosing quotes, if any.
+    pub fn next_raw(&mut self) -> Option<&'a str> {
+        self.next_field().map(|(_, raw, _)| raw)
+    }
+
+    /// Return the next field without the enclosing quotes and with the doubled quotes inside
+    /// unescaped. Only the field containing them is allocated.
+    pub fn next_unescaped(&mut self) -> Option<Cow<'a, str>> {
+        let (field, _, quoted) = self.next_field()?;
+        match self.quote {
+            Some(q) if quoted && field.contains(q) => {
+                let mut qq = String::with_capacity(2 * q.len_utf8());
+                qq.push(q);
+                qq.push(q);
+                Some(Cow::Owned(field.replace(&qq, q.encode_utf8(&mut [0; 4]))))
+            },
+            _ => Some(Cow::Borrowed(field)),
+        }
+    }
+
+    /// Splits the unquoted field at the next field separator.
+    fn next_unquoted(&mut self, rest: &'a str) -> &'a str {
+        match self.field_sep.find_in(rest) {
+            // an empty separator would never advance, so the rest is yielded once
+            Some((start, end)) if start < end => {
+                self.rest = Some(&rest[end..]);
+                &rest[..start]
+            },
+            _ => rest,
+        }
+    }
+
+    /// Splits the next field, returning it without the enclosing quotes, the raw field and whether
+    /// it was quoted.
+    fn next_field(&mut self) -> Option<(&'a str, &'a str, bool)> {
+        let rest = self.rest.take()?;
+        let q = match self.quote {
+            Some(q) if rest.starts_with(q) => q,
+            _ => {
+                let field = self.next_unquoted(rest);
+                return Some((field, field, false));
+            },
+        };
+        let inner = &rest[q.len_utf8()..];
+        // find the closing quote, skipping the escaped (doubled) ones
+        let mut end = None;
+        let mut chars = inner.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == q {
+                match chars.peek() {
+                    Some(&(_, n)) if n == q => {chars.next();},
+                    _ => {
+                        end = Some(i);
+                        break;
+                    },
+                }
+            }
+        }
+        match end {
+            Some(i) => {
+                // skip anything between the closing quote and the field separator
+                let after = &inner[i + q.len_utf8()..];
+                let junk = self.next_unquoted(after);
+                let raw_len = rest.len() - after.len() + junk.len();
+                Some((&inner[..i], &rest[..raw_len], true))
+            },
+            // unterminated quote spans the rest of the record
+            None => Some((inner, rest, true)),
+        }
+    }
+}
+
+impl<'a, S: ?Sized + Separator> Iterator for Fields<'a, S> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.next_field().map(|(field, _, _)| field)
+    }
+}
+
+/// Interprets the escape sequences `\t`, `\n`, `\r`, `\0` and `\\` in the separator.
+///
+/// Any other backslash is left as is.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// assert_eq!("\t", util::unescape("\\t"));
+/// assert_eq!("\\x;", util::unescape("\\x;"));
+/// ```
+pub fn unescape(sep: &str) -> Cow<'_, str> {
+    if !sep.contains('\\') {
+        return Cow::Borrowed(sep);
+    }
+    let mut unescaped = String::with_capacity(sep.len());
+    let mut chars = sep.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let e = match chars.peek() {
+            Some(&'t') => '\t',
+            Some(&'n') => '\n',
+            Some(&'r') => '\r',
+            Some(&'0') => '\0',
+            Some(&'\\') => '\\',
+            _ => {
+                unescaped.push(c);
+                continue;
+            },
+        };
+        chars.next();
+        unescaped.push(e);
+    }
+    Cow::Owned(unescaped)
+}
+
+/// Guesses the field separator of a file from its first `record`: a tab if the record contains
+/// any, otherwise a comma.
+///
+/// A tab rarely occurs inside the fields of a CSV file, while a comma commonly does inside the
+/// fields of a TSV file, so the tab wins. It is only a heuristic though: a CSV file whose first
+/// record has a tab inside a field is taken for a TSV one, and any other separator is never
+/// detected.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// assert_eq!("\t", util::detect_field_sep("1,2\tAlice, Bob"));
+/// assert_eq!(",", util::detect_field_sep("1,Alice Bob"));
+/// ```
+pub fn detect_field_sep(record: &str) -> &'static str {
+    if record.contains('\t') {"\t"} else {","}
+}
+
 /// Converts a record separator to a single byte
 pub fn rec_sep_as_byte(rec_str: &str) -> Result<u8, clap::Error> {
     let bytes = rec_str.as_bytes();
     if bytes.len() == 1 {
-        return Ok(bytes[0]);
+        Ok(bytes[0])
     } else {
         let e = clap::Error {message: "Error: input record separator must be encodable to 1 byte \
         exactly!".to_owned(),
                              kind: clap::ErrorKind::ValueValidation,
                              info: None};
-        return Err(e);
+        Err(e)
     }
 }
 
@@ -51,8 +628,14 @@ pub fn rec_sep_as_byte(rec_str: &str) -> Result<u8, clap::Error> {
 /// be sorted (to facilitate the key extraction in `extract_key()` function) and thus might loose
 /// the info about their correct position.
 ///
+/// A contiguous block of fields can be given as a range, e.g. `3:6` expands to `3,4,5,6`, with an
+/// optional data type applying to all of them, e.g. `3:6-u`.
+///
 /// The resulting vector is sorted on the field indices. The error is returned if the input string
-/// contains duplicate field indices or the provided data type is not recognized.
+/// contains the field index 0, duplicate field indices or the provided data type is not
+/// recognized. The duplicates are checked once the ranges are expanded, so a range overlapping
+/// another range or an explicit field is rejected as well, regardless of their data types, naming
+/// both of the colliding items.
 ///
 /// # Example
 /// ```
@@ -71,42 +654,60 @@ pub fn rec_sep_as_byte(rec_str: &str) -> Result<u8, clap::Error> {
 ///                 (2, 1, DataType::I),
 ///                 (3, 3, DataType::S),
 ///                 (5, 2, DataType::U)], field_idx);
+///
+/// // the range expands to the individual fields in their order
+/// let field_idx = util::fields_to_idx(vec!["2:3-u", "1"]).unwrap();
+/// assert_eq!(vec![(0, 2, DataType::S), 
+///                 (1, 0, DataType::U),
+///                 (2, 1, DataType::U)], field_idx);
 pub fn fields_to_idx(f: Vec<&str>) -> Result<Vec<(usize, isize, DataType)>, clap::Error> {
+    let parse_error = || clap::Error {message: "Error: could not parse integer fields!".to_owned(),
+                                      kind: clap::ErrorKind::ValueValidation,
+                                      info: None};
     let mut idx: Vec<(usize, isize, DataType)> = Vec::new();
-    let it = f.iter()
-              .enumerate()
-              .flat_map(|(i0, s)| s.split('-')
-                                   .enumerate()
-                                   .take(2)
-                                   .map(move |(i1, s)| (i0, i1, s)));
-    for (i0, i1, s) in it {
-        // parse index
-        if i1 == 0 {
-            match s.parse::<usize>() {
-                // convert from base 1 to base 0 and assign default data type
-                Ok(u) => idx.push((u - 1, i0 as isize, DataType::S)),
-                Err(_) => return Err(clap::Error {message: "Error: could not parse integer fields!".to_owned(),
-                                                  kind: clap::ErrorKind::ValueValidation,
-                                                  info: None}),
-                
-            }
-        } else { // parse data_type
-            let dt = match s {
-                "i" => DataType::I,
-                "u" => DataType::U,
-                _ => return Err(clap::Error {message: format!("Error: '{}' is not a valid data type!", s),
-                                             kind: clap::ErrorKind::ValueValidation,
-                                             info: None}),
-            };
-
-            // update data type
-            unsafe {
-                // we cannot get here without first pushing to vector, so this is safe
-                idx.get_unchecked_mut(i0).2 = dt;
-            }
+    // the item of the input each field is given by, for the error messages
+    let mut given_by: Vec<&str> = Vec::new();
+    for s in f {
+        let mut it = s.split('-').take(2);
+        // parse index or range of indices
+        let fields = it.next().unwrap_or("");
+        let (first, last) = match fields.find(':') {
+            Some(i) => match (fields[..i].parse::<usize>(), fields[i + 1..].parse::<usize>()) {
+                (Ok(first), Ok(last)) => (first, last),
+                _ => return Err(parse_error()),
+            },
+            None => match fields.parse::<usize>() {
+                Ok(u) => (u, u),
+                Err(_) => return Err(parse_error()),
+            },
+        };
+        if first == 0 {
+            return Err(clap::Error {message: format!("Error: the key field '{}' is invalid, the index starts with 1!", s),
+                                    kind: clap::ErrorKind::ValueValidation,
+                                    info: None});
+        }
+        if first > last {
+            return Err(clap::Error {message: format!("Error: the field range '{}' is empty!", fields),
+                                    kind: clap::ErrorKind::ValueValidation,
+                                    info: None});
+        }
+        // parse data_type, which applies to the whole range
+        let dt = match it.next() {
+            None => DataType::S,
+            Some("i") => DataType::I,
+            Some("u") => DataType::U,
+            Some(s) => return Err(clap::Error {message: format!("Error: '{}' is not a valid data type!", s),
+                                               kind: clap::ErrorKind::ValueValidation,
+                                               info: None}),
+        };
+        for u in first..=last {
+            // convert from base 1 to base 0
+            let pos = idx.len() as isize;
+            idx.push((u - 1, pos, dt.clone()));
+            given_by.push(s);
         }
     }
-    idx.sort_by(|a, b| a.0.cmp(&b.0));
+    idx.sort_by_key(|a| a.0);
     // check if there are duplicates
     {
         let mut it = idx.iter();
@@ -121,7 +722,11 @@ pub fn fields_to_idx(f: Vec<&str>) -> Result<Vec<(usize, isize, DataType)>, clap
         };
         for current in it {
             if previous.0 == current.0 {
-                let e = clap::Error {message: "Error: the key fields must be unique!".to_owned(),
+                // the positions are in the order of the expanded input fields
+                let (a, b) = (given_by[previous.1 as usize], given_by[current.1 as usize]);
+                let (a, b) = if previous.1 < current.1 {(a, b)} else {(b, a)};
+                let e = clap::Error {message: format!("Error: the key fields must be unique, but the field {} \
+                                                       is given by both '{}' and '{}'!", current.0 + 1, a, b),
                                      kind: clap::ErrorKind::ValueValidation,
                                      info: None};
                 return Err(e);
@@ -132,6 +737,128 @@ pub fn fields_to_idx(f: Vec<&str>) -> Result<Vec<(usize, isize, DataType)>, clap
     Ok(idx)
 }
 
+/// The validated key fields of the records, the only way to specify the keys of
+/// [`JoinOptions`](struct.JoinOptions.html).
+///
+/// It contains at least one key field, since the empty keys would all compare equal, so every
+/// left record would be joined with every right one. It dereferences to the key fields, as
+/// returned by [`fields_to_idx()`](fn.fields_to_idx.html), so it can be passed to the key
+/// extraction functions directly.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType, KeySpec, VarData};
+///
+/// let spec = KeySpec::parse(vec!["2-u"]).unwrap();
+/// assert_eq!(Ok(vec![VarData::U(1)]), util::try_extract_key("a;1", ";", &spec));
+///
+/// assert!(KeySpec::new(vec![(1, 0, DataType::U)]).is_ok());
+/// assert!(KeySpec::new(Vec::new()).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySpec(Vec<(usize, isize, DataType)>);
+
+impl KeySpec {
+    /// Create a `KeySpec` from the key fields, returning an error if there are none.
+    pub fn new(key_idx: Vec<(usize, isize, DataType)>) -> Result<Self, clap::Error> {
+        if key_idx.is_empty() {
+            return Err(clap::Error {message: "Error: at least one key field expected!".to_owned(),
+                                    kind: clap::ErrorKind::ValueValidation,
+                                    info: None});
+        }
+        Ok(KeySpec(key_idx))
+    }
+
+    /// Create a `KeySpec` from the field specifications, as parsed by
+    /// [`fields_to_idx()`](fn.fields_to_idx.html).
+    pub fn parse(f: Vec<&str>) -> Result<Self, clap::Error> {
+        fields_to_idx(f).and_then(KeySpec::new)
+    }
+}
+
+impl Deref for KeySpec {
+    type Target = [(usize, isize, DataType)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Checks the keys of both input files, as returned by [`fields_to_idx()`](fn.fields_to_idx.html),
+/// can compare equal.
+///
+/// The keys compare equal only if they have the same number of fields and the fields at the same
+/// position have the same data type, e.g. `VarData::U(10)` never equals `VarData::S("10")`. The
+/// error is returned otherwise, since such a join would silently match nothing.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let left = util::fields_to_idx(vec!["1-u", "2"]).unwrap();
+/// assert!(util::check_key_types(&left, &util::fields_to_idx(vec!["3-u", "1"]).unwrap()).is_ok());
+/// assert!(util::check_key_types(&left, &util::fields_to_idx(vec!["3", "1"]).unwrap()).is_err());
+/// assert!(util::check_key_types(&left, &util::fields_to_idx(vec!["3-u"]).unwrap()).is_err());
+/// ```
+pub fn check_key_types(left: &[(usize, isize, DataType)], 
+                       right: &[(usize, isize, DataType)]) -> Result<(), clap::Error> {
+    if left.len() != right.len() {
+        return Err(clap::Error {message: format!("Error: FIELDS1 contains {} key fields, but FIELDS2 contains {}!", 
+                                                 left.len(), right.len()),
+                                kind: clap::ErrorKind::ArgumentConflict,
+                                info: None});
+    }
+    let by_pos = |key_idx: &[(usize, isize, DataType)]| {
+        let mut dts: Vec<(isize, DataType)> = key_idx.iter().map(|&(_, pos, ref dt)| (pos, dt.clone())).collect();
+        dts.sort_by_key(|&(pos, _)| pos);
+        dts
+    };
+    let name = |dt: &DataType| match *dt {
+        DataType::I => "i64",
+        DataType::U => "u64",
+        DataType::S => "string",
+    };
+    for ((pos, l), (_, r)) in by_pos(left).into_iter().zip(by_pos(right)) {
+        if l != r {
+            return Err(clap::Error {message: format!("Error: the key field number {} is '{}' in FIELDS1, but '{}' in FIELDS2!", 
+                                                     pos + 1, name(&l), name(&r)),
+                                    kind: clap::ErrorKind::ArgumentConflict,
+                                    info: None});
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the column names to the field indices in base1 by looking them up in the header.
+///
+/// The resulting vector can be passed to `fields_to_idx()`. The error is returned if any of the
+/// names is not found in the header.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let header = "name;id;amount";
+/// let fields = util::header_fields(header, ";", vec!["id", "name"]).unwrap();
+/// assert_eq!(vec!["2", "1"], fields);
+/// ```
+pub fn header_fields<S>(header: &str, field_sep: &S, names: Vec<&str>) -> Result<Vec<String>, clap::Error> 
+    where S: ?Sized + Separator,
+{
+    let mut columns = Fields::new(header, field_sep);
+    let columns: Vec<Cow<str>> = iter::from_fn(|| columns.next_unescaped()).collect();
+    let mut fields = Vec::with_capacity(names.len());
+    for name in names {
+        match columns.iter().position(|c| *c == name) {
+            Some(i) => fields.push((i + 1).to_string()),
+            None => return Err(clap::Error {message: format!("Error: the column '{}' was not found in the header!", name),
+                                            kind: clap::ErrorKind::ValueValidation,
+                                            info: None}),
+        }
+    }
+    Ok(fields)
+}
+
 /// Extracts a key from the record.
 ///
 /// # Safety
@@ -153,36 +880,39 @@ pub fn fields_to_idx(f: Vec<&str>) -> Result<Vec<(usize, isize, DataType)>, clap
 ///     assert_eq!(vec![VarData::I(1), 
 ///                     VarData::S("a".to_owned())], key);
 /// }
-pub unsafe fn extract_key(record: &str, 
-                   field_sep: &str,
-                   key_idx: &[(usize, isize, DataType)]) -> Vec<VarData> { 
+pub unsafe fn extract_key<S>(record: &str, 
+                   field_sep: &S,
+                   key_idx: &[(usize, isize, DataType)]) -> Vec<VarData> 
+    where S: ?Sized + Separator,
+{ 
     let keys_len = key_idx.len();
     let mut keys: Vec<VarData> = Vec::with_capacity(keys_len);
     let mut actual_len = 0usize;
     {
         let ptr = keys.as_mut_ptr();
         let key_idx_it = key_idx.iter();
-        let key_fields_it = record.split(field_sep)
+        let mut fields = Fields::new(record, field_sep);
+        let key_fields_it = iter::from_fn(|| fields.next_unescaped())
             .enumerate()
             // join on enumerated value and key_idx
             .merge_join_inner_by(key_idx_it, |l, r| Ord::cmp(&l.0, &r.0));
         for ((_, k), &(_, i, ref dt)) in key_fields_it {
-            let data = match dt {
-                &DataType::I => {
+            let data = match *dt {
+                DataType::I => {
                     VarData::I(k.parse::<i64>()
-                                .expect(&format!("Error while parsing the \
-                                                  key number {}: the value '{}' \
-                                                  cannot be converted into 'i64'", k,
-                                                  i + 1)))
+                                .unwrap_or_else(|_| panic!("Error while parsing the \
+                                                            key number {}: the value '{}' \
+                                                            cannot be converted into 'i64'",
+                                                            i + 1, k)))
                 }
-                &DataType::U => {
+                DataType::U => {
                     VarData::U(k.parse::<u64>()
-                                .expect(&format!("Error while parsing the \
-                                                  key number {}: the value '{}' \
-                                                  cannot be converted into 'u64'", k,
-                                                  i + 1)))
+                                .unwrap_or_else(|_| panic!("Error while parsing the \
+                                                            key number {}: the value '{}' \
+                                                            cannot be converted into 'u64'",
+                                                            i + 1, k)))
                 }
-                &DataType::S => VarData::S(k.to_owned()),
+                DataType::S => VarData::S(k.into_owned()),
             };
 
             ptr::write(ptr.offset(i), data);
@@ -220,16 +950,325 @@ pub unsafe fn extract_key(record: &str,
 ///                      VarData::S("a".to_owned())], 
 ///                 Cow::Borrowed("a;b;1")), key_val);
 /// }
-pub unsafe fn extract_key_value<'a, C>(record: C, 
-                                field_sep: &str,
+pub unsafe fn extract_key_value<'a, C, S>(record: C, 
+                                field_sep: &S,
                                 key_idx: &[(usize, isize, DataType)]) -> (Vec<VarData>, Cow<'a, str>) 
     where C: Into<Cow<'a, str>>,
+          S: ?Sized + Separator,
 { 
     let record = record.into();
     let key = extract_key(&record, field_sep, key_idx);
     (key, record)
 }
 
+/// Extracts a key from the record, returning an error instead of panicking if the record does
+/// not conform to the `key_idx`.
+///
+/// Unlike [`extract_key()`](fn.extract_key.html), this function is safe, but slightly slower,
+/// since the key is assembled in a separate buffer.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType, VarData, KeyError};
+///
+/// let key_idx = [(0, 1, DataType::S), (2, 0, DataType::I)];
+/// assert_eq!(Ok(vec![VarData::I(1), VarData::S("a".to_owned())]), 
+///            util::try_extract_key("a;b;1", ";", &key_idx));
+/// assert_eq!(Err(KeyError::ParseInt { idx: 2, value: "c".to_owned() }), 
+///            util::try_extract_key("a;b;c", ";", &key_idx));
+/// assert_eq!(Err(KeyError::IndexOutOfRange { idx: 2, nfields: 2 }), 
+///            util::try_extract_key("a;b", ";", &key_idx));
+/// ```
+pub fn try_extract_key<S>(record: &str, 
+                       field_sep: &S,
+                       key_idx: &[(usize, isize, DataType)]) -> Result<Vec<VarData>, KeyError> 
+    where S: ?Sized + Separator,
+{ 
+    try_extract_key_with(record, field_sep, key_idx, NumberCheck::Parse)
+}
+
+/// Extracts a key from the record, the same as [`try_extract_key()`](fn.try_extract_key.html),
+/// except a numeric key field, which cannot be parsed, is taken as zero.
+///
+/// Only the key is affected, not the record. Beware, all such records match each other and any
+/// record with the zero key.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType, VarData, KeyError};
+///
+/// let key_idx = [(0, 0, DataType::S), (1, 1, DataType::U)];
+/// assert_eq!(Ok(vec![VarData::S("a".to_owned()), VarData::U(0)]), 
+///            util::try_extract_key_or_zero("a;n/a", ";", &key_idx));
+/// assert_eq!(Err(KeyError::IndexOutOfRange { idx: 1, nfields: 1 }), 
+///            util::try_extract_key_or_zero("a", ";", &key_idx));
+/// ```
+pub fn try_extract_key_or_zero<S>(record: &str, 
+                                  field_sep: &S,
+                                  key_idx: &[(usize, isize, DataType)]) -> Result<Vec<VarData>, KeyError> 
+    where S: ?Sized + Separator,
+{ 
+    try_extract_key_with(record, field_sep, key_idx, NumberCheck::Zero)
+}
+
+/// Extracts a key from the record, the same as [`try_extract_key()`](fn.try_extract_key.html),
+/// except a numeric key field must also be written in the canonical form of the parsed number,
+/// i.e. without a sign other than `-` of a negative number and without the leading zeros.
+///
+/// Such key fields are parsed fine otherwise, but they are silently coerced, e.g. `007` matches
+/// `7`, which it may not be expected to.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType, VarData, KeyError};
+///
+/// let key_idx = [(0, 0, DataType::U)];
+/// assert_eq!(Ok(vec![VarData::U(7)]), util::try_extract_key_strict("7;a", ";", &key_idx));
+/// assert_eq!(Err(KeyError::NotCanonical { idx: 0, value: "+7".to_owned() }), 
+///            util::try_extract_key_strict("+7;a", ";", &key_idx));
+/// ```
+pub fn try_extract_key_strict<S>(record: &str, 
+                                 field_sep: &S,
+                                 key_idx: &[(usize, isize, DataType)]) -> Result<Vec<VarData>, KeyError> 
+    where S: ?Sized + Separator,
+{ 
+    try_extract_key_with(record, field_sep, key_idx, NumberCheck::Canonical)
+}
+
+/// How the numeric key fields are checked by `try_extract_key_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberCheck {
+    /// the field must be parsed
+    Parse,
+    /// an unparsable field is taken as zero
+    Zero,
+    /// the field must be parsed and written in the canonical form
+    Canonical,
+}
+
+/// Extracts a key from the record, checking the numeric key fields according to `check`.
+fn try_extract_key_with<S>(record: &str, 
+                           field_sep: &S,
+                           key_idx: &[(usize, isize, DataType)],
+                           check: NumberCheck) -> Result<Vec<VarData>, KeyError> 
+    where S: ?Sized + Separator,
+{ 
+    let mut fields = Fields::new(record, field_sep);
+    let fields: Vec<Cow<str>> = iter::from_fn(|| fields.next_unescaped()).collect();
+    let mut keys: Vec<(isize, VarData)> = Vec::with_capacity(key_idx.len());
+    for &(idx, pos, ref dt) in key_idx {
+        let k: &str = match fields.get(idx) {
+            Some(k) => k,
+            None => return Err(KeyError::IndexOutOfRange { idx, nfields: fields.len() }),
+        };
+        let zero = check == NumberCheck::Zero;
+        let data = match *dt {
+            DataType::I => match k.parse::<i64>() {
+                Ok(n) => VarData::I(n),
+                Err(_) if zero => VarData::I(0),
+                Err(_) => return Err(KeyError::ParseInt { idx, value: k.to_owned() }),
+            },
+            DataType::U => match k.parse::<u64>() {
+                Ok(n) => VarData::U(n),
+                Err(_) if zero => VarData::U(0),
+                Err(_) => return Err(KeyError::ParseUint { idx, value: k.to_owned() }),
+            },
+            DataType::S => VarData::S(k.to_owned()),
+        };
+        if check == NumberCheck::Canonical && *dt != DataType::S && data.to_string() != k {
+            return Err(KeyError::NotCanonical { idx, value: k.to_owned() });
+        }
+        keys.push((pos, data));
+    }
+    keys.sort_by_key(|&(pos, _)| pos);
+    Ok(keys.into_iter().map(|(_, data)| data).collect())
+}
+
+/// Extracts a key from the record, borrowing the string components from it.
+///
+/// This is the same as [`try_extract_key()`](fn.try_extract_key.html), but no `String` is
+/// allocated, which pays off if the key is only compared transiently, e.g. in a merge join step.
+/// A key stored beyond the lifetime of the record, e.g. in the `HashMap` of a hash join, must be
+/// owned: use `try_extract_key()` or [`KeyRef::into_owned()`](enum.KeyRef.html#method.into_owned).
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use joinkit::util::{self, DataType, KeyRef};
+///
+/// let key_idx = [(0, 1, DataType::S), (2, 0, DataType::I)];
+/// let key = util::try_extract_key_ref("a;b;1", ";", &key_idx).unwrap();
+/// assert_eq!(vec![KeyRef::I(1), KeyRef::S(Cow::Borrowed("a"))], key);
+/// ```
+pub fn try_extract_key_ref<'a, S>(record: &'a str, 
+                                  field_sep: &'a S,
+                                  key_idx: &[(usize, isize, DataType)]) -> Result<Vec<KeyRef<'a>>, KeyError> 
+    where S: ?Sized + Separator,
+{ 
+    let mut fields = Fields::new(record, field_sep);
+    let mut fields: Vec<Option<Cow<str>>> = iter::from_fn(|| fields.next_unescaped()).map(Some).collect();
+    let nfields = fields.len();
+    let mut keys: Vec<(isize, KeyRef)> = Vec::with_capacity(key_idx.len());
+    for &(idx, pos, ref dt) in key_idx {
+        // the key fields are unique, so each is taken at most once
+        let k = match fields.get_mut(idx).and_then(Option::take) {
+            Some(k) => k,
+            None => return Err(KeyError::IndexOutOfRange { idx, nfields }),
+        };
+        let data = match *dt {
+            DataType::I => match k.parse::<i64>() {
+                Ok(n) => KeyRef::I(n),
+                Err(_) => return Err(KeyError::ParseInt { idx, value: k.into_owned() }),
+            },
+            DataType::U => match k.parse::<u64>() {
+                Ok(n) => KeyRef::U(n),
+                Err(_) => return Err(KeyError::ParseUint { idx, value: k.into_owned() }),
+            },
+            DataType::S => KeyRef::S(k),
+        };
+        keys.push((pos, data));
+    }
+    keys.sort_by_key(|&(pos, _)| pos);
+    Ok(keys.into_iter().map(|(_, data)| data).collect())
+}
+
+/// Extracts a key from the record and returns a tuple of the key and the record, or an error if
+/// the record does not conform to the `key_idx`.
+///
+/// See [`try_extract_key()`](fn.try_extract_key.html) for the details.
+///
+/// # Example
+/// ```
+/// use std::borrow::Cow;
+/// use joinkit::util::{self, DataType, VarData};
+///
+/// let key_idx = [(2, 0, DataType::U)];
+/// assert_eq!(Ok((vec![VarData::U(1)], Cow::Borrowed("a;b;1"))), 
+///            util::try_extract_key_value("a;b;1", ";", &key_idx));
+/// ```
+pub fn try_extract_key_value<'a, C, S>(record: C, 
+                                    field_sep: &S,
+                                    key_idx: &[(usize, isize, DataType)]) 
+                                    -> Result<(Vec<VarData>, Cow<'a, str>), KeyError> 
+    where C: Into<Cow<'a, str>>,
+          S: ?Sized + Separator,
+{ 
+    let record = record.into();
+    let key = try_extract_key(&record, field_sep, key_idx)?;
+    Ok((key, record))
+}
+
+/// Parses the key of the record and returns a tuple of the key and the borrowed record, or an
+/// error if the record does not conform to the `key_idx`.
+///
+/// Along with [`varvec_cmp()`](fn.varvec_cmp.html), it lets the library users merge join their
+/// own records on the numeric-aware keys, the same as the command-line tools. See
+/// [`try_extract_key()`](fn.try_extract_key.html) for the details.
+///
+/// # Example
+/// ```
+/// use joinkit::Joinkit;
+/// use joinkit::util::{self, DataType};
+///
+/// // the numeric keys are sorted numerically, not lexicographically
+/// let key_idx = util::fields_to_idx(vec!["1-u"]).unwrap();
+/// let l = vec!["2;a", "10;b"].into_iter().map(|r| util::parse_key(r, ";", &key_idx).unwrap());
+/// let r = vec!["2;x", "9;y", "10;z"].into_iter().map(|r| util::parse_key(r, ";", &key_idx).unwrap());
+/// let joined: Vec<_> = l.merge_join_inner_by(r, util::varvec_cmp)
+///     .map(|((_, lr), (_, rr))| (lr, rr))
+///     .collect();
+///
+/// assert_eq!(joined, vec![("2;a", "2;x"), ("10;b", "10;z")]);
+/// ```
+pub fn parse_key<'a, S>(record: &'a str, 
+                        field_sep: &S, 
+                        key_idx: &[(usize, isize, DataType)]) 
+                        -> Result<(Vec<VarData>, &'a str), KeyError> 
+    where S: ?Sized + Separator,
+{
+    let key = try_extract_key(record, field_sep, key_idx)?;
+    Ok((key, record))
+}
+
+/// Compares the keys of the two `(key, record)` tuples, e.g. as returned by
+/// [`parse_key()`](fn.parse_key.html), in the order of the command-line merge join: the key
+/// fields one by one, the numbers numerically and the strings lexicographically.
+///
+/// It is meant to be passed to the `*_by` merge joins directly, see `parse_key()` for an example.
+pub fn varvec_cmp<L, R>(left: &(Vec<VarData>, L), right: &(Vec<VarData>, R)) -> Ordering {
+    Ord::cmp(&left.0, &right.0)
+}
+
+/// Extracts a single `i64` key from the field `idx` (in base0) of the record.
+///
+/// This is a fast path of [`try_extract_key()`](fn.try_extract_key.html) for the key composed of
+/// a single numeric field, which needs neither `Vec` nor `VarData`.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, KeyError};
+///
+/// assert_eq!(Ok(-1), util::try_extract_i64("a;-1", ";", 1));
+/// assert_eq!(Err(KeyError::IndexOutOfRange { idx: 2, nfields: 2 }), 
+///            util::try_extract_i64("a;-1", ";", 2));
+/// ```
+pub fn try_extract_i64<S>(record: &str, field_sep: &S, idx: usize) -> Result<i64, KeyError> 
+    where S: ?Sized + Separator,
+{
+    let k = nth_field(record, field_sep, idx)?;
+    k.parse::<i64>().map_err(|_| KeyError::ParseInt { idx, value: k.to_owned() })
+}
+
+/// Extracts a single `u64` key from the field `idx` (in base0) of the record.
+///
+/// See [`try_extract_i64()`](fn.try_extract_i64.html) for the details.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, KeyError};
+///
+/// assert_eq!(Ok(1), util::try_extract_u64("a;1", ";", 1));
+/// assert_eq!(Err(KeyError::ParseUint { idx: 0, value: "a".to_owned() }), 
+///            util::try_extract_u64("a;1", ";", 0));
+/// ```
+pub fn try_extract_u64<S>(record: &str, field_sep: &S, idx: usize) -> Result<u64, KeyError> 
+    where S: ?Sized + Separator,
+{
+    let k = nth_field(record, field_sep, idx)?;
+    k.parse::<u64>().map_err(|_| KeyError::ParseUint { idx, value: k.to_owned() })
+}
+
+/// Returns `true` if any of the key fields of the record is empty.
+///
+/// The fields missing from the record are not considered empty.
+///
+/// # Example
+/// ```
+/// use joinkit::util::{self, DataType};
+///
+/// let key_idx = [(0, 0, DataType::S), (2, 1, DataType::U)];
+/// assert!(!util::has_empty_key("a;;1", ";", &key_idx));
+/// assert!(util::has_empty_key("a;b;", ";", &key_idx));
+/// assert!(!util::has_empty_key("a", ";", &key_idx));
+/// ```
+pub fn has_empty_key<S>(record: &str, field_sep: &S, key_idx: &[(usize, isize, DataType)]) -> bool 
+    where S: ?Sized + Separator,
+{
+    let fields: Vec<&str> = Fields::new(record, field_sep).collect();
+    key_idx.iter().any(|&(idx, _, _)| fields.get(idx).is_some_and(|f| f.is_empty()))
+}
+
+/// Returns the field `idx` (in base0) of the record.
+fn nth_field<'a, S>(record: &'a str, field_sep: &'a S, idx: usize) -> Result<&'a str, KeyError> 
+    where S: ?Sized + Separator,
+{
+    let mut fields = Fields::new(record, field_sep);
+    match fields.nth(idx) {
+        Some(k) => Ok(k),
+        None => Err(KeyError::IndexOutOfRange { idx, nfields: num_fields(record, field_sep) }),
+    }
+}
+
 /// Returns a number of fields in the record.
 ///
 /// #Example
@@ -241,9 +1280,164 @@ pub unsafe fn extract_key_value<'a, C>(record: C,
 /// let n = util::num_fields(rec, field_sep);
 ///
 /// assert_eq!(4, n);
-pub fn num_fields(record: &str, 
-                  field_sep: &str,) -> usize {
-    record.split(field_sep).count()
+pub fn num_fields<S>(record: &str, 
+                     field_sep: &S,) -> usize 
+    where S: ?Sized + Separator,
+{
+    Fields::new(record, field_sep).count()
+}
+
+/// See [`split_records()`](fn.split_records.html) for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SplitRecords<B> {
+    stream: B,
+    rec_sep: u8,
+    quote: Option<u8>,
+}
+
+/// Returns an iterator over the records of the `stream` separated by `rec_sep`, like
+/// `BufRead::split`, but the record separators enclosed in the `quote` bytes, if any, do not
+/// split the records, e.g. the newlines inside the quoted fields of a CSV file.
+///
+/// A quote inside the quotes must be escaped by doubling it, as in CSV. An unterminated quote
+/// spans the rest of the stream. The records are yielded without the record separator.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let csv = &b"1,\"a\nb\"\n2,c\n"[..];
+/// let records: Vec<_> = util::split_records(csv, b'\n', Some(b'"')).map(Result::unwrap).collect();
+/// assert_eq!(records, vec![b"1,\"a\nb\"".to_vec(), b"2,c".to_vec()]);
+///
+/// let records: Vec<_> = util::split_records(csv, b'\n', None).map(Result::unwrap).collect();
+/// assert_eq!(records.len(), 3);
+/// ```
+pub fn split_records<B: BufRead>(stream: B, rec_sep: u8, quote: Option<u8>) -> SplitRecords<B> {
+    SplitRecords {
+        stream,
+        rec_sep,
+        quote,
+    }
+}
+
+impl<B: BufRead> Iterator for SplitRecords<B> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = Vec::new();
+        // whether the end of the record read so far is inside the quotes
+        let mut quoted = false;
+        loop {
+            let start = buf.len();
+            match self.stream.read_until(self.rec_sep, &mut buf) {
+                Ok(0) => return if buf.is_empty() {None} else {Some(Ok(buf))},
+                Ok(_) => {},
+                Err(e) => return Some(Err(e)),
+            }
+            if let Some(q) = self.quote {
+                quoted ^= buf[start..].iter().filter(|&&b| b == q).count() % 2 == 1;
+            }
+            // otherwise, the record continues after the quoted separator or ends with the stream
+            if !quoted && buf.last() == Some(&self.rec_sep) {
+                buf.pop();
+                return Some(Ok(buf));
+            }
+        }
+    }
+}
+
+/// See [`skip_empty()`](fn.skip_empty.html) for the description and examples.
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct SkipEmpty<I: Iterator> {
+    iter: I,
+    keep_inner: bool,
+    /// empty records, which are yielded only if a non-empty record follows
+    pending: Vec<I::Item>,
+    /// the non-empty record following the pending empty records
+    next: Option<I::Item>,
+}
+
+/// Returns an iterator adaptor, which skips the empty records.
+///
+/// If `keep_inner` is `true`, only the empty records at the end of the input (e.g. blank lines
+/// following the last record) are skipped, while the empty records in between are preserved.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let records = vec!["a", "", "b", "", ""];
+/// let all: Vec<_> = util::skip_empty(records.clone().into_iter(), false).collect();
+/// assert_eq!(vec!["a", "b"], all);
+///
+/// let trailing: Vec<_> = util::skip_empty(records.into_iter(), true).collect();
+/// assert_eq!(vec!["a", "", "b"], trailing);
+/// ```
+pub fn skip_empty<I>(iter: I, keep_inner: bool) -> SkipEmpty<I>
+    where I: Iterator,
+          I::Item: AsRef<[u8]>,
+{
+    SkipEmpty {
+        iter,
+        keep_inner,
+        pending: Vec::new(),
+        next: None,
+    }
+}
+
+impl<I> Iterator for SkipEmpty<I>
+    where I: Iterator,
+          I::Item: AsRef<[u8]>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.pending.pop() {
+            return Some(e);
+        }
+        if let Some(r) = self.next.take() {
+            return Some(r);
+        }
+        for r in self.iter.by_ref() {
+            if !r.as_ref().is_empty() {
+                // the pending empty records are not trailing, so yield them first
+                return match self.pending.pop() {
+                    Some(e) => {
+                        self.next = Some(r);
+                        Some(e)
+                    },
+                    None => Some(r),
+                };
+            } else if self.keep_inner {
+                self.pending.push(r);
+            }
+        }
+        None
+    }
+}
+
+/// Return the number of fields padded in place of the missing left and the missing right record
+/// respectively, given the number of fields of the left and the right records, when joining in
+/// the `mode`.
+///
+/// Only the outer joins pad the missing records, the other modes write the records as they are.
+///
+/// ```
+/// use joinkit::util;
+/// use joinkit::JoinMode;
+///
+/// assert_eq!(util::output_arity(2, 3, JoinMode::LeftOuter), (0, 3));
+/// assert_eq!(util::output_arity(2, 3, JoinMode::FullOuter), (2, 3));
+/// assert_eq!(util::output_arity(2, 3, JoinMode::LeftExcl), (0, 0));
+/// ```
+pub fn output_arity(left_fields: usize, right_fields: usize, mode: JoinMode) -> (usize, usize) {
+    match mode {
+        JoinMode::Inner | JoinMode::LeftExcl | JoinMode::RightExcl => (0, 0),
+        JoinMode::LeftOuter => (0, right_fields),
+        JoinMode::RightOuter => (left_fields, 0),
+        JoinMode::FullOuter => (left_fields, right_fields),
+    }
 }
 
 /// Writes both, the left value and the right value into output stream. 
@@ -251,29 +1445,353 @@ pub fn num_fields(record: &str,
 /// The values are separated by the field separator and the record separator is appended at the
 /// end.
 pub fn write_both<W: Write>(stream: &mut BufWriter<W>, lv: &str, rv: &str, fs: &[u8], rs: &[u8]) {
-    stream.write(lv.as_bytes()).expect("Error: could not write into output stream!");
-    stream.write(fs).expect("Error: could not write into output stream!");
-    stream.write(rv.as_bytes()).expect("Error: could not write into output stream!");
-    stream.write(rs).expect("Error: could not write into output stream!");
+    write_both_bytes(stream, lv.as_bytes(), rv.as_bytes(), fs, rs)
 }
 
 /// Writes only the left value with padded field separators in place of missing right value. 
 pub fn write_left<W: Write>(stream: &mut BufWriter<W>, lv: &str, r_len: usize, fs: &[u8], rs: &[u8]) {
-    stream.write(lv.as_bytes()).expect("Error: could not write into output stream!");
-    // pad field separators for empty fields
-    for _ in 0..r_len {
-        stream.write(fs).expect("Error: could not write into output stream!");
-    }
-    stream.write(rs).expect("Error: could not write into output stream!");
+    write_left_bytes(stream, lv.as_bytes(), r_len, fs, rs)
 }
 
 /// Writes only the right value with padded field separators in place of missing left value. 
 pub fn write_right<W: Write>(stream: &mut BufWriter<W>, rv: &str, l_len: usize, fs: &[u8], rs: &[u8]) {
-    // pad field separators for empty fields
-    for _ in 0..l_len {
-        stream.write(fs).expect("Error: could not write into output stream!");
+    write_right_bytes(stream, rv.as_bytes(), l_len, fs, rs)
+}
+
+/// Writes the `parts` of a record separated by the field separator `fs`, followed by the record
+/// separator `rs`, into output stream.
+///
+/// The record is assembled in a scratch buffer, which is reused by the subsequent calls, and
+/// written in a single call, instead of one call per part and separator.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let mut out = Vec::new();
+/// util::write_record(&mut out, &[b"1;A", b"", b"1;X"], b";", b"\n");
+/// assert_eq!(out, b"1;A;;1;X\n");
+/// ```
+pub fn write_record<W: Write>(stream: &mut W, parts: &[&[u8]], fs: &[u8], rs: &[u8]) {
+    write_assembled(stream, |buf| {
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                buf.extend_from_slice(fs);
+            }
+            buf.extend_from_slice(part);
+        }
+        buf.extend_from_slice(rs);
+    })
+}
+
+thread_local! {
+    /// the scratch buffer the output records are assembled in
+    static RECORD_BUF: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Assembles a record by the `assemble` closure in the reused scratch buffer and writes it into
+/// output stream in a single call.
+fn write_assembled<W, F>(stream: &mut W, assemble: F) 
+    where W: Write,
+          F: FnOnce(&mut Vec<u8>),
+{
+    RECORD_BUF.with(|buf| {
+        let mut buf = buf.borrow_mut();
+        buf.clear();
+        assemble(&mut buf);
+        stream.write_all(&buf).expect("Error: could not write into output stream!");
+    })
+}
+
+/// Same as [`write_both()`](fn.write_both.html), but the values are raw bytes, which need not be
+/// valid UTF-8.
+///
+/// # Example
+/// ```
+/// use joinkit::util;
+///
+/// let mut out = Vec::new();
+/// util::write_both_bytes(&mut out, b"1;\xff", b"1;\xfe", b";", b"\n");
+/// assert_eq!(out, b"1;\xff;1;\xfe\n");
+/// ```
+pub fn write_both_bytes<W: Write>(stream: &mut W, lv: &[u8], rv: &[u8], fs: &[u8], rs: &[u8]) {
+    write_record(stream, &[lv, rv], fs, rs)
+}
+
+/// Same as [`write_left()`](fn.write_left.html), but the value is raw bytes, which need not be
+/// valid UTF-8.
+pub fn write_left_bytes<W: Write>(stream: &mut W, lv: &[u8], r_len: usize, fs: &[u8], rs: &[u8]) {
+    write_assembled(stream, |buf| {
+        buf.extend_from_slice(lv);
+        // pad field separators for empty fields
+        for _ in 0..r_len {
+            buf.extend_from_slice(fs);
+        }
+        buf.extend_from_slice(rs);
+    })
+}
+
+/// Same as [`write_right()`](fn.write_right.html), but the value is raw bytes, which need not be
+/// valid UTF-8.
+pub fn write_right_bytes<W: Write>(stream: &mut W, rv: &[u8], l_len: usize, fs: &[u8], rs: &[u8]) {
+    write_assembled(stream, |buf| {
+        // pad field separators for empty fields
+        for _ in 0..l_len {
+            buf.extend_from_slice(fs);
+        }
+        buf.extend_from_slice(rv);
+        buf.extend_from_slice(rs);
+    })
+}
+
+/// Writes a single record coalesced from the left value and the right value into output stream.
+///
+/// The fields are paired by their position: the left field is taken, unless it is empty or
+/// missing, in which case the right field is taken instead. `lfs` and `rfs` are the field
+/// separators of the left and the right value respectively; the fields are written separated by
+/// `fs` and the record separator is appended at the end. The quoted fields are written with their
+/// quotes.
+pub fn write_coalesced<W, S>(stream: &mut BufWriter<W>, 
+                             lv: &str, 
+                             rv: &str, 
+                             lfs: &S, 
+                             rfs: &S, 
+                             fs: &[u8], 
+                             rs: &[u8]) 
+    where W: Write,
+          S: ?Sized + Separator,
+{
+    let mut left = Fields::new(lv, lfs);
+    let mut right = Fields::new(rv, rfs);
+    let mut first = true;
+    loop {
+        let field = match (left.next_field(), right.next_field()) {
+            (Some((l, lraw, _)), Some((_, rraw, _))) => if l.is_empty() {rraw} else {lraw},
+            (Some((_, lraw, _)), None) => lraw,
+            (None, Some((_, rraw, _))) => rraw,
+            (None, None) => break,
+        };
+        if !first {
+            stream.write_all(fs).expect("Error: could not write into output stream!");
+        }
+        stream.write_all(field.as_bytes()).expect("Error: could not write into output stream!");
+        first = false;
+    }
+    stream.write_all(rs).expect("Error: could not write into output stream!");
+}
+
+/// The options of [`join_records()`](fn.join_records.html), the same as the arguments of the
+/// binaries.
+#[derive(Debug, Clone)]
+pub struct JoinOptions<'a> {
+    /// the join strategy
+    pub strategy: Strategy,
+    /// the join mode
+    pub mode: JoinMode,
+    /// the key fields of the left records
+    pub key_idx_left: KeySpec,
+    /// the key fields of the right records
+    pub key_idx_right: KeySpec,
+    /// the field separator of the input records
+    pub field_sep: &'a str,
+    /// the field separator of the output records
+    pub out_field_sep: &'a str,
+}
+
+impl<'a> Default for JoinOptions<'a> {
+    /// The defaults of the binaries: the hash inner join on the first field of the
+    /// comma-separated records.
+    fn default() -> Self {
+        JoinOptions {
+            strategy: Strategy::Hash,
+            mode: JoinMode::Inner,
+            key_idx_left: KeySpec(vec![(0, 0, DataType::S)]),
+            key_idx_right: KeySpec(vec![(0, 0, DataType::S)]),
+            field_sep: ",",
+            out_field_sep: ",",
+        }
     }
-    stream.write(rv.as_bytes()).expect("Error: could not write into output stream!");
-    stream.write(rs).expect("Error: could not write into output stream!");
 }
 
+/// Join the `left` and the `right` records in memory, exactly as the binaries join the records of
+/// two files, and return the output records.
+///
+/// This is useful to test or embed the join without any files. With the merge strategy, the
+/// records must be sorted on the key. Unlike the binaries, the records are not filtered (e.g. the
+/// empty ones are joined) and a key, which cannot be extracted, is returned as an error.
+///
+/// ```
+/// use joinkit::util::{self, JoinOptions};
+/// use joinkit::JoinMode;
+///
+/// let l = vec!["1,a".to_string(), "2,b".to_string()];
+/// let r = vec!["2,x".to_string(), "3,y".to_string()];
+/// let opts = JoinOptions { mode: JoinMode::FullOuter, ..JoinOptions::default() };
+/// let mut out = util::join_records(l, r, &opts).unwrap();
+/// // the unmatched right records of the hash join are not ordered
+/// out.sort();
+///
+/// assert_eq!(out, vec![",,3,y", "1,a,,", "2,b,2,x"]);
+/// ```
+pub fn join_records(left: Vec<String>, 
+                    right: Vec<String>, 
+                    opts: &JoinOptions) -> Result<Vec<String>, KeyError> {
+    let field_sep = opts.field_sep;
+    let with_key = |records: Vec<String>, key_idx: &[(usize, isize, DataType)]| {
+        records.into_iter()
+            .map(|r| try_extract_key(&r, field_sep, key_idx).map(|k| (k, r)))
+            .collect::<Result<Vec<(Vec<VarData>, String)>, KeyError>>()
+    };
+    let left = with_key(left, &opts.key_idx_left)?;
+    let right = with_key(right, &opts.key_idx_right)?;
+
+    // the missing records of the outer joins are padded by the number of fields of the first one
+    let (l_len, r_len) = output_arity(left.first().map_or(0, |r| num_fields(&r.1, field_sep)),
+                                      right.first().map_or(0, |r| num_fields(&r.1, field_sep)),
+                                      opts.mode);
+    let fs = opts.out_field_sep.as_bytes();
+    let mut out: Vec<String> = Vec::new();
+    {
+        let mut write = |e: EitherOrBoth<&str, &str>| {
+            let mut rec: Vec<u8> = Vec::new();
+            match e {
+                Both(lv, rv) => write_both_bytes(&mut rec, lv.as_bytes(), rv.as_bytes(), fs, b""),
+                Left(lv) => write_left_bytes(&mut rec, lv.as_bytes(), r_len, fs, b""),
+                Right(rv) => write_right_bytes(&mut rec, rv.as_bytes(), l_len, fs, b""),
+            }
+            // the records and the separators are all valid UTF-8
+            out.push(String::from_utf8(rec).expect("the joined record is not valid UTF-8"));
+        };
+        match opts.strategy {
+            Strategy::Hash => join_records_hash(left, right, opts.mode, &mut write),
+            Strategy::Merge => join_records_merge(left, right, opts.mode, &mut write),
+        }
+    }
+    Ok(out)
+}
+
+/// Hash joins the records with the extracted keys for [`join_records()`](fn.join_records.html).
+fn join_records_hash<F>(left: Vec<(Vec<VarData>, String)>, 
+                        right: Vec<(Vec<VarData>, String)>, 
+                        mode: JoinMode, 
+                        write: &mut F)
+    where F: FnMut(EitherOrBoth<&str, &str>),
+{
+    let left = left.into_iter();
+    match mode {
+        JoinMode::Inner => for (lv, rvv) in left.hash_join_inner(right) {
+            for rv in &rvv {
+                write(Both(&lv, rv));
+            }
+        },
+        JoinMode::LeftExcl => for lv in left.hash_join_left_excl(right) {
+            write(Left(&lv));
+        },
+        JoinMode::LeftOuter => for e in left.hash_join_left_outer(right) {
+            match e {
+                Left(lv) => write(Left(&lv)),
+                Both(lv, rvv) => for rv in &rvv {
+                    write(Both(&lv, rv));
+                },
+                _ => unreachable!(),
+            }
+        },
+        JoinMode::RightExcl => for rvv in left.hash_join_right_excl(right) {
+            for rv in &rvv {
+                write(Right(rv));
+            }
+        },
+        JoinMode::RightOuter => for e in left.hash_join_right_outer_flat(right) {
+            match e {
+                Right(rv) => write(Right(&rv)),
+                Both(lv, rv) => write(Both(&lv, &rv)),
+                _ => unreachable!(),
+            }
+        },
+        JoinMode::FullOuter => for e in left.hash_join_full_outer(right) {
+            match e {
+                Left(lv) => write(Left(&lv)),
+                Right(rvv) => for rv in &rvv {
+                    write(Right(rv));
+                },
+                Both(lv, rvv) => for rv in &rvv {
+                    write(Both(&lv, rv));
+                },
+            }
+        },
+    }
+}
+
+/// Merge joins the records with the extracted keys for [`join_records()`](fn.join_records.html).
+fn join_records_merge<F>(left: Vec<(Vec<VarData>, String)>, 
+                         right: Vec<(Vec<VarData>, String)>, 
+                         mode: JoinMode, 
+                         write: &mut F)
+    where F: FnMut(EitherOrBoth<&str, &str>),
+{
+    // the consecutive records of equal keys are grouped, as in the binaries, but borrowed in place
+    type Run<'a> = &'a [(Vec<VarData>, String)];
+    let (left, right) = (left.chunk_by(|a, b| a.0 == b.0), right.chunk_by(|a, b| a.0 == b.0));
+    let cmp = |l: &Run, r: &Run| Ord::cmp(&l[0].0, &r[0].0);
+    match mode {
+        JoinMode::Inner => for (lvv, rvv) in left.merge_join_inner_by(right, cmp) {
+            for (_, lv) in lvv {
+                for (_, rv) in rvv {
+                    write(Both(lv, rv));
+                }
+            }
+        },
+        JoinMode::LeftExcl => for lvv in left.merge_join_left_excl_by(right, cmp) {
+            for (_, lv) in lvv {
+                write(Left(lv));
+            }
+        },
+        JoinMode::LeftOuter => for e in left.merge_join_left_outer_by(right, cmp) {
+            match e {
+                Left(lvv) => for (_, lv) in lvv {
+                    write(Left(lv));
+                },
+                Both(lvv, rvv) => for (_, lv) in lvv {
+                    for (_, rv) in rvv {
+                        write(Both(lv, rv));
+                    }
+                },
+                _ => unreachable!(),
+            }
+        },
+        // the right modes are the left ones with inverted input
+        JoinMode::RightExcl => for rvv in right.merge_join_left_excl_by(left, cmp) {
+            for (_, rv) in rvv {
+                write(Right(rv));
+            }
+        },
+        JoinMode::RightOuter => for e in right.merge_join_left_outer_by(left, cmp) {
+            match e {
+                Left(rvv) => for (_, rv) in rvv {
+                    write(Right(rv));
+                },
+                Both(rvv, lvv) => for (_, rv) in rvv {
+                    for (_, lv) in lvv {
+                        write(Both(lv, rv));
+                    }
+                },
+                _ => unreachable!(),
+            }
+        },
+        JoinMode::FullOuter => for e in left.merge_join_full_outer_by(right, cmp) {
+            match e {
+                Left(lvv) => for (_, lv) in lvv {
+                    write(Left(lv));
+                },
+                Right(rvv) => for (_, rv) in rvv {
+                    write(Right(rv));
+                },
+                Both(lvv, rvv) => for (_, lv) in lvv {
+                    for (_, rv) in rvv {
+                        write(Both(lv, rv));
+                    }
+                },
+            }
+        },
+    }
+}
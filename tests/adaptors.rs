@@ -0,0 +1,89 @@
+extern crate joinkit;
+
+use joinkit::{Joinkit, OutOfOrder};
+
+#[test]
+fn check_sorted_in_order() {
+    let it = (0..5).check_sorted_by(Ord::cmp);
+    assert_eq!(it.size_hint(), (5, Some(5)));
+    assert_eq!(it.collect::<Result<Vec<_>, _>>(), Ok(vec![0, 1, 2, 3, 4]));
+}
+
+#[test]
+fn check_sorted_out_of_order() {
+    let it = vec![3, 1, 2, 0].into_iter().check_sorted_by(Ord::cmp);
+    assert_eq!(it.collect::<Vec<_>>(), 
+               vec![Ok(3), Err(OutOfOrder { index: 1, item: 1 }), Ok(2), 
+                    Err(OutOfOrder { index: 3, item: 0 })]);
+
+    let e = (0..3).rev().check_sorted_by(Ord::cmp).find(|r| r.is_err()).unwrap().unwrap_err();
+    assert_eq!(e.to_string(), "the item at index 1 is ordered before its predecessor");
+}
+
+#[test]
+fn assert_sorted_in_order() {
+    let v: Vec<_> = vec![1, 1, 2].into_iter().assert_sorted_by(Ord::cmp).collect();
+    assert_eq!(v, vec![1, 1, 2]);
+    // the reversed order
+    let v: Vec<_> = vec![2, 1, 1].into_iter().assert_sorted_by(|a, b| Ord::cmp(b, a)).collect();
+    assert_eq!(v, vec![2, 1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "the item at index 2 is ordered before its predecessor")]
+fn assert_sorted_out_of_order() {
+    for _ in vec![1, 2, 0].into_iter().assert_sorted_by(Ord::cmp) {}
+}
+
+#[test]
+fn chain_sorted_into_merge_join() {
+    let shard1 = vec![(1, "a"), (3, "c"), (3, "d")].into_iter();
+    let shard2 = vec![(0, "z"), (3, "y")].into_iter();
+    let it = shard1.chain_sorted_by(shard2, |a, b| Ord::cmp(&a.0, &b.0));
+    assert_eq!(it.size_hint(), (5, Some(5)));
+    let r = vec![(3, "X"), (3, "Y"), (3, "Z")];
+    let rows: Vec<_> = it.merge_join_inner_by(r, |a, b| Ord::cmp(&a.0, &b.0))
+        .map(|(l, r)| (l.1, r.1))
+        .collect();
+    // the equal items of the left shard are yielded first
+    assert_eq!(rows, vec![("c", "X"), ("d", "Y"), ("y", "Z")]);
+
+    let v: Vec<u32> = Vec::new().into_iter().chain_sorted_by(vec![2, 1], Ord::cmp).collect();
+    assert_eq!(v, vec![2, 1]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn ungroup_joined() {
+    let l = vec![(0, 'a'), (1, 'b')].into_iter();
+    let r = vec![(1, 'x'), (2, 'y'), (1, 'z')].into_iter();
+    let rows: Vec<_> = l.hash_join_inner(r).ungroup().collect();
+    assert_eq!(rows, vec![('b', 'x'), ('b', 'z')]);
+
+    let mut it = vec![(0, vec![]), (1, vec!['a', 'b'])].into_iter().ungroup();
+    assert_eq!(it.size_hint(), (0, None));
+    assert_eq!(it.next(), Some((1, 'a')));
+    assert_eq!(it.size_hint(), (1, Some(1)));
+    assert_eq!(it.next(), Some((1, 'b')));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn group_joined_by_key_ungroup() {
+    let rows = vec![(1, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+    let mut it = rows.clone().into_iter().group_joined_by_key(|r| r.0);
+    assert_eq!(it.size_hint(), (1, Some(4)));
+    assert_eq!(it.next(), Some((1, vec![(1, 'a'), (1, 'b')])));
+    assert_eq!(it.next(), Some((2, vec![(2, 'c')])));
+    // the input out of order yields the key again
+    assert_eq!(it.next(), Some((1, vec![(1, 'd')])));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.size_hint(), (0, Some(0)));
+
+    let regrouped: Vec<_> = rows.into_iter()
+        .group_joined_by_key(|r| r.0)
+        .map(|(k, g)| (k, g.into_iter().map(|r| r.1).collect::<Vec<_>>()))
+        .ungroup()
+        .collect();
+    assert_eq!(regrouped, vec![(1, 'a'), (1, 'b'), (2, 'c'), (1, 'd')]);
+}
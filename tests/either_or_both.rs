@@ -0,0 +1,49 @@
+extern crate joinkit;
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use joinkit::EitherOrBoth::{self, Left, Both, Right};
+
+#[test]
+fn ord_variants() {
+    let mut v: Vec<EitherOrBoth<u32, u32>> = vec![Right(0), Both(1, 1), Left(2), Both(0, 5), Left(1)];
+    v.sort();
+    assert_eq!(v, vec![Left(1), Left(2), Both(0, 5), Both(1, 1), Right(0)]);
+}
+
+#[test]
+fn ord_equal() {
+    let a: EitherOrBoth<u32, &str> = Both(1, "x");
+    let b: EitherOrBoth<u32, &str> = Both(1, "x");
+    assert_eq!(a.cmp(&b), Ordering::Equal);
+    assert_eq!(Left::<u32, &str>(1).cmp(&Left(1)), Ordering::Equal);
+}
+
+#[test]
+fn hash() {
+    let mut set = HashSet::new();
+    set.insert(Left::<u32, u32>(1));
+    set.insert(Both(1, 2));
+    set.insert(Both(1, 2));
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&Both(1, 2)));
+}
+
+#[test]
+fn as_ref_as_mut() {
+    let mut v: Vec<EitherOrBoth<String, u32>> = vec![Left("a".to_owned()), Both("b".to_owned(), 1), Right(2)];
+    let refs: Vec<EitherOrBoth<&String, &u32>> = v.iter().map(|e| e.as_ref()).collect();
+    assert_eq!(refs, vec![Left(&"a".to_owned()), Both(&"b".to_owned(), &1), Right(&2)]);
+
+    for e in &mut v {
+        match e.as_mut() {
+            Left(l) => l.push('!'),
+            Both(l, r) => {
+                l.push('?');
+                *r += 10;
+            },
+            Right(r) => *r *= 2,
+        }
+    }
+    assert_eq!(v, vec![Left("a!".to_owned()), Both("b?".to_owned(), 11), Right(4)]);
+}
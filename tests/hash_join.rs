@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 extern crate joinkit;
 
 use std::collections::HashSet;
@@ -82,6 +84,18 @@ fn right_excl_fused_inv() {
     assert!(right_values.contains(&vec![1]));
     assert_eq!(it.next(), None);
 }
+#[test]
+fn right_excl_with_key() {
+    let a = vec![(1, 'a'), (3, 'b'), (3, 'c'), (6, 'd')];
+    let b = vec![(0, 'w'), (3, 'x'), (4, 'y'), (0, 'z'), (7, 'v')];
+    let keys: HashSet<u32> = a.clone().into_iter().map(|(k, _)| k).collect();
+    let right_only: HashSet<u32> = b.iter().map(|&(k, _)| k).filter(|k| !keys.contains(k)).collect();
+
+    let excl: Vec<(u32, Vec<char>)> = a.into_iter().hash_join_right_excl_with_key(b).collect();
+    assert_eq!(excl.iter().map(|&(k, _)| k).collect::<HashSet<_>>(), right_only);
+    assert_eq!(excl.len(), right_only.len());
+    assert!(excl.contains(&(0, vec!['w', 'z'])));
+}
 
 #[test]
 fn right_outer_fused() {
@@ -92,7 +106,7 @@ fn right_outer_fused() {
     let right_values: HashSet<Vec<u64>> = it.by_ref()
         .take(2)
         .map(|e| match e {
-                    Right(r) => return r,
+                    Right(r) => r,
                     _ => panic!("Expected Right variant"),
              })
         .collect();
@@ -109,7 +123,7 @@ fn right_outer_fused_inv() {
     let right_values: HashSet<Vec<u64>> = it.by_ref()
         .take(2)
         .map(|e| match e {
-                    Right(r) => return r,
+                    Right(r) => r,
                     _ => panic!("Expected Right variant"),
              })
         .collect();
@@ -129,7 +143,7 @@ fn full_outer_fused() {
     let right_values: HashSet<Vec<u64>> = it.by_ref()
         .take(2)
         .map(|e| match e {
-                    Right(r) => return r,
+                    Right(r) => r,
                     _ => panic!("Expected Right variant"),
              })
         .collect();
@@ -149,7 +163,7 @@ fn full_outer_fused_inv() {
     let right_values: HashSet<Vec<u64>> = it.by_ref()
         .take(2)
         .map(|e| match e {
-                    Right(r) => return r,
+                    Right(r) => r,
                     _ => panic!("Expected Right variant"),
              })
         .collect();
@@ -157,3 +171,409 @@ fn full_outer_fused_inv() {
     assert!(right_values.contains(&vec![1]));
     assert_eq!(it.next(), None);
 }
+
+#[test]
+fn inner_pairs_ref_not_clone() {
+    #[derive(Debug, PartialEq)]
+    struct NoClone(u32);
+
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c')];
+    let map = joinkit::group_into_map(vec![(1, NoClone(10)), (2, NoClone(20)), (1, NoClone(11))]);
+    let mut it = a.into_iter().hash_join_inner_pairs_ref(&map);
+    assert_eq!(it.next(), Some(('b', &NoClone(10))));
+    assert_eq!(it.next(), Some(('b', &NoClone(11))));
+    assert_eq!(it.next(), Some(('c', &NoClone(20))));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_take_not_clone() {
+    #[derive(Debug, PartialEq)]
+    struct NoClone(u32);
+
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c'), (3, 'd')];
+    let b = vec![(2, NoClone(20)), (1, NoClone(10)), (4, NoClone(40))];
+    let mut it = a.into_iter().hash_join_inner_take(b);
+    assert_eq!(it.size_hint(), (0, Some(3)));
+    assert_eq!(it.next(), Some(('b', vec![NoClone(10)])));
+    assert_eq!(it.next(), Some(('c', vec![NoClone(20)])));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn sized_same_as_unsized() {
+    let a = || (0..20).map(|i| (i % 7, i));
+    let b = || (3..10).map(|i| (i, i * 10));
+    for &n in &[0, 1, 100] {
+        assert_eq!(a().hash_join_inner(b()).collect::<Vec<_>>(),
+                   a().hash_join_inner_sized(b(), n).collect::<Vec<_>>());
+        assert_eq!(a().hash_join_left_excl(b()).collect::<Vec<_>>(),
+                   a().hash_join_left_excl_sized(b(), n).collect::<Vec<_>>());
+        assert_eq!(a().hash_join_left_outer(b()).collect::<Vec<_>>(),
+                   a().hash_join_left_outer_sized(b(), n).collect::<Vec<_>>());
+        // the right exclusive values come in the map's order
+        fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> { v.sort(); v }
+        assert_eq!(sorted(a().hash_join_right_excl(b()).collect()),
+                   sorted(a().hash_join_right_excl_sized(b(), n).collect()));
+        assert_eq!(sorted(a().hash_join_right_outer(b()).collect()),
+                   sorted(a().hash_join_right_outer_sized(b(), n).collect()));
+        assert_eq!(sorted(a().hash_join_full_outer(b()).collect()),
+                   sorted(a().hash_join_full_outer_sized(b(), n).collect()));
+    }
+}
+
+#[test]
+fn inner_grouped_left() {
+    let a = vec![(0, vec!['a']), (1, vec!['b', 'c', 'd'])];
+    let b = vec![(1, 10), (2, 20), (1, 11)];
+    let mut it = a.into_iter().hash_join_inner_grouped_left(b);
+    assert_eq!(it.next(), Some((vec!['b', 'c', 'd'], vec![10, 11])));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_count() {
+    struct NoClone;
+
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+    let b = vec![(1, NoClone), (2, NoClone), (1, NoClone), (1, NoClone)];
+    let mut it = a.into_iter().hash_join_inner_count(b);
+    assert_eq!(it.next(), Some(('b', 3)));
+    assert_eq!(it.next(), Some(('c', 1)));
+    assert_eq!(it.next(), Some(('d', 3)));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_reduce() {
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c')];
+    let b = vec![(1, "x".to_owned()), (2, "z".to_owned()), (1, "y".to_owned())];
+    // the right values are folded in their order
+    let mut it = a.into_iter().hash_join_inner_reduce(b, |acc, v| acc + &v);
+    assert_eq!(it.next(), Some(('b', "xy".to_owned())));
+    assert_eq!(it.next(), Some(('c', "z".to_owned())));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn first() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c')];
+    let b = vec![(1, 10), (2, 20), (1, 11), (1, 12)];
+    let mut it = a.into_iter().hash_join_first(b);
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_eq!(it.next(), Some(('a', None)));
+    assert_eq!(it.next(), Some(('b', Some(10))));
+    assert_eq!(it.next(), Some(('c', Some(10))));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_unique_right() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd')];
+    let b = vec![(1, 10), (2, 20), (3, 30)];
+    let joined: Vec<_> = a.clone().into_iter().hash_join_inner_unique_right(b).unwrap().collect();
+    assert_eq!(joined, vec![('b', 10), ('c', 10), ('d', 20)]);
+
+    // the first duplicate is reported
+    let b = vec![(1, 10), (2, 20), (1, 11), (2, 21)];
+    let e = a.into_iter().hash_join_inner_unique_right(b).err().unwrap();
+    assert_eq!(e, joinkit::DuplicateKey { index: 2, key: 1 });
+    assert_eq!(e.to_string(), "the key at index 2 of the right input is a duplicate");
+}
+
+#[test]
+fn left_outer_dedup() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd')];
+    let b = vec![(1, 10), (2, 20), (1, 11), (1, 10), (2, 20), (1, 12), (1, 11)];
+    let joined: Vec<_> = a.into_iter().hash_join_left_outer_dedup(b).collect();
+    assert_eq!(joined, vec![Left('a'), Both('b', vec![10, 11, 12]), Both('c', vec![10, 11, 12]), Both('d', vec![20])]);
+}
+
+#[test]
+fn inner_retain() {
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+    let b = vec![(0, 10), (1, 11), (2, 20), (1, 12), (2, 21), (3, 30), (3, 31)];
+    let joined: Vec<_> = a.clone().into_iter().hash_join_inner(b.clone()).map(|(lv, _)| lv).collect();
+    assert_eq!(joined, vec!['a', 'b', 'c', 'd']);
+
+    // the key 0 occurs only once in the right iterator
+    let mut it = joinkit::HashJoinInner::new(a, b);
+    it.retain(|_, rvv| rvv.len() >= 2);
+    let joined: Vec<_> = it.collect();
+    assert_eq!(joined, vec![('b', vec![11, 12]), ('c', vec![20, 21]), ('d', vec![11, 12])]);
+}
+
+#[test]
+fn map_extend() {
+    let mut map = joinkit::HashJoinMap::new();
+    map.extend(vec![(1, 10), (2, 20)]);
+    {
+        let mut it = vec![(1, 'a'), (3, 'c')].into_iter().hash_join_inner_pairs_ref(map.as_map());
+        assert_eq!(it.next(), Some(('a', &10)));
+        assert_eq!(it.next(), None);
+    }
+    map.extend(vec![(3, 30), (1, 11)]);
+    map.add_right(3, 31);
+    let mut it = vec![(1, 'a'), (3, 'c')].into_iter().hash_join_inner_pairs_ref(map.as_map());
+    assert_eq!(it.next(), Some(('a', &10)));
+    assert_eq!(it.next(), Some(('a', &11)));
+    assert_eq!(it.next(), Some(('c', &30)));
+    assert_eq!(it.next(), Some(('c', &31)));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn with_map_borrowed() {
+    let map = joinkit::group_into_map((2..5).zip(2..5));
+    let mut it = (0..3).zip(0..3).hash_join_inner_with_map(&map);
+    assert_eq!(it.next(), Some((2, &[2][..])));
+    assert_eq!(it.next(), None);
+
+    let mut it = (0..3).zip(0..3).hash_join_left_outer_with_map(&map);
+    assert_eq!(it.next(), Some(Left(0)));
+    assert_eq!(it.next(), Some(Left(1)));
+    assert_eq!(it.next(), Some(Both(2, &[2][..])));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn from_map_same_as_new() {
+    use joinkit::{HashJoinInner, HashJoinLeftOuter, HashJoinRightExcl, HashJoinRightOuter,
+                  HashJoinFullOuter};
+
+    let a = || (0..3).zip(0..3);
+    let b = || (2..5).zip(2..5);
+    let map = || joinkit::group_into_map(b());
+    let sorted = |mut v: Vec<_>| { v.sort(); v };
+
+    assert_eq!(HashJoinInner::from_map(a(), map()).collect::<Vec<_>>(),
+               a().hash_join_inner(b()).collect::<Vec<_>>());
+    assert_eq!(HashJoinLeftOuter::from_map(a(), map()).collect::<Vec<_>>(),
+               a().hash_join_left_outer(b()).collect::<Vec<_>>());
+    assert_eq!(sorted(HashJoinRightExcl::from_map(a(), map()).collect()),
+               sorted(a().hash_join_right_excl(b()).collect()));
+    let mut it = HashJoinRightOuter::from_map(a(), map());
+    assert_eq!(it.next(), Some(Both(2, vec![2])));
+    assert_eq!(it.count(), 2);
+    let mut it = HashJoinFullOuter::from_map(a(), map());
+    assert_eq!(it.next(), Some(Left(0)));
+    assert_eq!(it.next(), Some(Left(1)));
+    assert_eq!(it.next(), Some(Both(2, vec![2])));
+    assert_eq!(it.count(), 2);
+}
+
+#[test]
+fn left_outer_unmatched() {
+    let a = (0..6).map(|i| (i, i));
+    let b = (2..4).map(|i| (i, i));
+    let mut it = a.hash_join_left_outer_unmatched(b);
+    assert_eq!(it.next(), Some(Left(0)));
+    // only the keys yielded so far
+    assert_eq!(it.unmatched_keys(), &[0]);
+    assert_eq!(it.by_ref().count(), 5);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.into_unmatched_keys(), vec![0, 1, 4, 5]);
+}
+
+#[test]
+fn right_outer_flat_same_as_grouped() {
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+    let b = vec![(1, 10), (2, 20), (1, 11), (3, 30), (4, 40), (4, 41)];
+    let mut flat: Vec<_> = a.clone().into_iter().hash_join_right_outer_flat(b.clone()).collect();
+    let mut grouped: Vec<_> = a.into_iter().hash_join_right_outer(b)
+        .flat_map(|e| match e {
+            Both(lv, rvv) => rvv.into_iter().map(|rv| Both(lv, rv)).collect::<Vec<_>>(),
+            Right(rvv) => rvv.into_iter().map(Right).collect(),
+            Left(_) => unreachable!(),
+        })
+        .collect();
+    // the matched values come first, in the order of the left input
+    assert_eq!(&flat[..5], &[Both('b', 10), Both('b', 11), Both('c', 20), Both('d', 10), Both('d', 11)]);
+    flat.sort();
+    grouped.sort();
+    assert_eq!(flat, grouped);
+    assert_eq!(flat.len(), 8);
+}
+
+#[test]
+fn empty_left_skips_right() {
+    use std::cell::Cell;
+
+    let consumed = Cell::new(0);
+    let r = || vec![(0, 'x'), (1, 'y')].into_iter().inspect(|_| consumed.set(consumed.get() + 1));
+    let l: Vec<(i32, char)> = Vec::new();
+
+    assert_eq!(l.clone().into_iter().hash_join_inner(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_left_excl(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_left_outer(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_inner_take(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_inner_count(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_inner_reduce(r(), |a, _| a).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_first(r()).count(), 0);
+    assert_eq!(l.clone().into_iter().hash_join_inner_unique_right(r()).unwrap().count(), 0);
+    assert_eq!(consumed.get(), 0);
+
+    // the duplicate right keys are not reported either, as they are never read
+    let dup = vec![(0, 'x'), (0, 'y')];
+    assert!(l.clone().into_iter().hash_join_inner_unique_right(dup.clone()).is_ok());
+    assert!(vec![(0, 'a')].into_iter().hash_join_inner_unique_right(dup).is_err());
+
+    // the right values are still yielded by the right modes
+    assert_eq!(l.clone().into_iter().hash_join_right_excl(r()).count(), 2);
+    assert_eq!(l.into_iter().hash_join_full_outer(r()).count(), 2);
+    assert_eq!(consumed.get(), 4);
+
+    // a non-empty left iterator is joined as usual, including its first value
+    let joined: Vec<_> = vec![(1, 'b')].into_iter().hash_join_inner(r()).collect();
+    assert_eq!(joined, vec![('b', vec!['y'])]);
+}
+
+#[test]
+fn try_inner_build_error() {
+    #[derive(Debug, PartialEq)]
+    struct ReadError(usize);
+
+    let r = vec![Ok((1, 'x')), Err(ReadError(1)), Ok((2, 'y')), Err(ReadError(3))];
+    let res = vec![(1, 'a')].into_iter().try_hash_join_inner(r);
+    assert_eq!(res.err(), Some(ReadError(1)));
+
+    let r: Vec<Result<(i32, char), ReadError>> = vec![Ok((1, 'x')), Ok((2, 'y')), Ok((1, 'z'))];
+    let joined: Vec<_> = vec![(1, 'a'), (3, 'c')].into_iter().try_hash_join_inner(r).unwrap().collect();
+    assert_eq!(joined, vec![('a', vec!['x', 'z'])]);
+}
+
+#[test]
+fn inner_by_norm_key() {
+    let l = vec![" Alice", "bob", "Carol "];
+    let r = vec!["alice", "CAROL", "dave", "carol"];
+    let norm = |s: &&str| s.trim().to_lowercase();
+    let joined: Vec<_> = l.into_iter().hash_join_inner_by_norm_key(r, norm, norm).collect();
+    assert_eq!(joined, vec![(" Alice", vec!["alice"]), ("Carol ", vec!["CAROL", "carol"])]);
+}
+
+#[test]
+fn into_right_excl_after_probe() {
+    let l = vec![(1, 'a'), (2, 'b'), (4, 'd')];
+    let r = vec![(1, 'x'), (2, 'y'), (3, 'z'), (5, 'w')];
+    let mut it = l.clone().into_iter().hash_join_right_outer(r.clone());
+    assert_eq!(it.next(), Some(Both('a', vec!['x'])));
+    let mut excl: Vec<_> = it.into_right_excl().collect();
+    excl.sort();
+    assert_eq!(excl, vec![vec!['w'], vec!['z']]);
+
+    let mut it = l.into_iter().hash_join_full_outer(r);
+    assert_eq!(it.next(), Some(Both('a', vec!['x'])));
+    assert_eq!(it.next(), Some(Both('b', vec!['y'])));
+    assert_eq!(it.next(), Some(Left('d')));
+    let mut excl: Vec<_> = it.into_right_excl().collect();
+    excl.sort();
+    assert_eq!(excl, vec![vec!['w'], vec!['z']]);
+}
+
+#[test]
+fn left_option() {
+    let l = vec![(1, 'a'), (2, 'b'), (1, 'c')];
+    let r = vec![(1, 'x'), (3, 'z'), (1, 'y')];
+    let joined: Vec<_> = l.into_iter().hash_join_left_option(r).collect();
+    assert_eq!(joined, vec![('a', Some(vec!['x', 'y'])), ('b', None), ('c', Some(vec!['x', 'y']))]);
+}
+
+#[test]
+fn inner_full_right() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Person {
+        id: u32,
+        name: String,
+        tags: Vec<&'static str>,
+    }
+
+    let alice = Person {id: 1, name: "Alice".to_string(), tags: vec!["admin"]};
+    let bob = Person {id: 2, name: "Bob".to_string(), tags: vec![]};
+    let alice2 = Person {id: 1, name: "Alice B.".to_string(), tags: vec!["guest", "new"]};
+    let r = vec![alice.clone(), bob, alice2.clone()];
+    let joined: Vec<_> = vec![(1, 'a'), (3, 'c')].into_iter().hash_join_inner_full_right(r, |p| p.id).collect();
+    assert_eq!(joined, vec![('a', vec![alice, alice2])]);
+}
+
+#[test]
+fn inner_collect() {
+    use std::collections::HashMap;
+
+    let l = vec![(1, 'a'), (2, 'b'), (1, 'c'), (4, 'd')];
+    let r = vec![(1, 'x'), (2, 'y'), (3, 'z'), (1, 'w')];
+    let map = l.into_iter().hash_join_inner_collect(r);
+    let expected: HashMap<_, _> = vec![(1, vec!['x', 'w']), (2, vec!['y'])].into_iter().collect();
+    assert_eq!(map, expected);
+
+    let r = vec![(1, 'x')].into_iter().inspect(|_| panic!("the right iterator must not be consumed"));
+    assert!(Vec::<(i32, char)>::new().into_iter().hash_join_inner_collect(r).is_empty());
+}
+
+#[test]
+fn inner_pairs_bounded() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static LIVE: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// A right value counting its live instances.
+    #[derive(Debug, PartialEq)]
+    struct Tracked(usize);
+
+    impl Tracked {
+        fn new(n: usize) -> Self {
+            LIVE.with(|c| c.set(c.get() + 1));
+            Tracked(n)
+        }
+    }
+
+    impl Clone for Tracked {
+        fn clone(&self) -> Self {
+            Tracked::new(self.0)
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            LIVE.with(|c| c.set(c.get() - 1));
+        }
+    }
+
+    let live = || LIVE.with(|c| c.get());
+    let group = 10_000;
+    let l = vec![(1, 'a'), (2, 'b'), (1, 'c')];
+    let r = (0..group).map(|n| (1, Tracked::new(n))).chain(vec![(3, Tracked::new(0))]);
+    let mut it = l.into_iter().hash_join_inner_pairs(r);
+    let mut pairs = 0;
+    for (lv, rv) in it.by_ref() {
+        // only the yielded right value is alive besides the map
+        assert_eq!(live(), group + 2);
+        assert_eq!((lv, rv.0), (if pairs < group {'a'} else {'c'}, pairs % group));
+        pairs += 1;
+    }
+    assert_eq!(pairs, 2 * group);
+    drop(it);
+    assert_eq!(live(), 0);
+}
+
+#[test]
+fn partition_by_membership() {
+    let keys: HashSet<u32> = vec![1, 3, 5].into_iter().collect();
+    let a = vec![(0, 'a'), (1, 'b'), (3, 'c'), (1, 'd'), (4, 'e')];
+    let (matched, unmatched) = a.clone().into_iter().partition_by_membership(keys.clone(), |&(k, _)| k);
+    // both parts keep the order of the input
+    assert_eq!(matched.collect::<Vec<_>>(), vec![(1, 'b'), (3, 'c'), (1, 'd')]);
+    assert_eq!(unmatched.collect::<Vec<_>>(), vec![(0, 'a'), (4, 'e')]);
+
+    // the same as the left exclusive join against the right items of the keys
+    let b: Vec<(u32, ())> = keys.iter().map(|&k| (k, ())).collect();
+    let (_, unmatched) = a.clone().into_iter().partition_by_membership(keys, |&(k, _)| k);
+    let excl: Vec<char> = a.into_iter().hash_join_left_excl(b).collect();
+    assert_eq!(unmatched.map(|(_, v)| v).collect::<Vec<_>>(), excl);
+
+    // an empty set matches nothing
+    let (mut matched, unmatched) = (0..3).partition_by_membership(HashSet::new(), |&k| k);
+    assert_eq!(matched.next(), None);
+    assert_eq!(unmatched.collect::<Vec<_>>(), vec![0, 1, 2]);
+}
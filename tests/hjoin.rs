@@ -0,0 +1,200 @@
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Writes `content` into a temporary file unique to the calling test.
+fn input(test: &str, side: &str, content: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("joinkit-hjoin-{}-{}", test, side));
+    let mut f = File::create(&path).unwrap();
+    f.write_all(content.as_bytes()).unwrap();
+    path
+}
+
+fn hjoin(args: &[&str], left: &PathBuf, right: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hjoin"))
+        .args(args)
+        .arg(left)
+        .arg(right)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn trailing_newline() {
+    let l = input("trailing_newline", "l", "1,a\n2,b\n\n");
+    let r = input("trailing_newline", "r", "1,x\n3,y");
+    let out = hjoin(&["-m", "left-outer"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n2,b,,\n");
+}
+
+#[test]
+fn by_column_name() {
+    let l = input("by_column_name", "l", "order_id,amount\n1,10\n2,20\n");
+    let r = input("by_column_name", "r", "name,id\nB,2\nC,3\n");
+    let out = hjoin(&["--header", "--left-by", "order_id", "--right-by", "id"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "order_id,amount,name,id\n2,20,B,2\n");
+}
+
+#[test]
+fn by_column_name_missing() {
+    let l = input("by_column_name_missing", "l", "id,amount\n1,10\n");
+    let r = input("by_column_name_missing", "r", "name,key\nB,1\n");
+    let out = hjoin(&["--header", "--by", "id"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr)
+        .contains("Error: the column 'id' was not found in the header!"));
+}
+
+#[test]
+fn composite_key() {
+    let l = input("composite_key", "l", "1,a,x\n2,b,y\n3,c,z\n");
+    let r = input("composite_key", "r", "p,01,q,x\nq,2,r,n\nr,3,s,z\n");
+
+    // the numeric component matches `01` with `1`
+    let out = hjoin(&["-1", "1-u,3", "-2", "2-u,4"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,x,p,01,q,x\n3,c,z,r,3,s,z\n");
+
+    // the key matching on one component only is not joined
+    let out = hjoin(&["-1", "1-u,3", "-2", "2-u,4", "-m", "left-excl"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b,y\n");
+
+    // as strings, `01` does not match `1`
+    let out = hjoin(&["-1", "1,3", "-2", "2,4"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "3,c,z,r,3,s,z\n");
+
+    // the components are compared in the given order
+    let out = hjoin(&["-1", "3,1-u", "-2", "4,2-u"], &l, &r);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,x,p,01,q,x\n3,c,z,r,3,s,z\n");
+    let out = hjoin(&["-1", "1,3", "-2", "4,2"], &l, &r);
+    assert!(out.stdout.is_empty());
+
+    // the data types must be the same at the same positions
+    let out = hjoin(&["-1", "1-u,3", "-2", "2,4"], &l, &r);
+    assert!(!out.status.success());
+}
+
+#[test]
+fn composite_key_by_column_names() {
+    let l = input("composite_key_by_column_names", "l", "id,tag,v\n1,x,a\n1,y,b\n");
+    let r = input("composite_key_by_column_names", "r", "tag,id,w\ny,1,c\nx,2,d\n");
+    let out = hjoin(&["--header", "--by", "id,tag"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "id,tag,v,tag,id,w\n1,y,b,y,1,c\n");
+}
+
+#[test]
+fn max_keys() {
+    let l = input("max_keys", "l", "1,a\n3,b\n");
+    let r = input("max_keys", "r", "1,x\n1,y\n2,z\n3,w\n4,v\n");
+    for m in &["inner", "left-excl", "left-outer", "right-excl", "right-outer", "full-outer"] {
+        let out = hjoin(&["-m", m, "--max-keys", "2"], &l, &r);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr)
+            .starts_with("Error: FILE2 has more than 2 distinct keys"));
+
+        // the repeated keys are counted once
+        let out = hjoin(&["-m", m, "--max-keys", "4"], &l, &r);
+        assert!(out.status.success());
+    }
+    let out = hjoin(&["--agg", "count", "--max-keys", "3"], &l, &r);
+    assert!(!out.status.success());
+
+    let out = hjoin(&["--max-keys", "x"], &l, &r);
+    assert!(!out.status.success());
+}
+
+#[test]
+fn auto_build_side() {
+    let sorted = |out: &[u8]| {
+        let mut lines: Vec<String> = String::from_utf8_lossy(out).lines().map(String::from).collect();
+        lines.sort();
+        lines
+    };
+    let l = input("auto_build_side", "l", "1,a\n3,b\n5,c\n");
+    let r = input("auto_build_side", "r", "3,w\n1,x\n2,z\n1,y\n4,v\n");
+    // the limits guard FILE2, which might not be the one loaded
+    for limit in &["--max-keys", "--max-group"] {
+        let out = hjoin(&["--auto-build-side", limit, "1"], &l, &r);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).contains("cannot be used with"));
+    }
+
+    // the fields of FILE1 come first, but the records follow FILE2
+    let out = hjoin(&["--auto-build-side"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "3,b,3,w\n1,a,1,x\n1,a,1,y\n");
+
+    for m in &["inner", "left-excl", "left-outer", "right-excl", "right-outer", "full-outer"] {
+        let expected = hjoin(&["-m", m], &l, &r);
+        let out = hjoin(&["-m", m, "--auto-build-side"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(sorted(&out.stdout), sorted(&expected.stdout));
+        let out = hjoin(&["-m", m, "--auto-build-side", "--parallel=2"], &l, &r);
+        assert_eq!(sorted(&out.stdout), sorted(&expected.stdout));
+    }
+
+    // the larger FILE1 is not swapped
+    let out = hjoin(&["--auto-build-side"], &r, &l);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "3,w,3,b\n1,x,1,a\n1,y,1,a\n");
+}
+
+#[test]
+fn parallel() {
+    let sorted = |out: &[u8]| {
+        let mut lines: Vec<String> = String::from_utf8_lossy(out).lines().map(String::from).collect();
+        lines.sort();
+        lines
+    };
+    let l: String = (0..2000).map(|i| format!("{},L{}\n", (i * 7) % 1500, i)).collect();
+    let r: String = (0..2000).map(|i| format!("{},R{}\n", (i * 11) % 1800 + 300, i)).collect();
+    let l = input("parallel", "l", &l);
+    let r = input("parallel", "r", &r);
+    for m in &["inner", "left-excl", "left-outer", "right-excl", "right-outer", "full-outer"] {
+        let seq = hjoin(&["-m", m, "-1", "1-u", "-2", "1-u"], &l, &r);
+        for threads in &["--parallel", "--parallel=1", "--parallel=3"] {
+            let par = hjoin(&["-m", m, "-1", "1-u", "-2", "1-u", threads], &l, &r);
+            assert!(par.status.success());
+            assert_eq!(sorted(&par.stdout), sorted(&seq.stdout));
+            // the records matching the left file are written in its order
+            if *m != "right-excl" && *m != "right-outer" && *m != "full-outer" {
+                assert_eq!(par.stdout, seq.stdout);
+            }
+        }
+    }
+
+    let out = hjoin(&["--parallel=0"], &l, &r);
+    assert!(!out.status.success());
+}
+
+#[test]
+fn parallel_max_group() {
+    let l = input("parallel_max_group", "l", "a,1\nb,2\nc,3\n");
+    let r = input("parallel_max_group", "r", "a,x\na,y\na,z\nb,w\n");
+    for m in &["inner", "left-excl", "left-outer", "right-excl", "right-outer", "full-outer"] {
+        for action in &["skip", "abort"] {
+            let args = ["-m", m, "--max-group", "1", "--on-max-group", action];
+            let seq = hjoin(&args, &l, &r);
+            let par = hjoin(&[&args[..], &["--parallel=2"]].concat(), &l, &r);
+            assert_eq!(par.status.success(), seq.status.success(), "{} {}", m, action);
+            let mut seq: Vec<_> = String::from_utf8_lossy(&seq.stdout).lines().map(String::from).collect();
+            let mut par: Vec<_> = String::from_utf8_lossy(&par.stdout).lines().map(String::from).collect();
+            seq.sort();
+            par.sort();
+            assert_eq!(par, seq, "{} {}", m, action);
+        }
+    }
+    // the exclusive modes are not checked
+    let out = hjoin(&["-m", "left-excl", "--max-group", "1", "--parallel=2"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "c,3\n");
+}
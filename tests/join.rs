@@ -0,0 +1,795 @@
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Writes `content` into a temporary file unique to the calling test.
+fn input(test: &str, side: &str, content: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("joinkit-join-{}-{}", test, side));
+    let mut f = File::create(&path).unwrap();
+    f.write_all(content.as_bytes()).unwrap();
+    path
+}
+
+fn join(args: &[&str], left: &PathBuf, right: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_join"))
+        .args(args)
+        .arg(left)
+        .arg(right)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn strategies_inner() {
+    let l = input("strategies_inner", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("strategies_inner", "r", "2,x\n3,y\n4,z\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b,2,x\n2,c,2,x\n4,d,4,z\n");
+    }
+}
+
+#[test]
+fn strategies_numeric_key() {
+    let l = input("strategies_numeric_key", "l", "2,a\n10,b\n");
+    let r = input("strategies_numeric_key", "r", "2,x\n10,y\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "-m", "full-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,a,2,x\n10,b,10,y\n");
+    }
+}
+
+#[test]
+fn strategies_right_outer() {
+    let l = input("strategies_right_outer", "l", "1,a\n2,b\n");
+    let r = input("strategies_right_outer", "r", "2,x\n3,y\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "-m", "right-outer"], &l, &r);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        // the hash join yields the unmatched right records in arbitrary order, after the matched
+        let mut lines: Vec<&str> = stdout.lines().collect();
+        lines.sort();
+        assert_eq!(lines, vec![",,3,y", "2,b,2,x"]);
+    }
+}
+
+#[test]
+fn validate_unique_hash() {
+    let l = input("validate_unique_hash", "l", "1,a\n");
+    let r = input("validate_unique_hash", "r", "1,x\n");
+    let out = join(&["--strategy", "hash", "--validate-unique"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("requires the 'merge' strategy"));
+}
+
+#[test]
+fn limit() {
+    let l = input("limit", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("limit", "r", "2,x\n3,y\n4,z\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--limit", "2", "-m", "left-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,,\n2,b,2,x\n");
+
+        let out = join(&["--strategy", s, "--limit", "0"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "");
+    }
+}
+
+#[test]
+fn out_sep_escapes() {
+    let l = input("out_sep_escapes", "l", "1,a\n2,b\n");
+    let r = input("out_sep_escapes", "r", "2,x\n");
+    let out = join(&["--out-field-sep", "\\t", "--out-rec-sep", "\\r\\n"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b\t2,x\r\n");
+}
+
+#[test]
+fn single_numeric_key() {
+    // sorted numerically, not lexicographically
+    let l = input("single_numeric_key", "l", "-3,a\n2,b\n2,c\n10,d\n");
+    let r = input("single_numeric_key", "r", "-3,x\n3,y\n10,z\n10,w\n");
+    let modes = ["inner", "left-excl", "left-outer", "right-excl", "right-outer", "full-outer"];
+    for m in &modes {
+        let sorted_lines = |out: Output| {
+            assert!(out.status.success());
+            let mut lines: Vec<String> = String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .map(str::to_owned)
+                .collect();
+            lines.sort();
+            lines
+        };
+        // the hash strategy extracts the general key
+        let hash = join(&["--strategy", "hash", "-1", "1-i", "-2", "1-i", "-m", m], &l, &r);
+        let merge = join(&["--strategy", "merge", "-1", "1-i", "-2", "1-i", "-m", m], &l, &r);
+        assert_eq!(sorted_lines(hash), sorted_lines(merge), "mode {}", m);
+    }
+}
+
+#[test]
+fn single_numeric_key_error() {
+    let l = input("single_numeric_key_error", "l", "1,a\nx,b\n");
+    let r = input("single_numeric_key_error", "r", "1,x\n");
+    let out = join(&["--strategy", "merge", "-1", "1-u", "-2", "1-u"], &l, &r);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), 
+               "Error: the value 'x' of the field 1 cannot be converted into 'u64' in FILE1\n");
+}
+
+/// Feeds the left input pipe with the `batches` of the records, checking each batch of the output
+/// records can be read before the next batch of the input records is written.
+#[cfg(unix)]
+fn assert_incremental(test: &str, args: &[&str], batches: &[&[(&str, &str)]]) {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut l = env::temp_dir();
+    l.push(format!("joinkit-join-{}-l", test));
+    let _ = std::fs::remove_file(&l);
+    assert!(Command::new("mkfifo").arg(&l).status().unwrap().success());
+    let r = input(test, "r", "1,x\n2,y\n");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_join"))
+        .args(args)
+        .args(["-m", "left-outer"])
+        .arg(&l)
+        .arg(&r)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut left = File::create(&l).unwrap();
+    let mut line = String::new();
+    for batch in batches {
+        for &(rec, _) in batch.iter() {
+            left.write_all(rec.as_bytes()).unwrap();
+        }
+        left.flush().unwrap();
+        for &(_, expected) in batch.iter() {
+            line.clear();
+            stdout.read_line(&mut line).unwrap();
+            assert_eq!(line, expected);
+        }
+    }
+    drop(left);
+    assert!(child.wait().unwrap().success());
+}
+
+/// The left input is a pipe fed record by record, so each output record must be readable before
+/// the next input record is written.
+#[cfg(unix)]
+#[test]
+fn unbuffered() {
+    assert_incremental("unbuffered", &["--unbuffered"], 
+                       &[&[("1,a\n", "1,a,1,x\n")], &[("3,c\n", "3,c,,\n")], &[("2,b\n", "2,b,2,y\n")]]);
+}
+
+#[cfg(unix)]
+#[test]
+fn flush_every() {
+    assert_incremental("flush_every_1", &["--flush-every", "1"], 
+                       &[&[("1,a\n", "1,a,1,x\n")], &[("3,c\n", "3,c,,\n")], &[("2,b\n", "2,b,2,y\n")]]);
+    assert_incremental("flush_every_2", &["--flush-every", "2"], 
+                       &[&[("1,a\n", "1,a,1,x\n"), ("3,c\n", "3,c,,\n")], 
+                         &[("2,b\n", "2,b,2,y\n"), ("4,d\n", "4,d,,\n")]]);
+
+    let l = input("flush_every", "l", "1,a\n");
+    let out = join(&["--flush-every", "0"], &l, &l);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), 
+               "Error: the number of records to flush after must be a positive integer\n");
+}
+
+#[test]
+fn empty_keys() {
+    let l = input("empty_keys", "l", ",a\n1,b\n");
+    let r = input("empty_keys", "r", ",x\n1,y\n2,z\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), ",a,,x\n1,b,1,y\n");
+
+        let out = join(&["--strategy", s, "--skip-empty-keys"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,b,1,y\n");
+
+        let out = join(&["--strategy", s, "--skip-empty-keys", "-m", "full-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,b,1,y\n,,2,z\n");
+    }
+}
+
+#[test]
+fn empty_as_null() {
+    let l = input("empty_as_null", "l", ",a\n1,b\n");
+    let r = input("empty_as_null", "r", ",x\n1,y\n2,z\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--empty-as-null"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,b,1,y\n");
+
+        // the null records are written after the joined ones
+        let out = join(&["--strategy", s, "--empty-as-null", "-m", "full-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,b,1,y\n,,2,z\n,a,,\n,,,x\n");
+
+        let out = join(&["--strategy", s, "--empty-as-null", "-m", "left-excl"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), ",a\n");
+    }
+    // an empty numeric key is not parsed
+    let out = join(&["--strategy", "merge", "--empty-as-null", "-1", "1-u", "-2", "1-u"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,b,1,y\n");
+}
+
+#[test]
+fn coalesce() {
+    let l = input("coalesce", "l", "1,a,\n2,,b\n3,c,c\n");
+    let r = input("coalesce", "r", "1,,x\n2,y,\n4,z,z\n");
+    let out = join(&["--strategy", "merge", "--coalesce", "-m", "full-outer"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,x\n2,y,b\n3,c,c\n4,z,z\n");
+
+    // the left field is preferred, the extra fields are kept
+    let r = input("coalesce", "r2", "1,b,x,extra\n");
+    let out = join(&["--strategy", "merge", "--coalesce", "--out-field-sep", ";"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1;a;x;extra\n");
+}
+
+#[test]
+fn emit_key() {
+    let l = input("emit_key", "l", "a,01,x\nb,02,y\n");
+    let r = input("emit_key", "r", "1,a,p\n3,c,q\n");
+    let lm = input("emit_key", "lm", "1,02,x\n2,01,y\n");
+    let rm = input("emit_key", "rm", "01,2,p\n");
+    for s in &["hash", "merge"] {
+        // each key component is written, the numbers as parsed
+        let out = join(&["--strategy", s, "--emit-key", "-1", "1:2-u", "-2", "1:2-u"], &lm, &rm);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,2,1,02,x,01,2,p\n");
+    }
+
+    // the unmatched right records write their own key
+    let out = join(&["--strategy", "merge", "--emit-key", "-1", "2-u", "-2", "1-u", "-m", "full-outer"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,01,x,1,a,p\n2,b,02,y,,,\n3,,,,3,c,q\n");
+
+    let l = input("emit_key", "lh", "name,id\na,1\n");
+    let r = input("emit_key", "rh", "id,value\n1,p\n");
+    let out = join(&["--header", "--emit-key", "--left-by", "id", "--right-by", "id"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "id,name,id,id,value\n1,a,1,1,p\n");
+}
+
+#[test]
+fn max_group() {
+    let l = input("max_group", "l", "1,a\n2,b\n");
+    let r = input("max_group", "r", "1,x\n1,y\n1,z\n2,w\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--max-group", "2"], &l, &r);
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty());
+        assert_eq!(String::from_utf8_lossy(&out.stderr), 
+                   "Error: the key [S(\"1\")] has more than 2 records in FILE2\n");
+
+        // the records of the key are dropped from FILE2 only
+        let out = join(&["--strategy", s, "--max-group", "2", "--on-max-group", "skip"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b,2,w\n");
+        assert_eq!(String::from_utf8_lossy(&out.stderr), 
+                   "Warning: skipping the key [S(\"1\")] with more than 2 records in FILE2\n");
+        let out = join(&["--strategy", s, "--max-group", "2", "--on-max-group", "skip", "-m", "left-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,,\n2,b,2,w\n");
+
+        // within the limit
+        let out = join(&["--strategy", s, "--max-group", "3"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n1,a,1,y\n1,a,1,z\n2,b,2,w\n");
+    }
+}
+
+#[test]
+fn max_group_incremental() {
+    // the merge join fails without reading the whole key run
+    let l = input("max_group_incremental", "l", "1,a\n");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_join"))
+        .args(["--strategy", "merge", "--max-group", "2"])
+        .arg(&l)
+        .arg("/dev/stdin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let total = 10_000_000;
+    let written = (0..total).take_while(|_| stdin.write_all(b"1,x\n").is_ok()).count();
+    drop(stdin);
+    let out = child.wait_with_output().unwrap();
+    assert!(!out.status.success());
+    assert!(written < total);
+    assert_eq!(String::from_utf8_lossy(&out.stderr), 
+               "Error: the key [S(\"1\")] has more than 2 records in FILE2\n");
+}
+
+#[test]
+fn project() {
+    let l = input("project", "l", "1,a,10\n2,b,20\n");
+    let r = input("project", "r", "1,x\n3,z\n");
+    for s in &["hash", "merge"] {
+        // the duplicate key is dropped, the rest is reordered
+        let out = join(&["--strategy", s, "--project", "1.1,2.2,1.3,1.2"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,x,10,a\n");
+    }
+
+    // the fields of the missing record are empty
+    let out = join(&["--strategy", "merge", "-m", "full-outer", "--project", "1.1,2.1,2.2,=EUR"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,1,x,EUR\n2,,,EUR\n,3,z,EUR\n");
+
+    let l = input("project", "lh", "id,name,amount\n1,a,10\n");
+    let r = input("project", "rh", "id,city\n1,x\n");
+    let out = join(&["--header", "--by", "id", "--project", "1.id,2.city:town,1.3,=EUR:currency"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "id,town,amount,currency\n1,x,10,EUR\n");
+
+    let out = join(&["--project", "1.1,2.city"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Error: invalid --project field '2.city'"));
+}
+
+#[test]
+fn progress() {
+    let l = input("progress", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("progress", "r", "2,x\n3,y\n4,z\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--progress"], &l, &r);
+        assert!(out.status.success());
+        // the output is not affected
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b,2,x\n2,c,2,x\n4,d,4,z\n");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(stderr.contains("\rleft records read: 4, records written: 3, elapsed: "), "{}", stderr);
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn field_sep_regex() {
+    let l = input("field_sep_regex", "l", "  1   a\n2 \t b  \n");
+    let r = input("field_sep_regex", "r", "1 x\n  3  y\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--field-sep-regex", r"\s+", "-m", "full-outer"], &l, &r);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let mut lines: Vec<&str> = stdout.lines().collect();
+        lines.sort();
+        // the records are written as read, separated by a tab, padded by tabs
+        assert_eq!(lines, vec!["\t\t  3  y", "  1   a\t1 x", "2 \t b  \t\t"]);
+    }
+
+    let out = join(&["--field-sep-regex", "("], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("invalid field separator regex"));
+}
+
+#[cfg(feature = "collation")]
+#[test]
+fn collation() {
+    let l = input("collation", "l", "Ann,1\nbob,2\nÉmile,3\nzoe,4\n");
+    let r = input("collation", "r", "ann,x\nBOB,y\nemile,z\nZOE,w\n");
+    let out = join(&["--strategy", "merge", "--collation", "nocase,noaccent"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout),
+               "Ann,1,ann,x\nbob,2,BOB,y\nÉmile,3,emile,z\nzoe,4,ZOE,w\n");
+    // without the accents folded, `Émile` sorts after `zoe`
+    let out = join(&["--strategy", "merge", "--collation", "nocase", "--check-sorted"], &l, &r);
+    assert!(!out.status.success());
+
+    let out = join(&["--strategy", "hash", "--collation", "nocase"], &l, &r);
+    assert!(!out.status.success());
+}
+
+#[test]
+fn key_types_mismatch() {
+    let l = input("key_types_mismatch", "l", "10,a\n");
+    let r = input("key_types_mismatch", "r", "a,10\n");
+    let out = join(&["-1", "1-u", "-2", "2"], &l, &r);
+    assert!(!out.status.success());
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&out.stderr)
+            .contains("the key field number 1 is 'u64' in FIELDS1, but 'string' in FIELDS2"));
+
+    let out = join(&["-1", "1-u", "-2", "1:2-u"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("FIELDS1 contains 1 key fields, but FIELDS2 contains 2"));
+
+    let out = join(&["-1", "1-u", "-2", "2-u"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "10,a,a,10\n");
+}
+
+#[test]
+fn explain() {
+    let l = input("explain", "l", "name;id\na;1\n");
+    let r = input("explain", "r", "id;amount\n1;10\n");
+    let out = join(&["--explain", "-s", "merge", "-m", "left-outer", "-F", ";", "--out-field-sep", "\\t",
+                     "--header", "--left-by", "id", "--right-by", "id"], &l, &r);
+    assert!(out.status.success());
+    // nothing is joined, not even the headers are written
+    assert!(out.stdout.is_empty());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), 
+               "strategy: merge\n\
+                mode: left-outer\n\
+                left key fields: 2\n\
+                right key fields: 1\n\
+                input record separators: \"\\n\" (left), \"\\n\" (right)\n\
+                input field separators: \";\" (left), \";\" (right)\n\
+                output record separator: \"\\n\"\n\
+                output field separator: \"\\t\"\n");
+}
+
+#[test]
+fn bad_number() {
+    let l = input("bad_number", "l", "n/a,b\n1,a\n");
+    let r = input("bad_number", "r", "0,z\n1,y\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "--bad-number", "skip"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,y\n");
+
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "--bad-number", "error"], &l, &r);
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty());
+        assert_eq!(String::from_utf8_lossy(&out.stderr), 
+                   "Error: the value 'n/a' of the field 1 cannot be converted into 'u64' in FILE1\n");
+
+        // the bad number matches the zero key
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "--bad-number", "zero"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "n/a,b,0,z\n1,a,1,y\n");
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "--bad-number", "zero", "--emit-key"], &l, &r);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "0,n/a,b,0,z\n1,1,a,1,y\n");
+    }
+}
+
+#[test]
+fn summary() {
+    let l = input("summary", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("summary", "r", "2,x\n2,y\n3,z\n4,w\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--summary"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), 
+                   "records: 4 (left), 4 (right)\n\
+                    distinct keys: 3 (left), 3 (right)\n\
+                    matched keys: 2\n\
+                    max group size: 2 (left), 2 (right)\n\
+                    output records: 5\n");
+    }
+
+    // the output records depend on the mode
+    for &(mode, n) in &[("left-excl", 1), ("left-outer", 6), ("right-excl", 1), ("right-outer", 6), ("full-outer", 7)] {
+        let out = join(&["--summary", "-m", mode], &l, &r);
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stdout).ends_with(&format!("output records: {}\n", n)), "{}", mode);
+    }
+}
+
+#[test]
+fn normalize_eol() {
+    let l = input("normalize_eol", "l", "1,a\r\n2,b\r\n\r\n");
+    let r = input("normalize_eol", "r", "1,x\n2,y\r\n");
+
+    // the stray `\r` ends up in the last field of the CRLF records
+    let out = join(&["--in-rec-sep", "\n", "-m", "left-outer"], &l, &r);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a\r,1,x\n2,b\r,2,y\r\n\r,,\n");
+
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--in-rec-sep", "\n", "--normalize-eol", "lf", "-m", "left-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n2,b,2,y\n");
+    }
+    let out = join(&["--normalize-eol", "crlf"], &l, &r);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\r\n2,b,2,y\r\n");
+}
+
+#[test]
+fn agg_count() {
+    let l = input("agg_count", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("agg_count", "r", "02,z\n2,x\n2,y\n3,w\n4,v\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--agg", "count"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,4\n4,1\n");
+
+        // the numeric keys are written as parsed
+        let out = join(&["--strategy", s, "--agg", "count", "-1", "1-u", "-2", "1-u"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,6\n4,1\n");
+    }
+
+    let l = input("agg_count_header", "l", "id,name\n1,a\n1,b\n");
+    let r = input("agg_count_header", "r", "val,id\nx,1\ny,1\nz,1\n");
+    let out = join(&["--header", "--left-by", "id", "--right-by", "id", "--agg", "count"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "id,count\n1,6\n");
+
+    let out = join(&["--agg", "count", "-m", "left-outer"], &l, &r);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), "Error: --agg requires the 'inner' mode\n");
+}
+
+#[test]
+fn expect_fields() {
+    let l = input("expect_fields", "l", "1,a,x\n2,b\n3,c,z\n");
+    let r = input("expect_fields", "r", "1,p\n2,q\n3,r,extra\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--expect-fields-left", "3"], &l, &r);
+        assert!(!out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "Error: the record 2 has 2 fields instead of 3 in FILE1\n");
+
+        let out = join(&["--strategy", s, "--expect-fields", "2"], &l, &r);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).starts_with("Error: the record "));
+
+        // the right file has at least 2 fields, the left one at least 2 as well
+        let out = join(&["--strategy", s, "--expect-fields", "2+"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,x,1,p\n2,b,2,q\n3,c,z,3,r,extra\n");
+        let out = join(&["--strategy", s, "--expect-fields", "2+", "--expect-fields-left", "3+"], &l, &r);
+        assert_eq!(String::from_utf8_lossy(&out.stderr), "Error: the record 2 has 2 fields instead of at least 3 in FILE1\n");
+    }
+
+    let out = join(&["--expect-fields", "x"], &l, &r);
+    assert!(!out.status.success());
+}
+
+#[test]
+fn descending() {
+    let l = input("descending", "l", "5,a\n3,b\n3,c\n1,d\n");
+    let r = input("descending", "r", "4,x\n3,y\n1,z\n0,w\n");
+    let out = join(&["--strategy", "merge", "--descending", "-1", "1-u", "-2", "1-u", "-m", "full-outer"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "5,a,,\n,,4,x\n3,b,3,y\n3,c,3,y\n1,d,1,z\n,,0,w\n");
+
+    let out = join(&["--strategy", "merge", "--descending"], &l, &r);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "3,b,3,y\n3,c,3,y\n1,d,1,z\n");
+
+    let out = join(&["--strategy", "merge", "--descending", "--check-sorted"], &l, &r);
+    assert!(out.status.success());
+
+    // the ascending order is checked by default
+    let out = join(&["--strategy", "merge", "--check-sorted"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).ends_with("at record 2 is out of order\n"));
+
+    let out = join(&["--strategy", "hash", "--descending"], &l, &r);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("requires the 'merge' strategy"));
+}
+
+#[test]
+fn left_excl_out() {
+    let l = input("left_excl_out", "l", "id,name\n1,a\n2,b\n2,c\n4,d\n");
+    let r = input("left_excl_out", "r", "id,value\n2,x\n3,y\n4,z\n");
+    let excl = input("left_excl_out", "excl", "");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--header", "--left-excl-out", excl.to_str().unwrap()], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "id,name,id,value\n2,b,2,x\n2,c,2,x\n4,d,4,z\n");
+        let mut unmatched = String::new();
+        File::open(&excl).unwrap().read_to_string(&mut unmatched).unwrap();
+        assert_eq!(unmatched, "id,name\n1,a\n");
+    }
+
+    let out = join(&["-m", "full-outer", "--left-excl-out", excl.to_str().unwrap()], &l, &r);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), "Error: --left-excl-out requires the 'inner' mode\n");
+}
+
+#[test]
+fn report_unmatched() {
+    let l = input("report_unmatched", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("report_unmatched", "r", "2,x\n3,y\n4,z\n5,w\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--report-unmatched"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b,2,x\n2,c,2,x\n4,d,4,z\n");
+        let mut unmatched: Vec<String> = String::from_utf8_lossy(&out.stderr).lines().map(String::from).collect();
+        // the unmatched right records of the hash join are not ordered
+        unmatched.sort();
+        assert_eq!(unmatched, vec!["FILE1: 1,a", "FILE2: 3,y", "FILE2: 5,w"]);
+    }
+
+    let out = join(&["-m", "left-outer", "--report-unmatched"], &l, &r);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), "Error: --report-unmatched requires the 'inner' mode\n");
+}
+
+#[test]
+fn pad_by_header() {
+    // the right file has no records, so the missing ones are padded by its header
+    let l = input("pad_by_header", "l", "id,name\n1,a\n");
+    let r = input("pad_by_header", "r", "id,value,note\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--header", "-m", "left-outer"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "id,name,id,value,note\n1,a,,,\n");
+    }
+}
+
+#[test]
+fn quoted() {
+    let l = input("quoted", "l", "1,\"multi\nline\"\n2,b\n");
+    let r = input("quoted", "r", "1,x\n2,\"y\n\"\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--quoted"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,\"multi\nline\",1,x\n2,b,2,\"y\n\"\n");
+    }
+    // the embedded newlines split the records without it
+    let out = join(&["--strategy", "hash"], &l, &r);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,\"multi,1,x\n2,b,2,\"y\n");
+}
+
+#[test]
+fn auto_sep() {
+    // the key of the TSV files contains a comma
+    let l = input("auto_sep", "l", "a,1\tx\nb,2\ty\n");
+    let r = input("auto_sep", "r", "b,2\tY\n");
+    let out = join(&["--auto-sep"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "b,2\ty\tb,2\tY\n");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("the field separator \"\\t\" in FILE1"));
+
+    let l = input("auto_sep", "csv_l", "1,a b\n2,c d\n");
+    let r = input("auto_sep", "csv_r", "2,x\n");
+    let out = join(&["--auto-sep"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2,c d,2,x\n");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("the field separator \",\" in FILE2"));
+}
+
+#[test]
+fn strict() {
+    let l = input("strict", "l", "1,a\n+2,b\n");
+    let r = input("strict", "r", "1,x\n2,y\n");
+    for s in &["hash", "merge"] {
+        // the sign is coerced silently without it
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n+2,b,2,y\n");
+
+        let out = join(&["--strategy", s, "-1", "1-u", "-2", "1-u", "--strict"], &l, &r);
+        assert!(!out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stderr), 
+                   "Error: the value '+2' of the field 1 is not a canonical number in FILE1 at record 2\n");
+
+        // the string keys are not checked
+        let out = join(&["--strategy", s, "--strict"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n");
+    }
+}
+
+#[test]
+fn with_fanout() {
+    let l = input("with_fanout", "l", "1,a\n2,b\n2,c\n4,d\n");
+    let r = input("with_fanout", "r", "2,x\n2,y\n2,z\n3,w\n4,v\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--with-fanout"], &l, &r);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert_eq!(stdout, "3,2,b,2,x\n3,2,b,2,y\n3,2,b,2,z\n3,2,c,2,x\n3,2,c,2,y\n3,2,c,2,z\n1,4,d,4,v\n");
+        // the fanout is the number of the output records of its left record
+        for line in stdout.lines() {
+            let (fanout, record) = line.split_once(',').unwrap();
+            let left = &record[..3];
+            let n = stdout.lines().filter(|l| &l[2..5] == left).count();
+            assert_eq!(fanout.parse::<usize>().unwrap(), n);
+        }
+
+        for m in &["left-outer", "right-outer", "full-outer"] {
+            let out = join(&["--strategy", s, "-m", m, "--with-fanout", "--emit-key"], &l, &r);
+            assert!(out.status.success());
+            let mut lines: Vec<String> = String::from_utf8_lossy(&out.stdout).lines().map(String::from).collect();
+            // the unmatched right records of the hash join are not ordered
+            lines.sort();
+            let expected: Vec<&str> = match *m {
+                "left-outer" => vec!["0,1,1,a,,", "1,4,4,d,4,v", "3,2,2,b,2,x", "3,2,2,b,2,y", "3,2,2,b,2,z",
+                                     "3,2,2,c,2,x", "3,2,2,c,2,y", "3,2,2,c,2,z"],
+                "right-outer" => vec!["0,3,,,3,w", "1,4,4,d,4,v", "3,2,2,b,2,x", "3,2,2,b,2,y", "3,2,2,b,2,z",
+                                      "3,2,2,c,2,x", "3,2,2,c,2,y", "3,2,2,c,2,z"],
+                _ => vec!["0,1,1,a,,", "0,3,,,3,w", "1,4,4,d,4,v", "3,2,2,b,2,x", "3,2,2,b,2,y",
+                          "3,2,2,b,2,z", "3,2,2,c,2,x", "3,2,2,c,2,y", "3,2,2,c,2,z"],
+            };
+            assert_eq!(lines, expected);
+        }
+    }
+
+    let l = input("with_fanout", "header_l", "id,name\n1,a\n");
+    let r = input("with_fanout", "header_r", "id,value\n1,x\n");
+    let out = join(&["--header", "--with-fanout"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "fanout,id,name,id,value\n1,1,a,1,x\n");
+}
+
+#[test]
+fn capabilities() {
+    let caps = |bin: &str| {
+        let out = Command::new(bin).arg("--capabilities").output().unwrap();
+        assert!(out.status.success());
+        String::from_utf8_lossy(&out.stdout).lines().map(String::from).collect::<Vec<_>>()
+    };
+    let join = caps(env!("CARGO_BIN_EXE_join"));
+    let core = ["strategy:hash", "strategy:merge", "mode:inner", "mode:left-excl", "mode:left-outer",
+                "mode:right-excl", "mode:right-outer", "mode:full-outer", "datatype:string", "datatype:i64",
+                "datatype:u64", "option:auto-sep", "option:quoted", "option:header", "option:keep-empty",
+                "option:normalize-eol", "option:max-keys", "option:parallel", "option:validate-unique",
+                "option:descending"];
+    for c in &core {
+        assert!(join.iter().any(|j| j == c), "missing {}", c);
+    }
+    assert_eq!(join.iter().any(|j| j == "feature:regex"), cfg!(feature = "regex"));
+
+    // the single strategy binaries list only the options of their strategy
+    let hjoin = caps(env!("CARGO_BIN_EXE_hjoin"));
+    assert!(hjoin.iter().any(|c| c == "option:parallel"));
+    assert!(!hjoin.iter().any(|c| c == "strategy:merge" || c == "option:descending"));
+    let mjoin = caps(env!("CARGO_BIN_EXE_mjoin"));
+    assert!(mjoin.iter().any(|c| c == "option:check-sorted"));
+    // the input format options are available with either strategy
+    assert_eq!(hjoin.iter().chain(&mjoin).filter(|c| *c == "option:quoted").count(), 2);
+    assert!(!mjoin.iter().any(|c| c == "strategy:hash" || c == "option:parallel"));
+    // every line is KIND:NAME
+    assert!(join.iter().chain(&hjoin).chain(&mjoin).all(|c| c.split(':').count() == 2));
+}
+
+#[test]
+fn empty_field_sep() {
+    let l = input("empty_field_sep", "l", "1,a\n");
+    let r = input("empty_field_sep", "r", "1,x\n");
+    for args in &[&["-F", ""][..], &["--in-field-sep-left", "", "--in-field-sep-right", ","][..]] {
+        let out = join(args, &l, &r);
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty());
+    }
+}
+
+#[test]
+fn quoted_keys() {
+    // the quoted field before the key contains the field separator
+    let l = input("quoted_keys", "l", "\"x,y\",1\n\"z\",\"2\"\n");
+    let r = input("quoted_keys", "r", "1,a\n2,\"b,\"\"c\"\"\"\n");
+    for s in &["hash", "merge"] {
+        let out = join(&["--strategy", s, "--quoted", "-1", "2", "-2", "1"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout),
+                   "\"x,y\",1,1,a\n\"z\",\"2\",2,\"b,\"\"c\"\"\"\n");
+        let out = join(&["--strategy", s, "--quoted", "-1", "2", "-2", "1", "--project", "2.2"], &l, &r);
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "a\n\"b,\"\"c\"\"\"\n");
+    }
+    // the emitted key is quoted again
+    let l = input("quoted_keys_emit", "l", "\"k,\"\"1\"\"\",a\n");
+    let r = input("quoted_keys_emit", "r", "\"k,\"\"1\"\"\"\n");
+    let out = join(&["--quoted", "--emit-key"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout),
+               "\"k,\"\"1\"\"\",\"k,\"\"1\"\"\",a,\"k,\"\"1\"\"\"\n");
+}
@@ -0,0 +1,35 @@
+extern crate joinkit;
+
+use joinkit::JoinMode;
+
+#[test]
+fn parse_all() {
+    let modes = [JoinMode::Inner,
+                 JoinMode::LeftExcl,
+                 JoinMode::LeftOuter,
+                 JoinMode::RightExcl,
+                 JoinMode::RightOuter,
+                 JoinMode::FullOuter];
+    for (name, mode) in JoinMode::NAMES.iter().zip(modes.iter()) {
+        assert_eq!(Ok(*mode), name.parse::<JoinMode>());
+        assert_eq!(*name, mode.to_string());
+    }
+}
+
+#[test]
+fn parse_unknown() {
+    for name in &["", "Inner", "left", "full_outer", "inner "] {
+        let e = name.parse::<JoinMode>().unwrap_err();
+        assert!(e.to_string().starts_with("unknown join mode"));
+    }
+}
+
+#[test]
+fn keeps_unmatched() {
+    let kept: Vec<(bool, bool)> = JoinMode::NAMES.iter()
+        .map(|name| name.parse::<JoinMode>().unwrap())
+        .map(|m| (m.keeps_unmatched_left(), m.keeps_unmatched_right()))
+        .collect();
+    assert_eq!(kept, vec![(false, false), (true, false), (true, false), 
+                          (false, true), (false, true), (true, true)]);
+}
@@ -85,3 +85,424 @@ fn full_outer_fused_inv() {
     assert_eq!(it.next(), Some(Left(4)));
     assert_eq!(it.next(), None);
 }
+
+#[test]
+fn inner_key_tuples() {
+    let a = vec![(0, 'a'), (1, 'b'), (2, 'c')];
+    let b = vec![(1, "x"), (2, "y"), (3, "z")];
+    let mut it = a.into_iter().merge_join_inner(b);
+    assert_eq!(it.next(), Some(((1, 'b'), (1, "x"))));
+    assert_eq!(it.next(), Some(((2, 'c'), (2, "y"))));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_into_parts() {
+    let a = 0..6;
+    let b = vec![1, 3, 5];
+    let mut it = a.merge_join_inner_by(b, |x, y| Ord::cmp(&x, &y));
+    assert_eq!(it.next(), Some((1, 1)));
+    let (l, r) = it.into_parts();
+    assert_eq!(l.collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+    assert_eq!(r.collect::<Vec<_>>(), vec![3, 5]);
+}
+
+#[test]
+fn full_outer_into_parts() {
+    let a = vec![0, 2, 4];
+    let b = vec![1, 2, 3];
+    let mut it = a.into_iter().merge_join_full_outer_by(b, |x, y| Ord::cmp(&x, &y));
+    assert_eq!(it.next(), Some(Left(0)));
+    assert_eq!(it.next(), Some(Right(1)));
+    // both peeked values are still buffered
+    let (l, r) = it.into_parts();
+    assert_eq!(l.collect::<Vec<_>>(), vec![2, 4]);
+    assert_eq!(r.collect::<Vec<_>>(), vec![2, 3]);
+}
+
+#[test]
+fn inner_groups() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (3, 'e'), (3, 'f')];
+    let b = vec![(1, 'x'), (1, 'y'), (1, 'z'), (3, 'w'), (4, 'v')];
+    let mut it = a.into_iter().merge_join_inner_groups_by(b, |x, y| Ord::cmp(&x.0, &y.0));
+    assert_eq!(it.next(), Some((vec![(1, 'b'), (1, 'c')], vec![(1, 'x'), (1, 'y'), (1, 'z')])));
+    assert_eq!(it.next(), Some((vec![(3, 'e'), (3, 'f')], vec![(3, 'w')])));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_groups_next_group() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (3, 'e'), (3, 'f')];
+    let b = vec![(1, 'x'), (1, 'y'), (1, 'z'), (3, 'w'), (4, 'v')];
+    let mut it = a.into_iter().merge_join_inner_groups_by(b, |x, y| Ord::cmp(&x.0, &y.0));
+    let mut groups = Vec::new();
+    while let Some((lvv, rvv)) = it.next_group() {
+        groups.push((lvv.to_vec(), rvv.to_vec()));
+    }
+    assert_eq!(groups, vec![
+        (vec![(1, 'b'), (1, 'c')], vec![(1, 'x'), (1, 'y'), (1, 'z')]),
+        (vec![(3, 'e'), (3, 'f')], vec![(3, 'w')]),
+    ]);
+    assert_eq!(it.next_group(), None);
+}
+
+#[test]
+fn inner_groups_next_group_reuses_buffers() {
+    // the first group is the largest, so the following ones fit into the same buffers
+    let a = vec![1, 1, 1, 2, 3, 3];
+    let b = vec![1, 1, 1, 2, 3, 3];
+    let mut it = a.into_iter().merge_join_inner_groups_by(b, Ord::cmp);
+    let (lvv, rvv) = it.next_group().unwrap();
+    assert_eq!((lvv, rvv), (&[1, 1, 1][..], &[1, 1, 1][..]));
+    let (lp, rp) = (lvv.as_ptr(), rvv.as_ptr());
+    for (l, r) in [(vec![2], vec![2]), (vec![3, 3], vec![3, 3])] {
+        let (lvv, rvv) = it.next_group().unwrap();
+        assert_eq!((lvv, rvv), (&l[..], &r[..]));
+        assert_eq!((lvv.as_ptr(), rvv.as_ptr()), (lp, rp));
+    }
+    assert_eq!(it.next_group(), None);
+}
+
+#[test]
+fn inner_groups_capped() {
+    use joinkit::{OverflowAction, GroupTooLarge};
+
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (3, 'e'), (3, 'f')];
+    let b = vec![(1, 'x'), (1, 'y'), (1, 'z'), (3, 'w'), (4, 'v')];
+    // the key 1 has 3 right items
+    let mut it = a.clone().into_iter()
+        .merge_join_inner_grouped_capped_by(b.clone(), |x, y| Ord::cmp(&x.0, &y.0), 2, OverflowAction::Skip);
+    assert_eq!(it.next(), Some(Ok((vec![(3, 'e'), (3, 'f')], vec![(3, 'w')]))));
+    assert_eq!(it.next(), None);
+
+    let mut it = a.clone().into_iter()
+        .merge_join_inner_grouped_capped_by(b.clone(), |x, y| Ord::cmp(&x.0, &y.0), 2, OverflowAction::Error);
+    assert_eq!(it.next(), Some(Err(GroupTooLarge { left: (1, 'b'), right: (1, 'x'), max: 2 })));
+    assert_eq!(it.next(), None);
+
+    // within the limit, the same as not capped
+    let capped: Vec<_> = a.clone().into_iter()
+        .merge_join_inner_grouped_capped_by(b.clone(), |x, y| Ord::cmp(&x.0, &y.0), 3, OverflowAction::Error)
+        .map(Result::unwrap)
+        .collect();
+    let groups: Vec<_> = a.into_iter().merge_join_inner_groups_by(b, |x, y| Ord::cmp(&x.0, &y.0)).collect();
+    assert_eq!(capped, groups);
+}
+
+#[test]
+fn inner_groups_capped_next_group() {
+    use joinkit::{OverflowAction, GroupTooLarge};
+
+    let a = vec![1, 1, 2, 3, 3, 3, 4];
+    let b = vec![1, 1, 2, 3, 4];
+    let mut it = a.into_iter().merge_join_inner_grouped_capped_by(b, Ord::cmp, 2, OverflowAction::Error);
+    let (lvv, rvv) = it.next_group().unwrap().unwrap();
+    assert_eq!((lvv, rvv), (&[1, 1][..], &[1, 1][..]));
+    let (lp, rp) = (lvv.as_ptr(), rvv.as_ptr());
+    let (lvv, rvv) = it.next_group().unwrap().unwrap();
+    assert_eq!((lvv, rvv), (&[2][..], &[2][..]));
+    assert_eq!((lvv.as_ptr(), rvv.as_ptr()), (lp, rp));
+    assert_eq!(it.next_group(), Some(Err(GroupTooLarge { left: 3, right: 3, max: 2 })));
+    assert_eq!(it.next_group(), None);
+}
+
+#[test]
+fn left_excl_with_context() {
+    let a = vec![1, 5, 10, 12, 30];
+    let b = vec![5, 10, 20];
+    let mut it = a.into_iter().merge_join_left_excl_with_context_by(b, |x, y| Ord::cmp(&x, &y));
+    assert_eq!(it.next(), Some((1, None, Some(5))));
+    assert_eq!(it.next(), Some((12, Some(10), Some(20))));
+    assert_eq!(it.next(), Some((30, Some(20), None)));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn records_runs() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (3, 'd'), (3, 'e'), (4, 'f')];
+    let b = vec![(1, 'x'), (1, 'y'), (2, 'z'), (3, 'w'), (5, 'v')];
+    let it = a.into_iter().merge_join_records_by(b, |l| l.0, |r| r.0);
+    let pairs: Vec<(char, char)> = it.map(|(l, r)| (l.1, r.1)).collect();
+    assert_eq!(pairs, vec![('b', 'x'), ('b', 'y'), ('c', 'x'), ('c', 'y'), ('d', 'w'), ('e', 'w')]);
+}
+
+#[test]
+fn records_empty() {
+    let a: Vec<(u32, char)> = vec![];
+    let b = vec![(1, 'x')];
+    assert_eq!(a.clone().into_iter().merge_join_records_by(b.clone(), |l| l.0, |r| r.0).next(), None);
+    assert_eq!(b.into_iter().merge_join_records_by(a, |l| l.0, |r| r.0).next(), None);
+}
+
+#[test]
+fn map_inner() {
+    let a = (0..5).map(|i| (i, i * 10));
+    let b = (3..10).map(|i| (i, i * 100));
+    let mut it = a.merge_join_map_inner_by(b, |x, y| Ord::cmp(&x.0, &y.0), |x, y| x.1 + y.1);
+    assert_eq!(it.size_hint(), (0, Some(5)));
+    assert_eq!(it.next(), Some(330));
+    assert_eq!(it.next(), Some(440));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn inner_ref_not_clone() {
+    #[derive(Debug, PartialEq)]
+    struct NoClone(u32);
+
+    let a = [NoClone(0), NoClone(1), NoClone(3)];
+    let b = [NoClone(1), NoClone(2), NoClone(3)];
+    let joined: Vec<_> = a.iter().merge_join_inner_ref_by(&b, |l, r| Ord::cmp(&l.0, &r.0)).collect();
+    assert_eq!(joined, vec![(&NoClone(1), &NoClone(1)), (&NoClone(3), &NoClone(3))]);
+    // the items are borrowed from the inputs
+    assert!(std::ptr::eq(joined[0].0, &a[1]));
+    assert!(std::ptr::eq(joined[1].1, &b[2]));
+}
+
+#[test]
+fn interval() {
+    let ranges = vec![(10u32, 19u32, "a"), (20, 20, "b"), (40, 49, "c")];
+    // inside, at either bound, between and outside of the ranges
+    let keys = vec![0, 10, 15, 19, 20, 21, 39, 40, 49, 50, 60];
+    let joined: Vec<_> = keys.into_iter()
+        .merge_join_interval_by(&ranges, |&k| k, |r| r.0, |r| r.1)
+        .map(|(k, r)| (k, r.2))
+        .collect();
+    assert_eq!(joined, vec![(10, "a"), (15, "a"), (19, "a"), (20, "b"), (40, "c"), (49, "c")]);
+
+    let none: Vec<_> = vec![1, 2].into_iter()
+        .merge_join_interval_by(&ranges[..0], |&k| k, |r| r.0, |r| r.1)
+        .collect();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn inner3() {
+    // 1 and 7 are in all three, 2 and 5 in two of them, 0, 3, 4 and 6 in one only
+    let a = [0, 1, 2, 5, 7];
+    let b = [1, 2, 3, 7];
+    let c = [1, 4, 5, 6, 7, 8];
+    let joined: Vec<_> = a.iter()
+        .merge_join_inner3_by(b.iter(), c.iter(), |x, y| Ord::cmp(&x, &y), |y, z| Ord::cmp(&y, &z))
+        .collect();
+    assert_eq!(joined, vec![(&1, &1, &1), (&7, &7, &7)]);
+
+    // the same as two chained joins
+    let chained: Vec<_> = a.iter()
+        .merge_join_inner_by(b.iter(), |x, y| Ord::cmp(&x, &y))
+        .merge_join_inner_by(c.iter(), |x, z| Ord::cmp(x.1, z))
+        .map(|((x, y), z)| (x, y, z))
+        .collect();
+    assert_eq!(joined, chained);
+
+    // no key in all three
+    let it = a.iter().merge_join_inner3_by(b.iter(), [4, 5].iter(), |x, y| Ord::cmp(&x, &y), |y, z| Ord::cmp(&y, &z));
+    assert_eq!(it.count(), 0);
+    let it = a.iter().merge_join_inner3_by(b.iter(), [0, 3].iter(), |x, y| Ord::cmp(&x, &y), |y, z| Ord::cmp(&y, &z));
+    assert_eq!(it.count(), 0);
+}
+
+#[test]
+fn inner3_into_parts() {
+    let mut it = (0..6).merge_join_inner3_by(vec![1, 3, 5], 1..4, |x, y| Ord::cmp(&x, &y), |y, z| Ord::cmp(&y, &z));
+    assert_eq!(it.size_hint(), (0, Some(3)));
+    assert_eq!(it.next(), Some((1, 1, 1)));
+    assert_eq!(it.next(), Some((3, 3, 3)));
+    assert_eq!(it.next(), None);
+    let (a, b, c) = it.into_parts();
+    assert_eq!(a.collect::<Vec<_>>(), vec![4, 5]);
+    assert_eq!(b.collect::<Vec<_>>(), vec![5]);
+    assert_eq!(c.collect::<Vec<_>>(), vec![]);
+}
+
+#[test]
+fn from_peekable_keeps_peeked() {
+    use joinkit::{MergeJoinInner, MergeJoinFullOuter};
+
+    let mut a = vec![(0, 'a'), (1, 'b'), (2, 'c')].into_iter().peekable();
+    let mut b = vec![(1, 'x'), (2, 'y'), (3, 'z')].into_iter().peekable();
+    // the peeked items are still joined
+    assert_eq!(a.peek(), Some(&(0, 'a')));
+    assert_eq!(b.peek(), Some(&(1, 'x')));
+    let joined: Vec<_> = MergeJoinFullOuter::from_peekable(a, b, |x: &(i32, char), y: &(i32, char)| Ord::cmp(&x.0, &y.0))
+        .collect();
+    assert_eq!(joined, vec![Left((0, 'a')), Both((1, 'b'), (1, 'x')), Both((2, 'c'), (2, 'y')), Right((3, 'z'))]);
+
+    // the items not consumed by the join are kept in the returned peekables
+    let mut it = MergeJoinInner::from_peekable((0..5).peekable(), vec![1, 3].into_iter().peekable(), |x, y| Ord::cmp(&x, &y));
+    assert_eq!(it.next(), Some((1, 1)));
+    let (l, r) = it.into_parts();
+    assert_eq!(l.collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(r.collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn from_peekable_inner3_and_capped() {
+    use joinkit::{MergeJoinInner3, MergeJoinInnerGroupsCapped, OverflowAction};
+
+    let mut a = (0..4).peekable();
+    assert_eq!(a.peek(), Some(&0));
+    let joined: Vec<_> = MergeJoinInner3::from_peekable(a, vec![0, 2].into_iter().peekable(), (0..3).peekable(), 
+                                                         |x, y| Ord::cmp(&x, &y), |y, z| Ord::cmp(&y, &z))
+        .collect();
+    assert_eq!(joined, vec![(0, 0, 0), (2, 2, 2)]);
+
+    let mut b = vec![1, 1, 1, 2, 3].into_iter().peekable();
+    assert_eq!(b.peek(), Some(&1));
+    let mut it = MergeJoinInnerGroupsCapped::from_peekable(vec![1, 2, 3, 4].into_iter().peekable(), b, Ord::cmp, 2, 
+                                                           OverflowAction::Skip);
+    // the run of the key 1 is skipped
+    assert_eq!(it.next(), Some(Ok((vec![2], vec![2]))));
+    let (l, r) = it.into_parts();
+    assert_eq!(l.collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!(r.collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn left_outer_groups() {
+    let a = vec![(0, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (3, 'e'), (5, 'f')];
+    let b = vec![(1, 'x'), (1, 'y'), (1, 'z'), (3, 'w'), (4, 'v'), (4, 'u')];
+    let joined: Vec<_> = a.into_iter().merge_join_left_outer_groups_by(b, |x, y| Ord::cmp(&x.0, &y.0)).collect();
+    let run1 = vec![(1, 'x'), (1, 'y'), (1, 'z')];
+    assert_eq!(joined, vec![Left((0, 'a')), 
+                            Both((1, 'b'), run1.clone()), 
+                            Both((1, 'c'), run1), 
+                            Left((2, 'd')), 
+                            Both((3, 'e'), vec![(3, 'w')]),
+                            Left((5, 'f'))]);
+
+    // the right iterator exhausted first
+    let joined: Vec<_> = (0..3).merge_join_left_outer_groups_by(vec![1, 1], |x, y| Ord::cmp(&x, &y)).collect();
+    assert_eq!(joined, vec![Left(0), Both(1, vec![1, 1]), Left(2)]);
+}
+
+#[test]
+fn full_outer_tie_break() {
+    use joinkit::TieBreak;
+    use std::cmp::Ordering;
+
+    // the version tied within the same id: (id, version)
+    let cmp = |x: &(u32, char), y: &(u32, char)| match Ord::cmp(&x.0, &y.0) {
+        Ordering::Equal if x.1 != y.1 => None,
+        o => Some(o),
+    };
+    let a = vec![(0, 'a'), (1, 'a'), (2, 'a'), (3, 'a')];
+    let b = vec![(1, 'b'), (2, 'a'), (3, 'b'), (4, 'b')];
+
+    let joined: Vec<_> = a.clone().into_iter().merge_join_full_outer_tie_break_by(b.clone(), cmp, TieBreak::LeftFirst).collect();
+    assert_eq!(joined, vec![Left((0, 'a')),
+                            Left((1, 'a')), Right((1, 'b')),
+                            Both((2, 'a'), (2, 'a')),
+                            Left((3, 'a')), Right((3, 'b')),
+                            Right((4, 'b'))]);
+
+    let joined: Vec<_> = a.clone().into_iter().merge_join_full_outer_tie_break_by(b.clone(), cmp, TieBreak::RightFirst).collect();
+    assert_eq!(joined, vec![Left((0, 'a')),
+                            Right((1, 'b')), Left((1, 'a')),
+                            Both((2, 'a'), (2, 'a')),
+                            Right((3, 'b')), Left((3, 'a')),
+                            Right((4, 'b'))]);
+
+    // the default is the order of merge_join_full_outer_by() breaking the ties as less
+    let joined: Vec<_> = a.clone().into_iter().merge_join_full_outer_tie_break_by(b.clone(), cmp, TieBreak::default()).collect();
+    let expected: Vec<_> = a.into_iter().merge_join_full_outer_by(b, |x, y| cmp(x, y).unwrap_or(Ordering::Less)).collect();
+    assert_eq!(joined, expected);
+}
+
+#[test]
+fn left_excl_by_key_same_as_peekable() {
+    let inputs = vec![
+        (vec![0, 1, 3, 5, 6], vec![1, 2, 5]),
+        (vec![0, 1], vec![]),
+        (vec![], vec![0, 1]),
+        (vec![4, 5], vec![0, 1, 4]),
+        (vec![0, 1, 2], vec![0, 1, 2]),
+    ];
+    for (a, b) in inputs {
+        let keyed: Vec<_> = a.clone().into_iter().merge_join_left_excl_by_key(b.clone(), |x| *x, |y| *y).collect();
+        let peekable: Vec<_> = a.into_iter().merge_join_left_excl_by(b, |x, y| Ord::cmp(&x, &y)).collect();
+        assert_eq!(keyed, peekable);
+    }
+
+    // the right items are dropped as soon as their key is computed
+    let r = vec![(1, String::from("X")), (2, String::from("Y"))];
+    let joined: Vec<_> = (0..4).merge_join_left_excl_by_key(r, |x| *x, |y| y.0).collect();
+    assert_eq!(joined, vec![0, 3]);
+}
+
+#[test]
+fn sorted_by_feeds_merge_join() {
+    let a = vec![(3, 'c'), (1, 'a'), (2, 'b'), (1, 'd')];
+    let b = vec![(2, 'y'), (4, 'w'), (1, 'x')];
+
+    // the sort is stable
+    let sorted: Vec<_> = a.clone().into_iter().sorted_by(|x, y| Ord::cmp(&x.0, &y.0)).collect();
+    assert_eq!(sorted, vec![(1, 'a'), (1, 'd'), (2, 'b'), (3, 'c')]);
+
+    let joined: Vec<_> = a.into_iter()
+        .sorted_by(|x, y| Ord::cmp(&x.0, &y.0))
+        .merge_join_full_outer_by(b.into_iter().sorted_by(|x, y| Ord::cmp(&x.0, &y.0)), |x, y| Ord::cmp(&x.0, &y.0))
+        .collect();
+    assert_eq!(joined, vec![Both((1, 'a'), (1, 'x')), Left((1, 'd')), Both((2, 'b'), (2, 'y')),
+                            Left((3, 'c')), Right((4, 'w'))]);
+}
+
+#[test]
+fn inner_keyed_last_key() {
+    let a = vec![("a".to_string(), 1), ("c".to_string(), 2), ("d".to_string(), 3), ("f".to_string(), 4)];
+    let b = vec![("b".to_string(), 'x'), ("c".to_string(), 'y'), ("f".to_string(), 'z')];
+    // the comparator is given only the keys
+    let mut it = a.into_iter().merge_join_inner_keyed_by(b, |l: &String, r: &String| Ord::cmp(l, r));
+    assert_eq!(it.last_key(), None);
+
+    assert_eq!(it.next(), Some((("c".to_string(), 2), ("c".to_string(), 'y'))));
+    assert_eq!(it.last_key().map(|k| k.as_str()), Some("c"));
+    // the unmatched items do not change the last key
+    assert_eq!(it.next(), Some((("f".to_string(), 4), ("f".to_string(), 'z'))));
+    assert_eq!(it.last_key().map(|k| k.as_str()), Some("f"));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.last_key().map(|k| k.as_str()), Some("f"));
+}
+
+#[test]
+fn inner_by_then() {
+    use std::cmp::Ordering;
+
+    let a = vec![(1, 'b'), (1, 'a'), (2, 'c'), (3, 'z'), (3, 'y')];
+    let b = vec![(1, 20), (1, 10), (3, 30), (4, 40)];
+    let cmp = |x: &(u32, char), y: &(u32, u32)| Ord::cmp(&x.0, &y.0);
+
+    // the pairs of a key are yielded in the input order without the secondary order
+    let plain: Vec<_> = a.clone().into_iter().merge_join_inner_by_then(b.clone(), cmp, |_, _| Ordering::Equal).collect();
+    assert_eq!(plain, vec![((1, 'b'), (1, 20)), ((1, 'b'), (1, 10)), ((1, 'a'), (1, 20)), ((1, 'a'), (1, 10)),
+                           ((3, 'z'), (3, 30)), ((3, 'y'), (3, 30))]);
+
+    // ordered by the right value first, then by the left one
+    let then = |x: &((u32, char), (u32, u32)), y: &((u32, char), (u32, u32))| {
+        Ord::cmp(&((x.1).1, (x.0).1), &((y.1).1, (y.0).1))
+    };
+    let ordered: Vec<_> = a.into_iter().merge_join_inner_by_then(b, cmp, then).collect();
+    assert_eq!(ordered, vec![((1, 'a'), (1, 10)), ((1, 'b'), (1, 10)), ((1, 'a'), (1, 20)), ((1, 'b'), (1, 20)),
+                             ((3, 'y'), (3, 30)), ((3, 'z'), (3, 30))]);
+}
+
+#[test]
+fn left_option() {
+    let a = vec![(0, 'a'), (1, 'b'), (3, 'c'), (4, 'd')];
+    let b = vec![(1, 'x'), (2, 'y'), (4, 'z')];
+    let joined: Vec<_> = a.into_iter().merge_join_left_option_by(b, |x, y| Ord::cmp(&x.0, &y.0)).collect();
+    assert_eq!(joined, vec![((0, 'a'), None), ((1, 'b'), Some((1, 'x'))), ((3, 'c'), None), ((4, 'd'), Some((4, 'z')))]);
+}
+
+#[test]
+fn inner_by_with_progress() {
+    let a: Vec<u32> = (0..1000).collect();
+    let b: Vec<u32> = (0..2000).filter(|n| n % 2 == 0).collect();
+    let mut calls = 0;
+    let mut last = 0;
+    let joined = a.into_iter()
+        .merge_join_inner_by_with_progress(b, Ord::cmp, 100, |n| {calls += 1; last = n;})
+        .count();
+    assert_eq!(joined, 500);
+    assert_eq!((calls, last), (10, 1000));
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "std")]
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Writes `content` into a temporary file unique to the calling test.
+fn input(test: &str, side: &str, content: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("joinkit-mjoin-{}-{}", test, side));
+    let mut f = File::create(&path).unwrap();
+    f.write_all(content.as_bytes()).unwrap();
+    path
+}
+
+fn mjoin(args: &[&str], left: &PathBuf, right: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mjoin"))
+        .args(args)
+        .arg(left)
+        .arg(right)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn validate_unique_pass() {
+    let l = input("validate_unique_pass", "l", "1,a\n2,b\n");
+    let r = input("validate_unique_pass", "r", "1,x\n2,y\n");
+    let out = mjoin(&["--validate-unique"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "1,a,1,x\n2,b,2,y\n");
+}
+
+#[test]
+fn validate_unique_duplicate() {
+    let l = input("validate_unique_duplicate", "l", "1,a\n2,b\n");
+    let r = input("validate_unique_duplicate", "r", "1,x\n2,y\n2,z\n");
+    let out = mjoin(&["--validate-unique"], &l, &r);
+    assert!(!out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stderr), 
+               "Error: duplicate key [S(\"2\")] in FILE2 at records 2 and 3\n");
+}
+
+#[test]
+fn trailing_newline() {
+    let l = input("trailing_newline", "l", "1,a\n2,b\n\n");
+    let r = input("trailing_newline", "r", "1,x");
+    let out = mjoin(&["-m", "left-excl"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "2,b\n");
+}
+
+#[test]
+fn keep_empty_inner() {
+    let l = input("keep_empty_inner", "l", "\n1,a\n\n");
+    let r = input("keep_empty_inner", "r", "\n1,x\n");
+    let out = mjoin(&["--keep-empty"], &l, &r);
+    assert!(out.status.success());
+    // the leading empty records have equal (empty) keys
+    assert_eq!(String::from_utf8_lossy(&out.stdout), ",\n1,a,1,x\n");
+}
+
+#[test]
+fn by_column_name() {
+    let l = input("by_column_name", "l", "order_id,amount\n1,10\n2,20\n");
+    let r = input("by_column_name", "r", "name,id\nA,1\nB,2\n");
+    let out = mjoin(&["--header", "--left-by", "order_id", "--right-by", "id"], &l, &r);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8_lossy(&out.stdout), 
+               "order_id,amount,name,id\n1,10,A,1\n2,20,B,2\n");
+}
@@ -0,0 +1,20 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Builds the library without the default `std` feature, i.e. as `no_std`.
+#[test]
+fn build_no_std() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // a separate target directory avoids waiting for the lock held by the running build
+    let target_dir = manifest_dir.join("target").join("no_std");
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()))
+        .arg("build")
+        .arg("--lib")
+        .arg("--no-default-features")
+        .arg("--manifest-path").arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir").arg(target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "serde")]
+
+extern crate joinkit;
+extern crate serde_json;
+
+use joinkit::EitherOrBoth::{self, Left, Both, Right};
+
+#[test]
+fn either_or_both_round_trip() {
+    let values: Vec<EitherOrBoth<i32, String>> = vec![Left(1), Both(2, "b".to_string()), Right("c".to_string())];
+    let json: Vec<String> = values.iter().map(|v| serde_json::to_string(v).unwrap()).collect();
+    assert_eq!(json, vec![r#"{"Left":1}"#, r#"{"Both":[2,"b"]}"#, r#"{"Right":"c"}"#]);
+    for (v, j) in values.iter().zip(&json) {
+        assert_eq!(&serde_json::from_str::<EitherOrBoth<i32, String>>(j).unwrap(), v);
+    }
+}
+
+#[test]
+fn full_outer_round_trip() {
+    use joinkit::Joinkit;
+    type Row<'a> = (i32, &'a str);
+
+    let l = vec![(0, "0;A"), (1, "1;B")];
+    let r = vec![(1, "1;X"), (2, "2;Y")];
+    let joined: Vec<_> = l.into_iter().merge_join_full_outer_by(r, |x, y| Ord::cmp(&x.0, &y.0)).collect();
+    let json = serde_json::to_string(&joined).unwrap();
+    let restored: Vec<EitherOrBoth<Row, Row>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, joined);
+}
@@ -1,5 +1,9 @@
+#![cfg(feature = "std")]
+
 extern crate joinkit;
 
+use joinkit::{JoinMode, Strategy};
+use std::io::BufRead;
 use joinkit::util::{self, DataType};
 
 #[test]
@@ -54,3 +58,406 @@ fn extract_key_multiple_ne() {
     }
 }
 
+
+#[test]
+fn skip_empty_trailing() {
+    let records = vec!["a", "", "", "b", "", ""];
+    let all: Vec<_> = util::skip_empty(records.clone().into_iter(), false).collect();
+    assert_eq!(vec!["a", "b"], all);
+    let inner: Vec<_> = util::skip_empty(records.into_iter(), true).collect();
+    assert_eq!(vec!["a", "", "", "b"], inner);
+}
+
+#[test]
+fn fields_plain() {
+    let fields: Vec<_> = util::Fields::new("a,,b,", ",").collect();
+    assert_eq!(vec!["a", "", "b", ""], fields);
+    let fields: Vec<_> = util::Fields::new("", ",").collect();
+    assert_eq!(vec![""], fields);
+    let fields: Vec<_> = util::Fields::new("a::b", "::").collect();
+    assert_eq!(vec!["a", "b"], fields);
+}
+
+#[test]
+fn fields_quoted() {
+    let fields: Vec<_> = util::Fields::quoted("\"a,b\",c,\"\"", ",", '"').collect();
+    assert_eq!(vec!["a,b", "c", ""], fields);
+}
+
+#[test]
+fn fields_escaped() {
+    let fields: Vec<_> = util::Fields::quoted("\"a \"\"b\"\", c\",d", ",", '"').collect();
+    assert_eq!(vec!["a \"\"b\"\", c", "d"], fields);
+}
+
+#[test]
+fn fields_unescaped() {
+    let mut fields = util::Fields::quoted("\"a \"\"b\"\", c\",\"d\"x,e\"\"", ",", '"');
+    assert_eq!(Some("a \"b\", c".into()), fields.next_unescaped());
+    assert_eq!(Some("d".into()), fields.next_unescaped());
+    // the doubled quotes are unescaped only inside the quoted fields
+    assert_eq!(Some("e\"\"".into()), fields.next_unescaped());
+    assert_eq!(None, fields.next_unescaped());
+}
+
+#[test]
+fn fields_raw() {
+    let mut fields = util::Fields::quoted("\"a,b\"x,c,\"d", ",", '"');
+    assert_eq!(Some("\"a,b\"x"), fields.next_raw());
+    assert_eq!(Some("c"), fields.next_raw());
+    assert_eq!(Some("\"d"), fields.next_raw());
+    assert_eq!(None, fields.next_raw());
+}
+
+#[test]
+fn fields_empty_sep() {
+    let fields: Vec<_> = util::Fields::new("a,b", "").collect();
+    assert_eq!(vec!["a,b"], fields);
+    let fields: Vec<_> = util::Fields::quoted("\"a\",b", "", '"').collect();
+    assert_eq!(vec!["a"], fields);
+}
+
+#[test]
+fn extract_key_quoted() {
+    let sep = util::Quoted { sep: ",", quote: '"' };
+    let key_idx = util::fields_to_idx(vec!["2", "3-i"]).unwrap();
+    let keys = util::try_extract_key("\"x,y\",\"a\"\"b\",\"12\"", &sep, &key_idx);
+    assert_eq!(Ok(vec![util::VarData::S("a\"b".to_owned()), util::VarData::I(12)]), keys);
+    assert_eq!(3, util::num_fields("\"x,y\",b,c", &sep));
+    assert_eq!(vec!["2"], util::header_fields("\"a,b\",\"id\"", &sep, vec!["id"]).unwrap());
+}
+
+#[test]
+fn fields_trailing() {
+    let fields: Vec<_> = util::Fields::quoted("a,\"b\",", ",", '"').collect();
+    assert_eq!(vec!["a", "b", ""], fields);
+    // an unterminated quote spans the rest of the record
+    let fields: Vec<_> = util::Fields::quoted("a,\"b,c", ",", '"').collect();
+    assert_eq!(vec!["a", "b,c"], fields);
+}
+
+#[test]
+fn try_extract_key_errors() {
+    use joinkit::util::{KeyError, VarData};
+    let key_idx = [(0, 0, DataType::I), (1, 1, DataType::U)];
+    assert_eq!(Ok(vec![VarData::I(-1), VarData::U(2)]), 
+               util::try_extract_key("-1;2", ";", &key_idx));
+
+    let e = util::try_extract_key("x;2", ";", &key_idx).unwrap_err();
+    assert_eq!(KeyError::ParseInt { idx: 0, value: "x".to_owned() }, e);
+    assert_eq!("the value 'x' of the field 1 cannot be converted into 'i64'", e.to_string());
+
+    let e = util::try_extract_key("1;-2", ";", &key_idx).unwrap_err();
+    assert_eq!(KeyError::ParseUint { idx: 1, value: "-2".to_owned() }, e);
+    assert_eq!("the value '-2' of the field 2 cannot be converted into 'u64'", e.to_string());
+
+    let e = util::try_extract_key("1", ";", &key_idx).unwrap_err();
+    assert_eq!(KeyError::IndexOutOfRange { idx: 1, nfields: 1 }, e);
+    assert_eq!("the key field 2 exceeds the number of fields (1) in the record", e.to_string());
+}
+
+#[test]
+fn try_extract_key_strict() {
+    use joinkit::util::{KeyError, VarData};
+    let key_idx = [(0, 0, DataType::I), (1, 1, DataType::U), (2, 2, DataType::S)];
+    assert_eq!(Ok(vec![VarData::I(-10), VarData::U(0), VarData::S("007".to_owned())]), 
+               util::try_extract_key_strict("-10;0;007", ";", &key_idx));
+
+    // parsed fine by the lenient extraction, but coerced
+    for &(record, idx, value) in &[("+1;2;a", 0, "+1"), ("-0;2;a", 0, "-0"), ("1;007;a", 1, "007"), ("1;+2;a", 1, "+2")] {
+        assert!(util::try_extract_key(record, ";", &key_idx).is_ok());
+        let e = util::try_extract_key_strict(record, ";", &key_idx).unwrap_err();
+        assert_eq!(KeyError::NotCanonical { idx, value: value.to_owned() }, e);
+    }
+    let e = util::try_extract_key_strict("1;007;a", ";", &key_idx).unwrap_err();
+    assert_eq!("the value '007' of the field 2 is not a canonical number", e.to_string());
+}
+
+#[test]
+fn collation() {
+    use joinkit::util::Collation;
+    use std::cmp::Ordering;
+    let nocase = Collation { fold_case: true, ..Collation::default() };
+    let noaccent = Collation { fold_accents: true, ..Collation::default() };
+    assert_eq!(Ordering::Greater, Collation::default().cmp("b", "B"));
+    assert_eq!(Ordering::Equal, nocase.cmp("Abc", "aBC"));
+    assert_eq!(Ordering::Less, nocase.cmp("abc", "ABD"));
+    // the letters sort before `_` regardless of their case, as by `LC_ALL=C sort -f`
+    assert_eq!(Ordering::Greater, nocase.cmp("_", "a"));
+    assert_eq!(Ordering::Equal, nocase.cmp("straße", "STRASSE"));
+    // the case is kept unless folded
+    assert_eq!("Creme brulee", noaccent.key("Crème brûlée"));
+    assert_eq!(Ordering::Less, noaccent.cmp("Émile", "emile"));
+    assert_eq!(Ordering::Equal, noaccent.cmp("Ångström", "Angstrom"));
+}
+
+#[test]
+fn unescape() {
+    assert_eq!("\t", util::unescape("\\t"));
+    assert_eq!("\r\n", util::unescape("\\r\\n"));
+    assert_eq!("a\0b", util::unescape("a\\0b"));
+    assert_eq!("\\t", util::unescape("\\\\t"));
+    // unknown or trailing escapes are left as is
+    assert_eq!("\\x\\", util::unescape("\\x\\"));
+    assert_eq!(",", util::unescape(","));
+}
+
+#[test]
+fn fields_to_idx_range() {
+    let idx = util::fields_to_idx(vec!["3:6"]).unwrap();
+    assert_eq!(vec![(2, 0, DataType::S), 
+                    (3, 1, DataType::S), 
+                    (4, 2, DataType::S), 
+                    (5, 3, DataType::S)], idx);
+    // a single-field range
+    assert_eq!(vec![(1, 0, DataType::S)], util::fields_to_idx(vec!["2:2"]).unwrap());
+    assert!(util::fields_to_idx(vec!["3:2"]).is_err());
+    assert!(util::fields_to_idx(vec!["3:x"]).is_err());
+}
+
+#[test]
+fn fields_to_idx_range_overlap() {
+    assert!(util::fields_to_idx(vec!["3:6", "4"]).is_err());
+    assert!(util::fields_to_idx(vec!["1:3", "3:4"]).is_err());
+    assert!(util::fields_to_idx(vec!["1:2", "3:4"]).is_ok());
+}
+
+#[test]
+fn fields_to_idx_duplicate_message() {
+    let message = |f: Vec<&str>| util::fields_to_idx(f).unwrap_err().message;
+    let expected = |field, a, b| format!("Error: the key fields must be unique, but the field {} is given by both \
+                                          '{}' and '{}'!", field, a, b);
+    // an explicit field inside a range, in either order
+    assert_eq!(message(vec!["3:6", "4"]), expected(4, "3:6", "4"));
+    assert_eq!(message(vec!["4", "3:6"]), expected(4, "4", "3:6"));
+    // the overlapping ranges
+    assert_eq!(message(vec!["1:3", "3:4"]), expected(3, "1:3", "3:4"));
+    assert_eq!(message(vec!["2:5", "1:3"]), expected(2, "2:5", "1:3"));
+    // the data types do not make the fields distinct
+    assert_eq!(message(vec!["2-u", "2-i"]), expected(2, "2-u", "2-i"));
+    assert_eq!(message(vec!["1:3-u", "2"]), expected(2, "1:3-u", "2"));
+}
+
+#[test]
+fn fields_to_idx_zero() {
+    for f in &["0", "0-u", "0:2"] {
+        let e = util::fields_to_idx(vec!["1", f]).unwrap_err();
+        assert_eq!(e.message, format!("Error: the key field '{}' is invalid, the index starts with 1!", f));
+    }
+}
+
+#[test]
+fn fields_to_idx_range_datatype() {
+    let idx = util::fields_to_idx(vec!["1-i", "2:3-u"]).unwrap();
+    assert_eq!(vec![(0, 0, DataType::I), 
+                    (1, 1, DataType::U), 
+                    (2, 2, DataType::U)], idx);
+    assert!(util::fields_to_idx(vec!["2:3-x"]).is_err());
+}
+
+#[test]
+fn write_bytes_non_utf8() {
+    use std::io::BufWriter;
+
+    let lv: &[u8] = b"1,\xff\xfe";
+    let rv: &[u8] = b"1,\xc3";
+    let mut out = Vec::new();
+    util::write_both_bytes(&mut out, lv, rv, b",", b"\n");
+    util::write_left_bytes(&mut out, lv, 2, b",", b"\n");
+    util::write_right_bytes(&mut out, rv, 2, b",", b"\n");
+    assert_eq!(out, &b"1,\xff\xfe,1,\xc3\n1,\xff\xfe,,\n,,1,\xc3\n"[..]);
+
+    // the `&str` writers delegate to the byte ones
+    let mut out = BufWriter::new(Vec::new());
+    util::write_both(&mut out, "1,a", "1,x", b";", b"\n");
+    util::write_left(&mut out, "2,b", 1, b";", b"\n");
+    assert_eq!(out.into_inner().unwrap(), b"1,a;1,x\n2,b;\n");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn fields_regex() {
+    extern crate regex;
+    use joinkit::util::Fields;
+
+    let re = regex::Regex::new(r"\s+").unwrap();
+    let fields: Vec<_> = Fields::new("  a \t b  c ", &re).collect();
+    assert_eq!(fields, vec!["a", "b", "c"]);
+    assert_eq!(util::num_fields("a", &re), 1);
+    assert_eq!(util::num_fields("   ", &re), 1);
+
+    let key_idx = util::fields_to_idx(vec!["2-u", "1"]).unwrap();
+    assert_eq!(util::try_extract_key(" x  10 ", &re, &key_idx), 
+               Ok(vec![util::VarData::U(10), util::VarData::S("x".to_owned())]));
+    // the empty matches do not split
+    let re = regex::Regex::new(r";*").unwrap();
+    let fields: Vec<_> = Fields::new("a;;b", &re).collect();
+    assert_eq!(fields, vec!["a", "b"]);
+}
+
+#[test]
+fn key_ref_same_as_owned() {
+    use util::KeyRef;
+
+    let key_idx = util::fields_to_idx(vec!["2-i", "1", "3-u"]).unwrap();
+    let records = ["b,-1,2", "a,-1,2", "a,10,0", "ab,10,0", "a,-1,3", ",-1,3"];
+    for l in &records {
+        let lo = util::try_extract_key(l, ",", &key_idx).unwrap();
+        let lr = util::try_extract_key_ref(l, ",", &key_idx).unwrap();
+        assert_eq!(lr.iter().cloned().map(KeyRef::into_owned).collect::<Vec<_>>(), lo);
+        assert_eq!(lo.iter().map(KeyRef::from).collect::<Vec<_>>(), lr);
+        for r in &records {
+            let ro = util::try_extract_key(r, ",", &key_idx).unwrap();
+            let rr = util::try_extract_key_ref(r, ",", &key_idx).unwrap();
+            assert_eq!(Ord::cmp(&lr, &rr), Ord::cmp(&lo, &ro), "{} vs {}", l, r);
+        }
+    }
+    assert_eq!(util::try_extract_key_ref("a", ",", &key_idx), 
+               Err(util::KeyError::IndexOutOfRange { idx: 1, nfields: 1 }));
+}
+
+#[test]
+fn normalized_key_same_bucket() {
+    use std::collections::HashMap;
+    use joinkit::Joinkit;
+    use joinkit::util::Normalization;
+
+    let norm = Normalization { trim: true, fold_case: true };
+    let mut map = HashMap::new();
+    map.insert(util::normalized_key("ABC ", norm), 1);
+    assert_eq!(map.get(&util::normalized_key("abc", norm)), Some(&1));
+    // not normalized by default
+    assert_ne!(util::normalized_key("ABC ", Normalization::default()), util::normalized_key("abc", Normalization::default()));
+
+    let l = vec!["ABC ", "def", "Ghi"].into_iter().map(|s| (util::normalized_key(s, norm), s));
+    let r = vec![" abc", "ghi"].into_iter().map(|s| (util::normalized_key(s, norm), s));
+    let joined: Vec<_> = l.hash_join_inner(r).collect();
+    assert_eq!(joined, vec![("ABC ", vec![" abc"]), ("Ghi", vec!["ghi"])]);
+}
+
+fn join_records(strategy: Strategy, mode: JoinMode) -> Vec<String> {
+    let l = ["1,a", "2,b", "2,c", "4,d"].iter().map(|s| s.to_string()).collect();
+    let r = ["2,x", "3,y", "4,z", "4,w"].iter().map(|s| s.to_string()).collect();
+    let opts = util::JoinOptions {
+        strategy,
+        mode,
+        key_idx_left: util::KeySpec::new(vec![(0, 0, DataType::U)]).unwrap(),
+        key_idx_right: util::KeySpec::new(vec![(0, 0, DataType::U)]).unwrap(),
+        out_field_sep: ";",
+        ..util::JoinOptions::default()
+    };
+    let mut out = util::join_records(l, r, &opts).unwrap();
+    out.sort();
+    out
+}
+
+#[test]
+fn join_records_modes() {
+    let expected: [(JoinMode, &[&str]); 6] = [
+        (JoinMode::Inner, &["2,b;2,x", "2,c;2,x", "4,d;4,w", "4,d;4,z"]),
+        (JoinMode::LeftExcl, &["1,a"]),
+        (JoinMode::LeftOuter, &["1,a;;", "2,b;2,x", "2,c;2,x", "4,d;4,w", "4,d;4,z"]),
+        (JoinMode::RightExcl, &["3,y"]),
+        (JoinMode::RightOuter, &["2,b;2,x", "2,c;2,x", "4,d;4,w", "4,d;4,z", ";;3,y"]),
+        (JoinMode::FullOuter, &["1,a;;", "2,b;2,x", "2,c;2,x", "4,d;4,w", "4,d;4,z", ";;3,y"]),
+    ];
+    for &(mode, exp) in &expected {
+        assert_eq!(join_records(Strategy::Hash, mode), exp, "hash {:?}", mode);
+        assert_eq!(join_records(Strategy::Merge, mode), exp, "merge {:?}", mode);
+    }
+}
+
+#[test]
+fn key_spec_empty() {
+    let e = util::KeySpec::new(Vec::new()).unwrap_err();
+    assert_eq!(e.message, "Error: at least one key field expected!");
+    assert!(util::KeySpec::parse(Vec::new()).is_err());
+
+    let spec = util::KeySpec::parse(vec!["3", "1-i"]).unwrap();
+    assert_eq!(&spec[..], &[(0, 1, DataType::I), (2, 0, DataType::S)]);
+}
+
+#[test]
+fn join_records_key_error() {
+    let l = vec!["1,a".to_string()];
+    let r = vec!["x,b".to_string()];
+    let opts = util::JoinOptions {
+        key_idx_right: util::KeySpec::parse(vec!["1-u"]).unwrap(),
+        ..util::JoinOptions::default()
+    };
+
+    assert!(util::join_records(l, r, &opts).is_err());
+}
+
+#[test]
+fn output_arity() {
+    assert_eq!(util::output_arity(2, 3, JoinMode::Inner), (0, 0));
+    assert_eq!(util::output_arity(2, 3, JoinMode::LeftExcl), (0, 0));
+    assert_eq!(util::output_arity(2, 3, JoinMode::LeftOuter), (0, 3));
+    assert_eq!(util::output_arity(2, 3, JoinMode::RightExcl), (0, 0));
+    assert_eq!(util::output_arity(2, 3, JoinMode::RightOuter), (2, 0));
+    assert_eq!(util::output_arity(2, 3, JoinMode::FullOuter), (2, 3));
+    // an empty input has no fields to pad
+    assert_eq!(util::output_arity(0, 3, JoinMode::FullOuter), (0, 3));
+}
+
+#[test]
+fn write_record_single_write() {
+    use std::io::{self, Write};
+
+    /// counts the calls of `write`
+    struct Counting(Vec<u8>, usize);
+
+    impl Write for Counting {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.1 += 1;
+            self.0.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    let parts: [&[u8]; 3] = [b"1,a", b"", b"1,x"];
+    // the record written part by part
+    let mut multi = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            multi.write_all(b";").unwrap();
+        }
+        multi.write_all(part).unwrap();
+    }
+    multi.write_all(b"\r\n").unwrap();
+
+    let mut out = Counting(Vec::new(), 0);
+    util::write_record(&mut out, &parts, b";", b"\r\n");
+    assert_eq!(out.0, multi);
+    assert_eq!(out.1, 1);
+
+    // the scratch buffer is cleared between the records
+    util::write_record(&mut out, &[b"2"], b";", b"\n");
+    util::write_record(&mut out, &[], b";", b"\n");
+    assert_eq!(out.0, &b"1,a;;1,x\r\n2\n\n"[..]);
+    assert_eq!(out.1, 3);
+
+    let mut out = Counting(Vec::new(), 0);
+    util::write_left_bytes(&mut out, b"1,a", 2, b",", b"\n");
+    util::write_right_bytes(&mut out, b"1,x", 2, b",", b"\n");
+    assert_eq!(out.0, &b"1,a,,\n,,1,x\n"[..]);
+    assert_eq!(out.1, 2);
+}
+
+#[test]
+fn split_records_quoted() {
+    let split = |input: &[u8], quote| -> Vec<Vec<u8>> {
+        util::split_records(input, b'\n', quote).map(Result::unwrap).collect()
+    };
+    // the escaped quotes do not end the quoted field
+    let csv = &b"1,\"a\n\"\"b\"\"\nc\",x\n2,d\n3,\"\"\n"[..];
+    assert_eq!(split(csv, Some(b'"')), vec![b"1,\"a\n\"\"b\"\"\nc\",x".to_vec(), b"2,d".to_vec(),
+                                            b"3,\"\"".to_vec()]);
+    // the same as `BufRead::split` without the quote
+    let lines: Vec<Vec<u8>> = BufRead::split(csv, b'\n').map(Result::unwrap).collect();
+    assert_eq!(split(csv, None), lines);
+    // the last record need not be terminated, an unterminated quote spans the rest of the input
+    assert_eq!(split(b"1,a\n2,\"b\n3,c", Some(b'"')), vec![b"1,a".to_vec(), b"2,\"b\n3,c".to_vec()]);
+    assert!(split(b"", Some(b'"')).is_empty());
+}